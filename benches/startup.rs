@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use gnome_workspace_shortcuts_menu::{gsettings::GSettings, keysym::Keysyms};
+
+// the keys the app reads before the first frame
+fn startup_keys() -> Vec<String> {
+    let mut keys = vec![];
+    for i in 1..=10 {
+        keys.push(format!("switch-to-workspace-{i}"));
+        keys.push(format!("move-to-workspace-{i}"));
+    }
+    keys
+}
+
+fn keysym_table(c: &mut Criterion) {
+    c.bench_function("keysym table init", |b| b.iter(Keysyms::load));
+}
+
+// needs a real gsettings on PATH, failed reads still show the spawn cost
+fn startup_read(c: &mut Criterion) {
+    let keys = startup_keys();
+    let mut group = c.benchmark_group("startup read");
+    group.sample_size(10);
+    group.bench_function("wm keybindings", |b| {
+        b.iter(|| {
+            for key in &keys {
+                let _ = GSettings::get_wm_keybinding(key);
+            }
+        })
+    });
+    group.bench_function("workspace settings", |b| {
+        b.iter(|| {
+            let _ = GSettings::get_number_of_workspaces();
+            let _ = GSettings::get_dynamic_workspaces();
+            for id in 1..10 {
+                let _ = GSettings::get_switch_to_application_keybinding(id);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, keysym_table, startup_read);
+criterion_main!(benches);
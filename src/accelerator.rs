@@ -0,0 +1,143 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Error, Result};
+
+use crate::gsettings::{format_string_array, parse_string_array};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Parse a run of modifiers like `<Shift><Super>`, in any order.
+    pub fn parse(s: &str) -> Result<Self> {
+        let (modifiers, rest) = Self::parse_prefix(s)?;
+        if !rest.is_empty() {
+            bail!("unexpected '{rest}' after modifiers");
+        }
+        Ok(modifiers)
+    }
+
+    // split the leading modifiers off an accelerator
+    fn parse_prefix(s: &str) -> Result<(Self, &str)> {
+        let mut modifiers = Self::default();
+        let mut rest = s.trim();
+        while let Some(stripped) = rest.strip_prefix('<') {
+            let Some(end) = stripped.find('>') else {
+                bail!("unterminated modifier in '{s}'");
+            };
+            let flag = match stripped[..end].to_ascii_lowercase().as_str() {
+                "shift" => &mut modifiers.shift,
+                // GTK spells Ctrl in several ways, <Primary> is what
+                // GNOME Settings writes
+                "ctrl" | "control" | "primary" => &mut modifiers.ctrl,
+                "alt" | "mod1" => &mut modifiers.alt,
+                "super" | "mod4" => &mut modifiers.super_key,
+                other => bail!("unknown modifier <{other}>"),
+            };
+            *flag = true;
+            rest = &stripped[end + 1..];
+        }
+        Ok((modifiers, rest))
+    }
+
+    // same order as the modifier combos in the UI, e.g. <Shift><Super>
+    pub fn format(&self) -> String {
+        let mut s = String::new();
+        if self.shift {
+            s.push_str("<Shift>");
+        }
+        if self.ctrl {
+            s.push_str("<Ctrl>");
+        }
+        if self.alt {
+            s.push_str("<Alt>");
+        }
+        if self.super_key {
+            s.push_str("<Super>");
+        }
+        s
+    }
+}
+
+/// A single GTK accelerator, modifiers followed by a keysym name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    pub modifiers: Modifiers,
+    pub key: String,
+}
+
+impl Accelerator {
+    pub fn new(modifiers: Modifiers, key: &str) -> Self {
+        Self {
+            modifiers,
+            key: key.into(),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        let (modifiers, key) = Modifiers::parse_prefix(s)?;
+        let key = key.trim();
+        if key.is_empty() {
+            bail!("'{s}' has no key");
+        }
+        if key.contains(['<', '>']) {
+            bail!("invalid key '{key}'");
+        }
+        Ok(Self::new(modifiers, key))
+    }
+
+    pub fn format(&self) -> String {
+        format!("{}{}", self.modifiers.format(), self.key)
+    }
+
+    /// All accelerators of a gsettings string array like
+    /// `['<Super>1', '<Ctrl><Alt>1']` or `@as []`. Empty strings, as written
+    /// by older versions of this app to disable a binding, are skipped.
+    pub fn list_from_gsettings(value: &str) -> Result<Vec<Self>> {
+        parse_string_array(value)?
+            .iter()
+            .filter(|item| !item.is_empty())
+            .map(|item| Self::parse(item))
+            .collect()
+    }
+
+    /// The first accelerator of a gsettings string array, `None` for an
+    /// empty array.
+    pub fn from_gsettings(value: &str) -> Result<Option<Self>> {
+        Ok(Self::list_from_gsettings(value)?.into_iter().next())
+    }
+
+    pub fn to_gsettings(&self) -> String {
+        Self::list_to_gsettings(std::slice::from_ref(self))
+    }
+
+    /// The gsettings string array of `accelerators`, `@as []` when there
+    /// are none.
+    pub fn list_to_gsettings(accelerators: &[Self]) -> String {
+        let items: Vec<String> = accelerators.iter().map(Self::format).collect();
+        format_string_array(&items)
+    }
+}
+
+impl FromStr for Accelerator {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.format())
+    }
+}
@@ -0,0 +1,90 @@
+//! Failures of actions that don't belong to a row, shown in the status bar
+//! at the bottom of the window. The kind leads, so it's clear at a glance
+//! whether GNOME refused a write or a file didn't make sense.
+
+use std::{collections::VecDeque, fmt};
+
+use eframe::egui::{self, Ui};
+
+// older ones are only kept for the hover text
+const MAX_ERRORS: usize = 20;
+
+pub enum AppError {
+    // a gsettings read or write failed
+    Settings(anyhow::Error),
+    // a file, share code or template couldn't be understood
+    Parse(anyhow::Error),
+    // the input was refused before anything was written
+    Validation(String),
+    // saving a file or starting another program failed
+    Io(anyhow::Error),
+    // the app's own state broke, e.g. a lock poisoned by a panic
+    Internal(String),
+}
+
+impl AppError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Settings(_) => "Settings",
+            Self::Parse(_) => "Can't read",
+            Self::Validation(_) => "Not applied",
+            Self::Io(_) => "File",
+            Self::Internal(_) => "Internal error",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Settings(e) | Self::Parse(e) | Self::Io(e) => write!(f, "{e:#}"),
+            Self::Validation(message) | Self::Internal(message) => f.write_str(message),
+        }
+    }
+}
+
+/// The latest errors, newest last. Dismissing clears all of them.
+#[derive(Default)]
+pub struct StatusBar {
+    errors: VecDeque<AppError>,
+}
+
+impl StatusBar {
+    pub fn report(&mut self, error: AppError) {
+        tracing::warn!(kind = error.kind(), "{}", error);
+        if self.errors.len() == MAX_ERRORS {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(error);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        let Some(latest) = self.errors.back() else {
+            return;
+        };
+        let mut dismiss = false;
+        ui.horizontal(|ui| {
+            let color = ui.visuals().error_fg_color;
+            ui.colored_label(color, egui::RichText::new(latest.kind()).strong());
+            ui.colored_label(color, latest.to_string());
+            let earlier = self.errors.len() - 1;
+            if earlier > 0 {
+                let all: Vec<String> = self
+                    .errors
+                    .iter()
+                    .map(|e| format!("{}: {e}", e.kind()))
+                    .collect();
+                ui.weak(format!("+{earlier} earlier"))
+                    .on_hover_text(all.join("\n"));
+            }
+            dismiss = ui.small_button("Dismiss").clicked();
+        });
+        if dismiss {
+            self.errors.clear();
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Rules of the Auto Move Windows extension, which opens applications on a
+//! fixed workspace. The extension keeps them as `app.desktop:N` strings with
+//! a 1-based workspace number.
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    backend::SettingsBackend,
+    backup,
+    gsettings::{format_string_array, parse_string_array},
+};
+
+pub const AUTO_MOVE_WINDOWS_SCHEMA: &str = "org.gnome.shell.extensions.auto-move-windows";
+pub const AUTO_MOVE_WINDOWS_UUID: &str =
+    "auto-move-windows@gnome-shell-extensions.gcampax.github.com";
+const APPLICATION_LIST: &str = "application-list";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    // desktop file id, e.g. firefox.desktop
+    pub app_id: String,
+    // 1-based like the extension
+    pub workspace: usize,
+}
+
+impl Rule {
+    pub fn new(app_id: &str, workspace: usize) -> Self {
+        Self {
+            app_id: app_id.into(),
+            workspace,
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        let Some((app_id, workspace)) = s.rsplit_once(':') else {
+            bail!("'{s}' is not app:workspace");
+        };
+        if app_id.is_empty() {
+            bail!("'{s}' has no application");
+        }
+        let workspace = workspace
+            .parse()
+            .with_context(|| format!("'{workspace}' is not a workspace number"))?;
+        if workspace == 0 {
+            bail!("workspace numbers start at 1");
+        }
+        Ok(Self::new(app_id, workspace))
+    }
+
+    pub fn format(&self) -> String {
+        format!("{}:{}", self.app_id, self.workspace)
+    }
+}
+
+pub fn rules_from_gsettings(value: &str) -> Result<Vec<Rule>> {
+    parse_string_array(value)?
+        .iter()
+        .map(|s| Rule::parse(s))
+        .collect()
+}
+
+pub fn rules_to_gsettings(rules: &[Rule]) -> String {
+    let items: Vec<String> = rules.iter().map(Rule::format).collect();
+    format_string_array(&items)
+}
+
+/// The extension's schema only exists when it's installed system-wide.
+pub fn is_available(backend: &dyn SettingsBackend) -> bool {
+    backend.has_schema(AUTO_MOVE_WINDOWS_SCHEMA)
+}
+
+pub fn read_rules(backend: &dyn SettingsBackend) -> Result<Vec<Rule>> {
+    rules_from_gsettings(&backend.get(AUTO_MOVE_WINDOWS_SCHEMA, APPLICATION_LIST)?)
+}
+
+/// Replace the whole rule list, keeping a rule per application.
+pub fn write_rules(backend: &dyn SettingsBackend, rules: &[Rule]) -> Result<()> {
+    let mut deduped: Vec<Rule> = vec![];
+    for rule in rules {
+        match deduped.iter_mut().find(|r| r.app_id == rule.app_id) {
+            Some(existing) => existing.workspace = rule.workspace,
+            None => deduped.push(rule.clone()),
+        }
+    }
+
+    let old = backend.get(AUTO_MOVE_WINDOWS_SCHEMA, APPLICATION_LIST)?;
+    backup::backup_value(AUTO_MOVE_WINDOWS_SCHEMA, APPLICATION_LIST, &old)?;
+    backend.set(
+        AUTO_MOVE_WINDOWS_SCHEMA,
+        APPLICATION_LIST,
+        &rules_to_gsettings(&deduped),
+    )
+}
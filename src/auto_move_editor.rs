@@ -0,0 +1,84 @@
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use gnome_workspace_shortcuts_menu::{
+    auto_move::{self, Rule, AUTO_MOVE_WINDOWS_UUID},
+    backend::SettingsBackend,
+    desktop_entries, extensions,
+    widget::app_picker::AppPicker,
+};
+
+/// "app → workspace" rules of the Auto Move Windows extension. Every add or
+/// remove is written right away, like the keybinding rows.
+pub struct AutoMoveEditor {
+    rules: Vec<Rule>,
+    apps: AppPicker,
+    new_workspace: usize,
+}
+
+impl AutoMoveEditor {
+    /// `None` unless the extension is enabled and its schema is installed.
+    pub fn load(backend: &dyn SettingsBackend) -> Option<Self> {
+        let enabled = extensions::is_enabled(backend, AUTO_MOVE_WINDOWS_UUID).unwrap_or(false);
+        if !enabled || !auto_move::is_available(backend) {
+            return None;
+        }
+        let rules = match auto_move::read_rules(backend) {
+            Ok(rules) => rules,
+            Err(e) => {
+                tracing::warn!("can't read auto-move-windows rules: {:#}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            rules,
+            apps: AppPicker::new(desktop_entries::installed()),
+            new_workspace: 1,
+        })
+    }
+
+    fn app_name(&self, app_id: &str) -> String {
+        self.apps
+            .app(app_id)
+            .map_or_else(|| app_id.to_string(), |a| a.name.clone())
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, backend: &dyn SettingsBackend) -> Result<()> {
+        let mut remove = None;
+        egui::Grid::new("auto_move_rules")
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, rule) in self.rules.iter().enumerate() {
+                    ui.label(self.app_name(&rule.app_id));
+                    ui.label(format!("workspace {}", rule.workspace));
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        self.apps.show(ui, "auto_move_apps");
+        let mut add = false;
+        ui.horizontal(|ui| {
+            let selected = self.apps.selected().map(|app| app.name.clone());
+            ui.label(selected.as_deref().unwrap_or("Pick an application above"));
+            ui.label("on workspace");
+            ui.add(egui::DragValue::new(&mut self.new_workspace).clamp_range(1..=36));
+            add = ui
+                .add_enabled(selected.is_some(), egui::Button::new("Add"))
+                .clicked();
+        });
+
+        let mut rules = self.rules.clone();
+        if let Some(i) = remove {
+            rules.remove(i);
+        } else if let (true, Some(app)) = (add, self.apps.selected()) {
+            rules.push(Rule::new(&app.id, self.new_workspace));
+        } else {
+            return Ok(());
+        }
+        auto_move::write_rules(backend, &rules)?;
+        self.rules = auto_move::read_rules(backend)?;
+        Ok(())
+    }
+}
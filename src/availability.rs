@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeMap,
+    sync::mpsc::{channel, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Result;
+use eframe::egui::{self, Ui};
+use gnome_workspace_shortcuts_menu::{accelerator::Accelerator, keysym::Keysyms};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Checker {
+    // GlobalShortcuts portal, on Wayland
+    #[cfg(feature = "portal")]
+    Portal,
+    // a short-lived XGrabKey, on X11
+    #[cfg(feature = "x11")]
+    X11,
+}
+
+impl Checker {
+    fn detect() -> Option<Self> {
+        #[cfg(feature = "portal")]
+        if gnome_workspace_shortcuts_menu::portal::is_wayland() {
+            return Some(Self::Portal);
+        }
+        #[cfg(feature = "x11")]
+        if gnome_workspace_shortcuts_menu::x11::is_x11() {
+            return Some(Self::X11);
+        }
+        None
+    }
+}
+
+enum Availability {
+    Free(String),
+    Taken(String),
+    Unknown(String),
+}
+
+enum ProbeState {
+    Pending,
+    Done(Availability),
+    Failed(String),
+}
+
+/// Per-row "Check availability" button asking the display server whether
+/// another client already owns the edited combination. The portal may show
+/// a dialog, so every check runs on its own thread.
+pub struct AvailabilityHints {
+    checker: Option<Checker>,
+    states: BTreeMap<usize, ProbeState>,
+    sender: Sender<(usize, Result<Availability>)>,
+    receiver: Receiver<(usize, Result<Availability>)>,
+}
+
+impl Default for AvailabilityHints {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            checker: Checker::detect(),
+            states: BTreeMap::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl AvailabilityHints {
+    /// Collect finished checks, keep repainting while some are running.
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        for (k, res) in self.receiver.try_iter() {
+            let state = match res {
+                Ok(availability) => ProbeState::Done(availability),
+                Err(e) => ProbeState::Failed(format!("{:#}", e)),
+            };
+            self.states.insert(k, state);
+        }
+        if self
+            .states
+            .values()
+            .any(|s| matches!(s, ProbeState::Pending))
+        {
+            ctx.request_repaint_after(Duration::from_millis(200));
+        }
+    }
+
+    /// Forget the result for a row whose combination changed.
+    pub fn reset(&mut self, k: usize) {
+        self.states.remove(&k);
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, k: usize, gsettings_value: &str, keysyms: &Keysyms) {
+        let Some(checker) = self.checker else {
+            return;
+        };
+        let accelerator = Accelerator::from_gsettings(gsettings_value)
+            .ok()
+            .flatten()
+            .filter(|a| !a.key.is_empty());
+        let pending = matches!(self.states.get(&k), Some(ProbeState::Pending));
+
+        ui.horizontal(|ui| {
+            let check = ui
+                .add_enabled(
+                    accelerator.is_some() && !pending,
+                    egui::Button::new("Check availability").small(),
+                )
+                .on_hover_text(
+                    "Ask the display server whether another client owns this combination",
+                );
+            if let (true, Some(accelerator)) = (check.clicked(), accelerator) {
+                self.start(checker, k, accelerator, keysyms);
+            }
+
+            match self.states.get(&k) {
+                None => {}
+                Some(ProbeState::Pending) => {
+                    ui.spinner();
+                }
+                Some(ProbeState::Done(Availability::Free(s))) => {
+                    ui.label(s);
+                }
+                Some(ProbeState::Done(Availability::Taken(s))) => {
+                    ui.colored_label(ui.visuals().warn_fg_color, s);
+                }
+                Some(ProbeState::Done(Availability::Unknown(s))) => {
+                    ui.weak(s);
+                }
+                Some(ProbeState::Failed(e)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+            }
+        });
+    }
+
+    #[allow(unused_variables)]
+    fn start(&mut self, checker: Checker, k: usize, accelerator: Accelerator, keysyms: &Keysyms) {
+        let probe: Box<dyn FnOnce() -> Result<Availability> + Send> = match checker {
+            #[cfg(feature = "portal")]
+            Checker::Portal => Box::new(move || {
+                use gnome_workspace_shortcuts_menu::portal::{self, ProbeResult};
+                Ok(match portal::probe(&accelerator)? {
+                    ProbeResult::Free(trigger) => Availability::Free(format!("free ({trigger})")),
+                    ProbeResult::Reserved => {
+                        Availability::Taken("reserved by the compositor".into())
+                    }
+                    ProbeResult::Cancelled => Availability::Unknown("check cancelled".into()),
+                    ProbeResult::Unavailable(reason) => Availability::Unknown(reason),
+                })
+            }),
+            #[cfg(feature = "x11")]
+            Checker::X11 => {
+                use gnome_workspace_shortcuts_menu::x11::{self, GrabResult};
+                let Some(keysym) = x11::keysym_value(keysyms, &accelerator.key) else {
+                    let reason = format!("no keysym known for {}", accelerator.key);
+                    self.states
+                        .insert(k, ProbeState::Done(Availability::Unknown(reason)));
+                    return;
+                };
+                let modifiers = accelerator.modifiers;
+                Box::new(move || {
+                    Ok(match x11::probe(modifiers, keysym)? {
+                        GrabResult::Free => Availability::Free("free".into()),
+                        GrabResult::Grabbed => {
+                            Availability::Taken("grabbed by another client".into())
+                        }
+                    })
+                })
+            }
+        };
+
+        self.states.insert(k, ProbeState::Pending);
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let _ = sender.send((k, probe()));
+        });
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::gsettings::{same_value, GSettings, EMPTY_KEYBINDING};
+
+// how long a read-back may lag behind a write, 250ms in total
+const CONFIRM_ATTEMPTS: usize = 5;
+const CONFIRM_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Where settings are read from and written to. Values use the GVariant
+/// text format, the same strings `gsettings get` prints. Backends are
+/// shared with the settings worker thread.
+pub trait SettingsBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn get(&self, schema: &str, key: &str) -> Result<String>;
+
+    fn set(&self, schema: &str, key: &str, value: &str) -> Result<()>;
+
+    fn list_keys(&self, schema: &str) -> Result<Vec<String>>;
+
+    /// The value the key has after `gsettings reset`.
+    fn default_value(&self, schema: &str, key: &str) -> Result<String>;
+
+    /// What the schema says the key is for.
+    fn describe(&self, schema: &str, key: &str) -> Result<String>;
+
+    /// False for keys locked by an administrator, backends without locks
+    /// have every key writable.
+    fn is_writable(&self, _schema: &str, _key: &str) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// (key, value) of every key of the schema.
+    fn list_values(&self, schema: &str) -> Result<Vec<(String, String)>> {
+        self.list_keys(schema)?
+            .into_iter()
+            .map(|key| {
+                let value = self.get(schema, &key)?.trim().to_string();
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    /// Whether the schema is installed, older or trimmed-down GNOME setups
+    /// may lack some of them.
+    fn has_schema(&self, schema: &str) -> bool {
+        self.list_keys(schema).is_ok()
+    }
+
+    /// Write a value and poll until it reads back. A locked key or a dead
+    /// dconf-service accept the write without storing anything.
+    fn set_confirmed(&self, schema: &str, key: &str, value: &str) -> Result<()> {
+        self.set(schema, key, value)?;
+        let mut current = self.get(schema, key)?;
+        for _ in 1..CONFIRM_ATTEMPTS {
+            if same_value(&current, value) {
+                return Ok(());
+            }
+            thread::sleep(CONFIRM_INTERVAL);
+            current = self.get(schema, key)?;
+        }
+        if !same_value(&current, value) {
+            bail!(
+                "{schema} {key} is still {} after writing, the key may be locked \
+                 or dconf-service not running",
+                current.trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Write an empty array, confirmed like any other write.
+    fn unbind(&self, schema: &str, key: &str) -> Result<()> {
+        self.set_confirmed(schema, key, EMPTY_KEYBINDING)
+    }
+}
+
+/// The keys of `keys` an administrator locked. A key whose lock can't be
+/// read counts as writable, the write itself will tell.
+pub fn locked_keys(
+    backend: &dyn SettingsBackend,
+    keys: &[(String, String)],
+) -> Vec<(String, String)> {
+    keys.iter()
+        .filter(|(schema, key)| !backend.is_writable(schema, key).unwrap_or(true))
+        .cloned()
+        .collect()
+}
+
+impl SettingsBackend for GSettings {
+    fn name(&self) -> &'static str {
+        if cfg!(feature = "native-settings") {
+            "gio"
+        } else {
+            "gsettings"
+        }
+    }
+
+    fn get(&self, schema: &str, key: &str) -> Result<String> {
+        GSettings::get(schema, key)
+    }
+
+    fn set(&self, schema: &str, key: &str, value: &str) -> Result<()> {
+        GSettings::set(schema, key, value)
+    }
+
+    fn list_keys(&self, schema: &str) -> Result<Vec<String>> {
+        GSettings::list_keys(schema)
+    }
+
+    fn default_value(&self, schema: &str, key: &str) -> Result<String> {
+        GSettings::default_value(schema, key)
+    }
+
+    fn describe(&self, schema: &str, key: &str) -> Result<String> {
+        GSettings::describe(schema, key)
+    }
+
+    fn is_writable(&self, schema: &str, key: &str) -> Result<bool> {
+        GSettings::is_writable(schema, key)
+    }
+
+    // in-process reads are cheap enough to do one by one
+    #[cfg(not(feature = "native-settings"))]
+    fn list_values(&self, schema: &str) -> Result<Vec<(String, String)>> {
+        GSettings::list_recursively(schema)
+    }
+}
+
+/// Reads from `inner` and refuses every write, for sessions where writing
+/// wouldn't reach a running GNOME Shell.
+pub struct ReadOnly {
+    pub inner: Box<dyn SettingsBackend>,
+    // why writes are refused, shown with every refused write
+    pub reason: String,
+}
+
+impl SettingsBackend for ReadOnly {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn get(&self, schema: &str, key: &str) -> Result<String> {
+        self.inner.get(schema, key)
+    }
+
+    fn set(&self, schema: &str, key: &str, _value: &str) -> Result<()> {
+        bail!("not writing {schema} {key}: {}", self.reason)
+    }
+
+    fn list_keys(&self, schema: &str) -> Result<Vec<String>> {
+        self.inner.list_keys(schema)
+    }
+
+    fn default_value(&self, schema: &str, key: &str) -> Result<String> {
+        self.inner.default_value(schema, key)
+    }
+
+    fn describe(&self, schema: &str, key: &str) -> Result<String> {
+        self.inner.describe(schema, key)
+    }
+
+    // locks are the administrator's, the refused writes are this app's
+    fn is_writable(&self, schema: &str, key: &str) -> Result<bool> {
+        self.inner.is_writable(schema, key)
+    }
+
+    fn list_values(&self, schema: &str) -> Result<Vec<(String, String)>> {
+        self.inner.list_values(schema)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingWrite {
+    pub schema: String,
+    pub key: String,
+    pub value: String,
+}
+
+// shared between the backend collecting them and the panel listing them
+pub type PendingWrites = Arc<Mutex<Vec<PendingWrite>>>;
+
+/// Reads from `inner` and keeps every write back in `writes`, for reviewing
+/// what a change would write before committing it. Reads see the kept
+/// back values, so the rest of the app behaves as if they were written.
+pub struct Recording {
+    pub inner: Box<dyn SettingsBackend>,
+    pub writes: PendingWrites,
+}
+
+impl Recording {
+    fn writes(&self) -> Result<MutexGuard<'_, Vec<PendingWrite>>> {
+        self.writes
+            .lock()
+            .map_err(|_| anyhow!("the kept back writes were lost, a write panicked"))
+    }
+
+    fn pending(&self, schema: &str, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .writes()?
+            .iter()
+            .rev()
+            .find(|w| w.schema == schema && w.key == key)
+            .map(|w| w.value.clone()))
+    }
+}
+
+impl SettingsBackend for Recording {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn get(&self, schema: &str, key: &str) -> Result<String> {
+        match self.pending(schema, key)? {
+            Some(value) => Ok(value),
+            None => self.inner.get(schema, key),
+        }
+    }
+
+    fn set(&self, schema: &str, key: &str, value: &str) -> Result<()> {
+        self.writes()?.push(PendingWrite {
+            schema: schema.into(),
+            key: key.into(),
+            value: value.trim().into(),
+        });
+        Ok(())
+    }
+
+    fn list_keys(&self, schema: &str) -> Result<Vec<String>> {
+        self.inner.list_keys(schema)
+    }
+
+    fn default_value(&self, schema: &str, key: &str) -> Result<String> {
+        self.inner.default_value(schema, key)
+    }
+
+    fn describe(&self, schema: &str, key: &str) -> Result<String> {
+        self.inner.describe(schema, key)
+    }
+
+    fn is_writable(&self, schema: &str, key: &str) -> Result<bool> {
+        self.inner.is_writable(schema, key)
+    }
+
+    fn list_values(&self, schema: &str) -> Result<Vec<(String, String)>> {
+        let values = self.inner.list_values(schema)?;
+        values
+            .into_iter()
+            .map(|(key, value)| {
+                let value = self.pending(schema, &key)?.unwrap_or(value);
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+/// The last write of every key, in the order the keys were first written,
+/// for committing kept back writes as one transaction.
+pub fn latest_writes(writes: &[PendingWrite]) -> Vec<PendingWrite> {
+    let mut latest: Vec<PendingWrite> = vec![];
+    for w in writes {
+        match latest
+            .iter_mut()
+            .find(|l| l.schema == w.schema && l.key == w.key)
+        {
+            Some(l) => l.value = w.value.clone(),
+            None => latest.push(w.clone()),
+        }
+    }
+    latest
+}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use crate::{backend::SettingsBackend, gsettings::same_value, transaction::Transaction, xdg};
+
+pub const BACKUP_FILE: &str = "backup.tsv";
+
+pub fn backup_file() -> PathBuf {
+    xdg::data_dir().join(BACKUP_FILE)
+}
+
+/// Every line is `schema<TAB>key<TAB>value`.
+pub fn read_backup() -> Result<Vec<(String, String, String)>> {
+    let path = backup_file();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(path)?;
+    let mut entries = vec![];
+    for line in content.lines() {
+        let s: Vec<&str> = line.splitn(3, '\t').collect();
+        if s.len() == 3 {
+            entries.push((s[0].into(), s[1].into(), s[2].into()));
+        }
+    }
+    Ok(entries)
+}
+
+/// Remember the value of a key before it gets changed. Only the first value
+/// is kept so the backup always holds what was there before this app
+/// touched the key.
+pub fn backup_value(schema: &str, key: &str, value: &str) -> Result<()> {
+    let already_saved = read_backup()?
+        .iter()
+        .any(|(s, k, _)| s == schema && k == key);
+    if already_saved {
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(xdg::create_file_path(xdg::data_dir(), BACKUP_FILE)?)?;
+    writeln!(file, "{}\t{}\t{}", schema, key, value.trim())?;
+    Ok(())
+}
+
+/// Back up every key of `keys` the backup doesn't have yet, so it holds
+/// everything the app may touch before the first write. Keys that can't be
+/// read are left out. Returns how many were added.
+pub fn snapshot(backend: &dyn SettingsBackend, keys: &[(String, String)]) -> Result<usize> {
+    let saved = read_backup()?;
+    let mut lines = String::new();
+    let mut added = 0;
+    for (schema, key) in keys {
+        if saved.iter().any(|(s, k, _)| s == schema && k == key) {
+            continue;
+        }
+        let Ok(value) = backend.get(schema, key) else {
+            continue;
+        };
+        lines.push_str(&format!("{}\t{}\t{}\n", schema, key, value.trim()));
+        added += 1;
+    }
+    if added > 0 {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(xdg::create_file_path(xdg::data_dir(), BACKUP_FILE)?)?
+            .write_all(lines.as_bytes())?;
+    }
+    Ok(added)
+}
+
+/// Stage putting back every backed up value the system no longer has.
+pub fn stage_restore(backend: &dyn SettingsBackend) -> Result<Transaction> {
+    let mut transaction = Transaction::new();
+    for (schema, key, value) in read_backup()? {
+        match backend.get(&schema, &key) {
+            Ok(current) if !same_value(&current, &value) => {
+                transaction.stage(backend, &schema, &key, &value)?
+            }
+            Ok(_) => {}
+            // e.g. an extension schema that was uninstalled since
+            Err(e) => tracing::warn!(schema, key, "not restoring: {:#}", e),
+        }
+    }
+    Ok(transaction)
+}
+
+/// Stage the schema defaults for every key of `keys` that differs from
+/// them.
+pub fn stage_defaults(
+    backend: &dyn SettingsBackend,
+    keys: &[(String, String)],
+) -> Result<Transaction> {
+    let mut transaction = Transaction::new();
+    for (schema, key) in keys {
+        let (Ok(current), Ok(default)) =
+            (backend.get(schema, key), backend.default_value(schema, key))
+        else {
+            continue;
+        };
+        if !same_value(&current, &default) {
+            transaction.stage(backend, schema, key, default.trim())?;
+        }
+    }
+    Ok(transaction)
+}
@@ -0,0 +1,162 @@
+//! A minimal libadwaita frontend on the same core library as the egui app:
+//! the workspace count and the switch/move shortcuts, for users who prefer
+//! a native GNOME look. The egui app stays the full-featured default.
+
+use adw::prelude::*;
+use anyhow::Result;
+use gnome_workspace_shortcuts_menu::{
+    accelerator::Accelerator,
+    backend::SettingsBackend,
+    gsettings::{
+        GSettings, EMPTY_KEYBINDING, MUTTER_SCHEMA, WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    transaction::Transaction,
+};
+use gtk::glib;
+
+const APP_ID: &str = "io.github.mucks.GnomeWorkspaceShortcutsMenu.Adwaita";
+
+// the shortcut schema only has keys for 12 workspaces, the egui app shows 10
+const MAX_SHORTCUT_WORKSPACES: usize = 10;
+
+fn main() -> glib::ExitCode {
+    let app = adw::Application::builder().application_id(APP_ID).build();
+    app.connect_activate(build_window);
+    app.run()
+}
+
+fn report(toasts: &adw::ToastOverlay, res: Result<()>, done: &str) {
+    let message = match res {
+        Ok(()) => done.to_string(),
+        Err(e) => format!("{:#}", e),
+    };
+    toasts.add_toast(adw::Toast::new(&message));
+}
+
+// one-change transaction, for the same backup and read-back as the egui app
+fn write(schema: &str, key: &str, value: &str) -> Result<()> {
+    let mut transaction = Transaction::new();
+    transaction.stage(&GSettings, schema, key, value)?;
+    transaction.apply(&GSettings)
+}
+
+fn build_window(app: &adw::Application) {
+    let toasts = adw::ToastOverlay::new();
+    let page = adw::PreferencesPage::new();
+    page.add(&workspaces_group(&toasts));
+
+    let count = GSettings::get_number_of_workspaces()
+        .unwrap_or(4)
+        .min(MAX_SHORTCUT_WORKSPACES);
+    for (title, prefix) in [
+        ("Switch to workspace", "switch-to-workspace"),
+        ("Move window to workspace", "move-to-workspace"),
+    ] {
+        let group = adw::PreferencesGroup::builder()
+            .title(title)
+            .description("Type a shortcut like <Super>1 and press Enter, empty disables it")
+            .build();
+        for i in 1..=count {
+            group.add(&shortcut_row(
+                &toasts,
+                &format!("{prefix}-{i}"),
+                &format!("Workspace {i}"),
+            ));
+        }
+        page.add(&group);
+    }
+    toasts.set_child(Some(&page));
+    toasts.set_vexpand(true);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    content.append(&adw::HeaderBar::new());
+    content.append(&toasts);
+
+    adw::ApplicationWindow::builder()
+        .application(app)
+        .title("Workspace Shortcuts")
+        .default_width(560)
+        .default_height(720)
+        .content(&content)
+        .build()
+        .present();
+}
+
+fn workspaces_group(toasts: &adw::ToastOverlay) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder().title("Workspaces").build();
+
+    let dynamic = gtk::Switch::builder()
+        .valign(gtk::Align::Center)
+        .active(GSettings::get_dynamic_workspaces().unwrap_or(false))
+        .build();
+    let dynamic_row = adw::ActionRow::builder()
+        .title("Dynamic workspaces")
+        .subtitle("Add and remove workspaces as needed")
+        .activatable_widget(&dynamic)
+        .build();
+    dynamic_row.add_suffix(&dynamic);
+
+    let count = gtk::SpinButton::with_range(1.0, 36.0, 1.0);
+    count.set_valign(gtk::Align::Center);
+    count.set_value(GSettings::get_number_of_workspaces().unwrap_or(4) as f64);
+    // the static count is ignored while dynamic workspaces are on
+    count.set_sensitive(!dynamic.is_active());
+    let count_row = adw::ActionRow::builder()
+        .title("Number of workspaces")
+        .subtitle("Reopen the window for the shortcuts of new workspaces")
+        .build();
+    count_row.add_suffix(&count);
+
+    {
+        let toasts = toasts.clone();
+        let count = count.clone();
+        dynamic.connect_active_notify(move |switch| {
+            let enabled = switch.is_active();
+            count.set_sensitive(!enabled);
+            let res = write(MUTTER_SCHEMA, "dynamic-workspaces", &enabled.to_string());
+            report(&toasts, res, "Dynamic workspaces changed");
+        });
+    }
+    {
+        let toasts = toasts.clone();
+        count.connect_value_changed(move |spin| {
+            let res = write(
+                WM_PREFERENCES_SCHEMA,
+                "num-workspaces",
+                &spin.value_as_int().to_string(),
+            );
+            report(&toasts, res, "Number of workspaces changed");
+        });
+    }
+
+    group.add(&dynamic_row);
+    group.add(&count_row);
+    group
+}
+
+fn shortcut_row(toasts: &adw::ToastOverlay, key: &str, title: &str) -> adw::ActionRow {
+    let current = SettingsBackend::get(&GSettings, WM_KEYBINDINGS_SCHEMA, key)
+        .ok()
+        .and_then(|v| Accelerator::from_gsettings(&v).ok().flatten());
+    let entry = gtk::Entry::builder()
+        .text(current.map(|a| a.format()).unwrap_or_default())
+        .placeholder_text("Disabled")
+        .valign(gtk::Align::Center)
+        .build();
+    let row = adw::ActionRow::builder().title(title).subtitle(key).build();
+    row.add_suffix(&entry);
+
+    let toasts = toasts.clone();
+    let key = key.to_string();
+    entry.connect_activate(move |entry| {
+        let text = entry.text();
+        let res = if text.trim().is_empty() {
+            write(WM_KEYBINDINGS_SCHEMA, &key, EMPTY_KEYBINDING)
+        } else {
+            Accelerator::parse(text.trim())
+                .and_then(|a| write(WM_KEYBINDINGS_SCHEMA, &key, &a.to_gsettings()))
+        };
+        report(&toasts, res, &format!("{key} saved"));
+    });
+    row
+}
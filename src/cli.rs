@@ -0,0 +1,374 @@
+//! Command line modes that run without opening a window.
+
+use std::{path::PathBuf, time::Instant};
+
+use anyhow::{bail, Context, Result};
+use gnome_workspace_shortcuts_menu::{
+    accelerator::{Accelerator, Modifiers},
+    drift, enforce, environment, export,
+    gsettings::{GSettings, WM_KEYBINDINGS_SCHEMA},
+    macros::{Macro, Operation},
+    model,
+    preferences::Preferences,
+    profile::Profile,
+    shortcut_dump,
+    watch::Watcher,
+};
+
+const USAGE: &str = "usage: gnome-workspace-shortcuts-menu [--check [PROFILE]]
+       gnome-workspace-shortcuts-menu --print-config
+       gnome-workspace-shortcuts-menu --enforce [PROFILE]
+       gnome-workspace-shortcuts-menu --replay MACRO
+       gnome-workspace-shortcuts-menu --export FORMAT
+       gnome-workspace-shortcuts-menu list
+       gnome-workspace-shortcuts-menu dump --format json
+       gnome-workspace-shortcuts-menu set-workspace N|last [--move] [--mod MODIFIER]... --key KEY
+       gnome-workspace-shortcuts-menu set-count N
+       gnome-workspace-shortcuts-menu disable-app-shortcuts
+       gnome-workspace-shortcuts-menu [--safe-mode] [--section SECTION] [--focus KEY]
+
+--force writes even over SSH, without a graphical session or outside GNOME";
+
+/// Where the GUI should scroll to once it's open, for deep links from
+/// other tools.
+#[derive(Debug, Clone, Default)]
+pub struct Jump {
+    // id of a collapsible section, see SECTIONS in main.rs
+    pub section: Option<String>,
+    // gsettings key of a shortcut row
+    pub focus: Option<String>,
+}
+
+pub enum Mode {
+    // safe mode opens the window without touching gsettings, for setups
+    // where reading the settings at startup crashes or hangs
+    // force skips the session check that turns writes off
+    Gui {
+        jump: Jump,
+        safe_mode: bool,
+        force: bool,
+    },
+    Exit(i32),
+}
+
+/// The mode the arguments ask for. Modes other than the GUI have already
+/// run when this returns.
+pub fn run(args: &[String]) -> Mode {
+    // allowed anywhere, it applies to whatever mode runs
+    let force = args.iter().any(|a| a == "--force");
+    let args: Vec<String> = args.iter().filter(|a| *a != "--force").cloned().collect();
+    let args = args.as_slice();
+    // subcommands take the rest of the arguments
+    if let Some((command, rest)) = args.split_first() {
+        let operation = match command.as_str() {
+            "list" if rest.is_empty() => return Mode::Exit(list()),
+            "dump" => {
+                return match rest {
+                    [flag, format] if flag == "--format" && format == "json" => Mode::Exit(dump()),
+                    _ => usage_error("dump: needs --format json"),
+                }
+            }
+            "set-workspace" => set_workspace(rest),
+            "set-count" => set_count(rest),
+            "disable-app-shortcuts" if rest.is_empty() => Ok(Operation::ClearAppShortcuts),
+            "list" | "disable-app-shortcuts" => Err(anyhow::anyhow!("takes no arguments")),
+            _ => return options(args, force),
+        };
+        return match operation {
+            Ok(operation) => Mode::Exit(run_operation(operation, force)),
+            Err(e) => usage_error(&format!("{command}: {:#}", e)),
+        };
+    }
+    options(args, force)
+}
+
+fn options(args: &[String], force: bool) -> Mode {
+    let mut jump = Jump::default();
+    let mut safe_mode = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => return Mode::Exit(check(args.next().map(PathBuf::from))),
+            "--print-config" => return Mode::Exit(print_config(force)),
+            "--enforce" => return Mode::Exit(enforce(args.next().map(PathBuf::from), force)),
+            "--replay" => {
+                let Some(name) = args.next() else {
+                    return usage_error("--replay needs a macro name or file");
+                };
+                return Mode::Exit(replay(name, force));
+            }
+            "--export" => {
+                let Some(format) = args.next() else {
+                    let ids: Vec<&str> = export::EXPORTERS.iter().map(|e| e.id()).collect();
+                    return usage_error(&format!("--export needs one of {}", ids.join(", ")));
+                };
+                return Mode::Exit(export(format));
+            }
+            "--safe-mode" => safe_mode = true,
+            "--section" | "--focus" => {
+                let Some(value) = args.next() else {
+                    return usage_error(&format!("{arg} needs a value"));
+                };
+                if arg == "--focus" {
+                    jump.focus = Some(value.clone());
+                } else if crate::ui::SECTIONS.iter().any(|(id, _)| id == value) {
+                    jump.section = Some(value.clone());
+                } else {
+                    let ids: Vec<&str> = crate::ui::SECTIONS.iter().map(|(id, _)| *id).collect();
+                    return usage_error(&format!(
+                        "unknown section '{value}', expected one of {}",
+                        ids.join(", ")
+                    ));
+                }
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Mode::Exit(0);
+            }
+            other => return usage_error(&format!("unknown argument '{other}'")),
+        }
+    }
+    Mode::Gui {
+        jump,
+        safe_mode,
+        force,
+    }
+}
+
+fn usage_error(message: &str) -> Mode {
+    eprintln!("{message}\n{USAGE}");
+    Mode::Exit(2)
+}
+
+// 0 when the system matches the profile, 1 on drift, 2 when the check
+// couldn't run, so timers can tell a changed system from a broken setup
+fn check(path: Option<PathBuf>) -> i32 {
+    let Some(path) = path.or(Preferences::load().active_profile) else {
+        eprintln!("no active profile set, pass a profile file");
+        return 2;
+    };
+    match drift::check_file(&path, &GSettings) {
+        Ok(drifts) => {
+            print!("{}", drift::report(&path, &drifts));
+            i32::from(!drifts.is_empty())
+        }
+        Err(e) => {
+            eprintln!("{}: {:#}", path.display(), e);
+            2
+        }
+    }
+}
+
+// what the GUI would start with: preferences, active profile, backend and
+// the environment the write guard looks at
+fn print_config(force: bool) -> i32 {
+    let preferences = Preferences::load();
+    println!("preferences: {}", Preferences::path().display());
+    match serde_json::to_string_pretty(&preferences) {
+        Ok(json) => println!("{json}"),
+        Err(e) => println!("<unprintable: {:#}>", e),
+    }
+    match &preferences.active_profile {
+        Some(path) => match Profile::load(path) {
+            Ok(profile) => println!(
+                "active profile: {} ({} values)",
+                path.display(),
+                profile.values.len()
+            ),
+            Err(e) => println!("active profile: {} ({:#})", path.display(), e),
+        },
+        None => println!("active profile: none"),
+    }
+    let writes = match environment::write_guard_reason() {
+        None => "allowed".to_string(),
+        Some(_) if force => "allowed, forced".to_string(),
+        Some(reason) => format!("refused, {reason}"),
+    };
+    println!("writes: {writes}");
+    print!("{}", environment::Environment::detect(&GSettings));
+    0
+}
+
+// runs until the monitors die, corrections go to stderr, which is the
+// journal when run as a systemd user service
+fn enforce(path: Option<PathBuf>, force: bool) -> i32 {
+    if refuse_writes(force) {
+        return 2;
+    }
+    let Some(path) = path.or(Preferences::load().active_profile) else {
+        eprintln!("no active profile set, pass a profile file");
+        return 2;
+    };
+    let profile = match Profile::load(&path) {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("{}: {:#}", path.display(), e);
+            return 2;
+        }
+    };
+    let mut schemas: Vec<&str> = profile.values.iter().map(|v| v.schema.as_str()).collect();
+    schemas.sort_unstable();
+    schemas.dedup();
+    let started = Watcher::start(&schemas, || {});
+    let watcher = match started {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return 2;
+        }
+    };
+    eprintln!("enforcing {}", path.display());
+    let mut enforcer = enforce::Enforcer::new(profile, enforce::COOLDOWN);
+    // a check at startup, then one per batch of changes
+    loop {
+        let corrections = enforcer.correct(&GSettings, Instant::now());
+        for correction in &corrections {
+            eprintln!("{}", enforce::describe(correction));
+        }
+        if let Err(e) = enforce::notify(&corrections) {
+            eprintln!("{:#}", e);
+        }
+        if watcher.changes.recv().is_err() {
+            eprintln!("gsettings monitor stopped");
+            return 2;
+        }
+        while watcher.changes.try_recv().is_ok() {}
+    }
+}
+
+// the reason writes would go astray, printed with how to write anyway
+fn refuse_writes(force: bool) -> bool {
+    if force {
+        return false;
+    }
+    match environment::write_guard_reason() {
+        Some(reason) => {
+            eprintln!("not writing, {reason}; pass --force to write anyway");
+            true
+        }
+        None => false,
+    }
+}
+
+fn replay(name_or_path: &str, force: bool) -> i32 {
+    if refuse_writes(force) {
+        return 2;
+    }
+    let res = Macro::find(name_or_path).and_then(|m| m.replay(&GSettings));
+    match res {
+        Ok(replay) => {
+            for step in &replay.ran {
+                println!("ran: {step}");
+            }
+            for step in &replay.skipped {
+                println!("skipped: {step}");
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{name_or_path}: {:#}", e);
+            1
+        }
+    }
+}
+
+// the current settings on stdout, for redirecting into a dotfiles repo
+fn export(format: &str) -> i32 {
+    let res = export::find(format).and_then(|e| e.export(&Profile::capture(&GSettings)));
+    match res {
+        Ok(text) => {
+            print!("{text}");
+            0
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            2
+        }
+    }
+}
+
+// every managed value as `schema key value`, like `gsettings list-recursively`
+fn list() -> i32 {
+    for v in Profile::capture(&GSettings).values {
+        println!("{} {} {}", v.schema, v.key, v.value);
+    }
+    0
+}
+
+// every shortcut of every schema, not only the managed ones
+fn dump() -> i32 {
+    match shortcut_dump::to_json(&shortcut_dump::collect(&GSettings)) {
+        Ok(json) => {
+            println!("{json}");
+            0
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            2
+        }
+    }
+}
+
+// set-workspace 3 --mod super --key 3
+fn set_workspace(args: &[String]) -> Result<Operation> {
+    let mut args = args.iter();
+    let target = args.next().context("needs a workspace number or last")?;
+    if target != "last" && !matches!(target.parse::<usize>(), Ok(1..=10)) {
+        bail!("'{target}' is not a workspace from 1 to 10 or last");
+    }
+    let mut action = "switch-to";
+    let mut modifiers = Modifiers::default();
+    let mut key = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--move" => action = "move-to",
+            "--mod" => {
+                let name = args.next().context("--mod needs a modifier")?;
+                let parsed = Modifiers::parse(&format!("<{name}>"))?;
+                modifiers = Modifiers {
+                    shift: modifiers.shift || parsed.shift,
+                    ctrl: modifiers.ctrl || parsed.ctrl,
+                    alt: modifiers.alt || parsed.alt,
+                    super_key: modifiers.super_key || parsed.super_key,
+                };
+            }
+            "--key" => key = Some(args.next().context("--key needs a key")?),
+            other => bail!("unknown argument '{other}'"),
+        }
+    }
+    let key = key.context("needs --key")?;
+    let accelerator = Accelerator::parse(&format!("{}{key}", modifiers.format()))?;
+    Ok(Operation::Set {
+        schema: WM_KEYBINDINGS_SCHEMA.into(),
+        key: format!("{action}-workspace-{target}"),
+        value: accelerator.to_gsettings(),
+    })
+}
+
+fn set_count(args: &[String]) -> Result<Operation> {
+    let [count] = args else {
+        bail!("needs exactly one number");
+    };
+    let count = model::parse_number_of_workspaces(count).map_err(anyhow::Error::msg)?;
+    Ok(Operation::SetWorkspaceCount { count })
+}
+
+// run like a one-step macro, so old values are backed up the same way
+fn run_operation(operation: Operation, force: bool) -> i32 {
+    if refuse_writes(force) {
+        return 2;
+    }
+    let description = operation.describe();
+    let mut m = Macro::new("command line");
+    m.record(operation);
+    match m.replay(&GSettings) {
+        Ok(_) => {
+            println!("{description}");
+            0
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            1
+        }
+    }
+}
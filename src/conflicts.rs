@@ -0,0 +1,101 @@
+//! Which keys share an accelerator, across every schema GNOME reads
+//! keybindings from.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    accelerator::Accelerator,
+    backend::SettingsBackend,
+    gsettings::{MUTTER_KEYBINDINGS_SCHEMA, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA},
+    transaction::Transaction,
+};
+
+pub const KEYBINDING_SCHEMAS: [&str; 3] = [
+    WM_KEYBINDINGS_SCHEMA,
+    SHELL_KEYBINDINGS_SCHEMA,
+    MUTTER_KEYBINDINGS_SCHEMA,
+];
+
+// (schema, key)
+pub type KeyId = (String, String);
+
+#[derive(Debug, Clone, Default)]
+pub struct ConflictIndex {
+    // (schema, key) to the accelerators bound to it
+    bindings: BTreeMap<(String, String), Vec<Accelerator>>,
+}
+
+impl ConflictIndex {
+    /// Schemas that aren't installed are left out.
+    pub fn scan(backend: &dyn SettingsBackend) -> Self {
+        let mut index = Self::default();
+        for schema in KEYBINDING_SCHEMAS {
+            match backend.list_values(schema) {
+                Ok(values) => {
+                    for (key, value) in values {
+                        index.update(schema, &key, &value);
+                    }
+                }
+                Err(e) => tracing::debug!(schema, "no conflict check: {:#}", e),
+            }
+        }
+        index
+    }
+
+    /// Take over a new value of a key. Values that aren't keybindings
+    /// are ignored.
+    pub fn update(&mut self, schema: &str, key: &str, value: &str) {
+        let accelerators = Accelerator::list_from_gsettings(value).unwrap_or_default();
+        let id = (schema.to_string(), key.to_string());
+        if accelerators.is_empty() {
+            self.bindings.remove(&id);
+        } else {
+            self.bindings.insert(id, accelerators);
+        }
+    }
+
+    /// (schema, key) of the other keys sharing an accelerator with `value`.
+    pub fn others(&self, schema: &str, key: &str, value: &str) -> Vec<(String, String)> {
+        let accelerators = Accelerator::list_from_gsettings(value).unwrap_or_default();
+        self.bindings
+            .iter()
+            .filter(|((s, k), _)| !(s == schema && k == key))
+            .filter(|(_, bound)| bound.iter().any(|a| accelerators.contains(a)))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// (schema, key) of every key bound to `accelerator`. Keysyms are
+    /// compared ignoring case, `<Shift><Super>a` and `<Shift><Super>A` are
+    /// the same keypress.
+    pub fn users(&self, accelerator: &Accelerator) -> Vec<(String, String)> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| {
+                bound.iter().any(|a| {
+                    a.modifiers == accelerator.modifiers
+                        && a.key.eq_ignore_ascii_case(&accelerator.key)
+                })
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// The keys `transaction` changes that would share an accelerator once
+    /// it's written, each with (schema, key) of the keys it would share it
+    /// with. Conflicts between keys it doesn't touch are left out.
+    pub fn introduced_by(&self, transaction: &Transaction) -> Vec<(KeyId, Vec<KeyId>)> {
+        let mut after = self.clone();
+        for c in &transaction.changes {
+            after.update(&c.schema, &c.key, &c.new_value);
+        }
+        transaction
+            .changes
+            .iter()
+            .filter_map(|c| {
+                let others = after.others(&c.schema, &c.key, &c.new_value);
+                (!others.is_empty()).then(|| ((c.schema.clone(), c.key.clone()), others))
+            })
+            .collect()
+    }
+}
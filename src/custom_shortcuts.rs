@@ -0,0 +1,152 @@
+//! The custom shortcuts set up in GNOME Settings → Keyboard, stored by
+//! gnome-settings-daemon as a list of paths into a relocatable schema.
+
+use anyhow::{Context, Result};
+
+use crate::{
+    backend::SettingsBackend,
+    gsettings::{format_string, format_string_array, parse_string, parse_string_array},
+    transaction::Transaction,
+};
+
+pub const MEDIA_KEYS_SCHEMA: &str = "org.gnome.settings-daemon.plugins.media-keys";
+pub const CUSTOM_KEYBINDING_SCHEMA: &str =
+    "org.gnome.settings-daemon.plugins.media-keys.custom-keybinding";
+// keys of every custom shortcut, all of them strings
+pub const CUSTOM_KEYBINDING_KEYS: [&str; 3] = ["name", "command", "binding"];
+// where GNOME Settings puts new custom shortcuts, as customN/ below it
+pub const CUSTOM_KEYBINDINGS_DIR: &str =
+    "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomShortcut {
+    // dconf path like /org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/
+    pub path: String,
+    pub name: String,
+    pub command: String,
+    // an accelerator, empty when disabled
+    pub binding: String,
+}
+
+/// The schema with the path that gsettings expects for a relocatable
+/// schema, `schema:path`.
+pub fn schema_at(path: &str) -> String {
+    format!("{CUSTOM_KEYBINDING_SCHEMA}:{path}")
+}
+
+impl CustomShortcut {
+    pub fn schema(&self) -> String {
+        schema_at(&self.path)
+    }
+
+    pub fn read(backend: &dyn SettingsBackend, path: &str) -> Result<Self> {
+        let schema = schema_at(path);
+        let get = |key: &str| {
+            backend
+                .get(&schema, key)
+                .and_then(|v| parse_string(&v))
+                .with_context(|| format!("can't read {key} of custom shortcut {path}"))
+        };
+        Ok(Self {
+            path: path.into(),
+            name: get("name")?,
+            command: get("command")?,
+            binding: get("binding")?,
+        })
+    }
+
+    /// A shortcut at the first customN path not in `paths`.
+    pub fn new_at_free_path(paths: &[String], name: &str, command: &str, binding: &str) -> Self {
+        let mut i = 0;
+        let path = loop {
+            let path = format!("{CUSTOM_KEYBINDINGS_DIR}custom{i}/");
+            if !paths.contains(&path) {
+                break path;
+            }
+            i += 1;
+        };
+        Self {
+            path,
+            name: name.into(),
+            command: command.into(),
+            binding: binding.into(),
+        }
+    }
+
+    /// Stage writing name, command and binding.
+    pub fn stage(
+        &self,
+        backend: &dyn SettingsBackend,
+        transaction: &mut Transaction,
+    ) -> Result<()> {
+        let schema = self.schema();
+        for (key, value) in
+            CUSTOM_KEYBINDING_KEYS
+                .iter()
+                .zip([&self.name, &self.command, &self.binding])
+        {
+            transaction.stage(backend, &schema, key, &format_string(value))?;
+        }
+        Ok(())
+    }
+
+    /// The writes adding this shortcut. Its keys come before the list, so
+    /// gnome-settings-daemon never sees a path without a command.
+    pub fn stage_add(&self, backend: &dyn SettingsBackend) -> Result<Transaction> {
+        let mut transaction = Transaction::new();
+        self.stage(backend, &mut transaction)?;
+        let mut paths = paths(backend)?;
+        paths.push(self.path.clone());
+        transaction.stage(
+            backend,
+            MEDIA_KEYS_SCHEMA,
+            "custom-keybindings",
+            &format_string_array(&paths),
+        )?;
+        Ok(transaction)
+    }
+
+    /// The writes removing this shortcut from the list and emptying its
+    /// keys, which is what the schema defaults them to. Undoing puts both back.
+    pub fn stage_remove(&self, backend: &dyn SettingsBackend) -> Result<Transaction> {
+        let mut transaction = Transaction::new();
+        let paths: Vec<String> = paths(backend)?
+            .into_iter()
+            .filter(|p| *p != self.path)
+            .collect();
+        transaction.stage(
+            backend,
+            MEDIA_KEYS_SCHEMA,
+            "custom-keybindings",
+            &format_string_array(&paths),
+        )?;
+        let emptied = Self {
+            path: self.path.clone(),
+            name: String::new(),
+            command: String::new(),
+            binding: String::new(),
+        };
+        emptied.stage(backend, &mut transaction)?;
+        Ok(transaction)
+    }
+}
+
+/// The paths listed in media-keys' custom-keybindings.
+pub fn paths(backend: &dyn SettingsBackend) -> Result<Vec<String>> {
+    parse_string_array(&backend.get(MEDIA_KEYS_SCHEMA, "custom-keybindings")?)
+}
+
+/// Every custom shortcut. One that can't be read is skipped, a stale path
+/// in the list shouldn't hide the others.
+pub fn read_all(backend: &dyn SettingsBackend) -> Result<Vec<CustomShortcut>> {
+    Ok(paths(backend)?
+        .iter()
+        .filter_map(|path| match CustomShortcut::read(backend, path) {
+            Ok(shortcut) => Some(shortcut),
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                None
+            }
+        })
+        .collect())
+}
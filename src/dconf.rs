@@ -0,0 +1,68 @@
+//! Reading settings out of a dconf database file that isn't the user's own,
+//! e.g. one copied from another machine. dconf only opens databases named in
+//! a profile, so a throwaway profile pointing at a copy of the file is
+//! created for `dconf dump`.
+
+use std::{fs, path::Path, process::Command};
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpEntry {
+    // relative to the dumped path, "/" for the path itself
+    pub group: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// Parse the keyfile format `dconf dump` prints.
+pub fn parse_dump(dump: &str) -> Result<Vec<DumpEntry>> {
+    let mut entries = vec![];
+    let mut group = None;
+    for (i, line) in dump.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            group = Some(name.to_string());
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {}: expected key=value", i + 1);
+        };
+        let Some(group) = &group else {
+            bail!("line {}: key outside of a group", i + 1);
+        };
+        entries.push(DumpEntry {
+            group: group.clone(),
+            key: key.trim().into(),
+            value: value.trim().into(),
+        });
+    }
+    Ok(entries)
+}
+
+/// `dconf dump <dir>` run against the given database file instead of the
+/// user's.
+pub fn dump_database(db_file: &Path, dir: &str) -> Result<String> {
+    let tmp = tempfile::tempdir()?;
+    let dconf_dir = tmp.path().join("config/dconf");
+    fs::create_dir_all(&dconf_dir)?;
+    fs::copy(db_file, dconf_dir.join("imported"))
+        .with_context(|| format!("can't read {}", db_file.display()))?;
+    let profile = tmp.path().join("profile");
+    fs::write(&profile, "user-db:imported\n")?;
+
+    let output = Command::new("dconf")
+        .arg("dump")
+        .arg(dir)
+        .env("DCONF_PROFILE", &profile)
+        .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+        .output()
+        .context("can't run dconf")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
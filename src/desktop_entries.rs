@@ -0,0 +1,141 @@
+//! Installed applications, from the `.desktop` files in the XDG data dirs.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{
+    backend::SettingsBackend,
+    gsettings::{parse_string_array, SHELL_SCHEMA},
+    xdg,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopEntry {
+    // desktop file id, e.g. org.gnome.Nautilus.desktop
+    pub id: String,
+    pub name: String,
+    pub exec: Option<String>,
+    pub icon: Option<String>,
+}
+
+// hicolor sizes to try, closest to the list icon first
+const ICON_SIZES: [&str; 6] = ["32x32", "48x48", "24x24", "64x64", "128x128", "256x256"];
+
+impl DesktopEntry {
+    /// The command line without field codes like `%U`, which only make
+    /// sense when launched with files.
+    pub fn command(&self) -> Option<String> {
+        let exec = self.exec.as_ref()?;
+        let args: Vec<&str> = exec
+            .split_whitespace()
+            .filter(|arg| !(arg.len() == 2 && arg.starts_with('%')))
+            .collect();
+        Some(args.join(" "))
+    }
+
+    /// A PNG for the icon from the hicolor theme or pixmaps. Themed SVG-only
+    /// icons aren't found.
+    pub fn icon_path(&self) -> Option<PathBuf> {
+        let icon = self.icon.as_ref()?;
+        let path = Path::new(icon);
+        if path.is_absolute() {
+            return path.exists().then(|| path.to_path_buf());
+        }
+        for dir in xdg::data_dirs() {
+            for size in ICON_SIZES {
+                let candidate = dir
+                    .join("icons/hicolor")
+                    .join(size)
+                    .join("apps")
+                    .join(format!("{icon}.png"));
+                if candidate.exists() {
+                    return Some(candidate);
+                }
+            }
+            let pixmap = dir.join("pixmaps").join(format!("{icon}.png"));
+            if pixmap.exists() {
+                return Some(pixmap);
+            }
+        }
+        None
+    }
+}
+
+/// Parse the `[Desktop Entry]` group. Entries that aren't applications or
+/// are hidden from menus give `None`.
+pub fn parse(id: &str, content: &str) -> Option<DesktopEntry> {
+    let mut in_entry = false;
+    let mut fields: BTreeMap<&str, &str> = BTreeMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || line.starts_with('#') {
+            continue;
+        }
+        // localized keys like Name[de] are ignored
+        if let Some((key, value)) = line.split_once('=') {
+            fields.entry(key.trim()).or_insert(value.trim());
+        }
+    }
+
+    let is_true = |key: &str| fields.get(key) == Some(&"true");
+    if fields.get("Type") != Some(&"Application") || is_true("NoDisplay") || is_true("Hidden") {
+        return None;
+    }
+    Some(DesktopEntry {
+        id: id.into(),
+        name: fields.get("Name")?.to_string(),
+        exec: fields.get("Exec").map(|s| s.to_string()),
+        icon: fields.get("Icon").map(|s| s.to_string()),
+    })
+}
+
+// subdirectories become part of the id, applications/kde/foo.desktop is
+// kde-foo.desktop
+fn scan(dir: &Path, prefix: &str, found: &mut BTreeMap<String, Option<DesktopEntry>>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            scan(&path, &format!("{prefix}{file_name}-"), found);
+        } else if file_name.ends_with(".desktop") {
+            let id = format!("{prefix}{file_name}");
+            // the first data dir wins, even if its entry is hidden
+            if found.contains_key(&id) {
+                continue;
+            }
+            let entry = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| parse(&id, &content));
+            found.insert(id, entry);
+        }
+    }
+}
+
+/// All visible applications, sorted by name.
+pub fn installed() -> Vec<DesktopEntry> {
+    let mut found = BTreeMap::new();
+    for dir in xdg::data_dirs() {
+        scan(&dir.join("applications"), "", &mut found);
+    }
+    let mut entries: Vec<DesktopEntry> = found.into_values().flatten().collect();
+    entries.sort_by_key(|e| e.name.to_lowercase());
+    entries
+}
+
+/// Desktop file ids of the apps pinned to the dash, in dash order. The Nth
+/// one is opened by switch-to-application-N.
+pub fn dash_favorites(backend: &dyn SettingsBackend) -> Result<Vec<String>> {
+    parse_string_array(&backend.get(SHELL_SCHEMA, "favorite-apps")?)
+}
@@ -0,0 +1,26 @@
+use std::{fs::File, io::Write, path::Path};
+
+use anyhow::{Context, Result};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{backend::SettingsBackend, doctor, environment::Environment, profile::Profile};
+
+/// Write a zip to attach to bug reports: the log, the current values of all
+/// managed keys, the doctor report and the environment.
+pub fn export(path: &Path, backend: &dyn SettingsBackend, log: &str) -> Result<()> {
+    let files = [
+        ("log.txt", log.to_string()),
+        ("profile.json", Profile::capture(backend).to_json()?),
+        ("doctor.txt", doctor::report(&doctor::run(backend))),
+        ("environment.txt", Environment::detect(backend).to_string()),
+    ];
+
+    let file = File::create(path).with_context(|| format!("can't create {}", path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    for (name, content) in files {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
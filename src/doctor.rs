@@ -0,0 +1,280 @@
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::Result;
+
+use crate::{
+    accelerator::{Accelerator, Modifiers},
+    backend::SettingsBackend,
+    backup,
+    gsettings::{
+        parse_string, MANAGED_SCHEMAS, MUTTER_SCHEMA, SHELL_KEYBINDINGS_SCHEMA,
+        WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    profile::Profile,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// A change that resolves a check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    DisableDynamicWorkspaces,
+    Unbind {
+        schema: String,
+        key: String,
+    },
+    Set {
+        schema: String,
+        key: String,
+        value: String,
+    },
+}
+
+impl Fix {
+    pub fn label(&self) -> String {
+        match self {
+            Fix::DisableDynamicWorkspaces => "Disable dynamic workspaces".into(),
+            Fix::Unbind { key, .. } => format!("Unbind {key}"),
+            Fix::Set { key, value, .. } => format!("Set {key} to {value}"),
+        }
+    }
+
+    /// The schema and key the fix writes.
+    pub fn target(&self) -> (&str, &str) {
+        match self {
+            Fix::DisableDynamicWorkspaces => (MUTTER_SCHEMA, "dynamic-workspaces"),
+            Fix::Unbind { schema, key } | Fix::Set { schema, key, .. } => (schema, key),
+        }
+    }
+
+    /// Old values are backed up like for any other write.
+    pub fn apply(&self, backend: &dyn SettingsBackend) -> Result<()> {
+        let (schema, key) = self.target();
+        let old = backend.get(schema, key)?;
+        backup::backup_value(schema, key, &old)?;
+        match self {
+            Fix::DisableDynamicWorkspaces => backend.set_confirmed(schema, key, "false"),
+            Fix::Unbind { .. } => backend.unbind(schema, key),
+            Fix::Set { value, .. } => backend.set_confirmed(schema, key, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub severity: Severity,
+    pub detail: String,
+    pub fix: Option<Fix>,
+}
+
+impl Check {
+    fn new(name: &str, severity: Severity, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            severity,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self.severity {
+            Severity::Ok => "ok",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "[{tag}] {}: {}", self.name, self.detail)
+    }
+}
+
+/// Look for the usual reasons a keybinding doesn't do what the user expects.
+pub fn run(backend: &dyn SettingsBackend) -> Vec<Check> {
+    let mut checks = vec![];
+    if let Err(e) = backend.get(WM_PREFERENCES_SCHEMA, "num-workspaces") {
+        // nothing else can work, spare the user a list of follow-up errors
+        return vec![Check::new(
+            "backend",
+            Severity::Error,
+            format!("{} doesn't answer: {:#}", backend.name(), e),
+        )];
+    }
+    for schema in MANAGED_SCHEMAS {
+        checks.push(if backend.has_schema(schema) {
+            Check::new("schema", Severity::Ok, format!("{schema} is installed"))
+        } else {
+            Check::new(
+                "schema",
+                Severity::Error,
+                format!("{schema} is not installed"),
+            )
+        });
+    }
+    checks.extend(check_profile(&Profile::capture(backend)));
+    checks
+}
+
+/// The checks that only need the current values.
+pub fn check_profile(profile: &Profile) -> Vec<Check> {
+    let mut checks = vec![];
+
+    if profile.get(MUTTER_SCHEMA, "dynamic-workspaces") == Some("true") {
+        checks.push(
+            Check::new(
+                "dynamic workspaces",
+                Severity::Warning,
+                "enabled, num-workspaces is ignored",
+            )
+            .with_fix(Fix::DisableDynamicWorkspaces),
+        );
+    }
+
+    // accelerator -> (schema, key) binding it
+    let mut users: BTreeMap<String, Vec<(&str, &str)>> = BTreeMap::new();
+    let mut bound: Vec<(Accelerator, &str)> = vec![];
+    for v in &profile.values {
+        if v.schema != WM_KEYBINDINGS_SCHEMA && v.schema != SHELL_KEYBINDINGS_SCHEMA {
+            continue;
+        }
+        match Accelerator::list_from_gsettings(&v.value) {
+            Ok(accelerators) => {
+                for a in accelerators {
+                    users
+                        .entry(a.format())
+                        .or_default()
+                        .push((&v.schema, &v.key));
+                    bound.push((a, &v.key));
+                }
+            }
+            Err(e) => checks.push(Check::new(
+                "parse",
+                Severity::Warning,
+                format!("{} {}: {:#}", v.schema, v.key, e),
+            )),
+        }
+    }
+    for (accelerator, keys) in &users {
+        if keys.len() > 1 {
+            // only one of the keys will react to the combination
+            let names: Vec<&str> = keys.iter().map(|(_, key)| *key).collect();
+            let mut check = Check::new(
+                "conflict",
+                Severity::Warning,
+                format!("{accelerator} is bound by {}", names.join(", ")),
+            );
+            // gnome-shell's own shortcuts win, giving them up is the usual fix
+            if let Some((schema, key)) = keys.iter().find(|(s, _)| *s == SHELL_KEYBINDINGS_SCHEMA) {
+                check = check.with_fix(Fix::Unbind {
+                    schema: schema.to_string(),
+                    key: key.to_string(),
+                });
+            }
+            checks.push(check);
+        }
+    }
+
+    checks.extend(check_prefixes(profile, &bound));
+
+    if checks.is_empty() {
+        checks.push(Check::new(
+            "keybindings",
+            Severity::Ok,
+            "no conflicts found",
+        ));
+    }
+    checks
+}
+
+// the modifier a keysym like Super_L presses
+fn modifier_of(keysym: &str) -> Option<Modifiers> {
+    let mut modifiers = Modifiers::default();
+    let flag = match keysym {
+        "Super_L" | "Super_R" => &mut modifiers.super_key,
+        "Alt_L" | "Alt_R" => &mut modifiers.alt,
+        "Control_L" | "Control_R" => &mut modifiers.ctrl,
+        "Shift_L" | "Shift_R" => &mut modifiers.shift,
+        _ => return None,
+    };
+    *flag = true;
+    Some(modifiers)
+}
+
+// A lone modifier key, the overlay key or a binding like ['Super_L'], also
+// held for chords like <Super>1. Mutter only tells them apart when the key
+// is released: a chord let go of in the wrong order runs the lone key too.
+fn check_prefixes(profile: &Profile, bound: &[(Accelerator, &str)]) -> Vec<Check> {
+    let mut prefixes: Vec<(String, String)> = bound
+        .iter()
+        .filter(|(a, _)| a.modifiers.is_empty())
+        .map(|(a, key)| (a.key.clone(), key.to_string()))
+        .collect();
+    let overlay_key = profile
+        .get(MUTTER_SCHEMA, "overlay-key")
+        .and_then(|v| parse_string(v).ok());
+    if let Some(overlay_key) = &overlay_key {
+        prefixes.push((overlay_key.clone(), "overlay-key".into()));
+    }
+
+    let mut checks = vec![];
+    for (keysym, key) in prefixes {
+        let Some(modifier) = modifier_of(&keysym) else {
+            continue;
+        };
+        let chords: Vec<&str> = bound
+            .iter()
+            .filter(|(a, _)| {
+                let m = a.modifiers;
+                (m.super_key && modifier.super_key)
+                    || (m.alt && modifier.alt)
+                    || (m.ctrl && modifier.ctrl)
+                    || (m.shift && modifier.shift)
+            })
+            .map(|(_, key)| *key)
+            .collect();
+        if chords.is_empty() {
+            continue;
+        }
+        let shown = chords.len().min(3);
+        let more = if chords.len() > shown { ", …" } else { "" };
+        let mut check = Check::new(
+            "prefix",
+            Severity::Warning,
+            format!(
+                "{keysym} alone runs {key} and is also held for {}{more}; \
+                 releasing it before the other key can run {key} as well",
+                chords[..shown].join(", ")
+            ),
+        );
+        // the other Super key keeps the overview a keypress away
+        let other = match keysym.as_str() {
+            "Super_L" => Some("Super_R"),
+            "Super_R" => Some("Super_L"),
+            _ => None,
+        };
+        if let (Some(other), "overlay-key") = (other, key.as_str()) {
+            check = check.with_fix(Fix::Set {
+                schema: MUTTER_SCHEMA.into(),
+                key: "overlay-key".into(),
+                value: format!("'{other}'"),
+            });
+        }
+        checks.push(check);
+    }
+    checks
+}
+
+pub fn report(checks: &[Check]) -> String {
+    checks.iter().map(|c| format!("{c}\n")).collect()
+}
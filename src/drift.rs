@@ -0,0 +1,59 @@
+//! Compare a saved profile with the live system, for noticing when GNOME or
+//! another tool changed keys behind the user's back.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::{backend::SettingsBackend, gsettings::same_value, profile::Profile};
+
+/// A profile value the system doesn't have, `actual` is `None` when the key
+/// can't be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub schema: String,
+    pub key: String,
+    pub expected: String,
+    pub actual: Option<String>,
+}
+
+pub fn check(profile: &Profile, backend: &dyn SettingsBackend) -> Vec<Drift> {
+    profile
+        .values
+        .iter()
+        .filter_map(|v| {
+            let actual = backend
+                .get(&v.schema, &v.key)
+                .ok()
+                .map(|a| a.trim().to_string());
+            if actual.as_deref().is_some_and(|a| same_value(a, &v.value)) {
+                return None;
+            }
+            Some(Drift {
+                schema: v.schema.clone(),
+                key: v.key.clone(),
+                expected: v.value.clone(),
+                actual,
+            })
+        })
+        .collect()
+}
+
+pub fn check_file(path: &Path, backend: &dyn SettingsBackend) -> Result<Vec<Drift>> {
+    Ok(check(&Profile::load(path)?, backend))
+}
+
+pub fn report(path: &Path, drifts: &[Drift]) -> String {
+    if drifts.is_empty() {
+        return format!("{}: no drift\n", path.display());
+    }
+    let mut report = format!("{}: {} key(s) drifted\n", path.display(), drifts.len());
+    for d in drifts {
+        let actual = d.actual.as_deref().unwrap_or("<unreadable>");
+        report.push_str(&format!(
+            "{} {}: expected {}, found {}\n",
+            d.schema, d.key, d.expected, actual
+        ));
+    }
+    report
+}
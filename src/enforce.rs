@@ -0,0 +1,98 @@
+//! Keeping the system on a profile: keys that drift are written back as
+//! soon as `gsettings monitor` reports them. Corrections are announced with
+//! a desktop notification, at most once per key and cooldown, so another
+//! tool fighting back doesn't flood the notification list.
+
+use std::{
+    collections::HashMap,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    backend::SettingsBackend,
+    drift::{self, Drift},
+    profile::Profile,
+};
+
+pub const COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    pub drift: Drift,
+    // false while an earlier correction of the key is in its cooldown
+    pub notify: bool,
+}
+
+pub struct Enforcer {
+    pub profile: Profile,
+    cooldown: Duration,
+    // (schema, key) to when its correction was last notified
+    notified: HashMap<(String, String), Instant>,
+}
+
+impl Enforcer {
+    pub fn new(profile: Profile, cooldown: Duration) -> Self {
+        Self {
+            profile,
+            cooldown,
+            notified: HashMap::new(),
+        }
+    }
+
+    /// Write back every drifted key. Keys that can't be written are logged
+    /// and left out, they'd be retried on every change otherwise.
+    pub fn correct(&mut self, backend: &dyn SettingsBackend, now: Instant) -> Vec<Correction> {
+        let mut corrections = vec![];
+        for drift in drift::check(&self.profile, backend) {
+            if let Err(e) = backend.set(&drift.schema, &drift.key, &drift.expected) {
+                tracing::warn!(drift.schema, drift.key, "can't correct: {:#}", e);
+                continue;
+            }
+            let id = (drift.schema.clone(), drift.key.clone());
+            let notify = self
+                .notified
+                .get(&id)
+                .is_none_or(|last| now.duration_since(*last) >= self.cooldown);
+            if notify {
+                self.notified.insert(id, now);
+            }
+            corrections.push(Correction { drift, notify });
+        }
+        corrections
+    }
+}
+
+/// A line for the journal, e.g. `corrected org.gnome... key: found X, wrote Y`.
+pub fn describe(correction: &Correction) -> String {
+    let d = &correction.drift;
+    let actual = d.actual.as_deref().unwrap_or("<unreadable>");
+    format!(
+        "corrected {} {}: found {}, wrote {}",
+        d.schema, d.key, actual, d.expected
+    )
+}
+
+/// One desktop notification for the corrections not in their cooldown.
+pub fn notify(corrections: &[Correction]) -> Result<()> {
+    let keys: Vec<&str> = corrections
+        .iter()
+        .filter(|c| c.notify)
+        .map(|c| c.drift.key.as_str())
+        .collect();
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let status = Command::new("notify-send")
+        .arg("--app-name=Gnome Workspace Shortcuts Menu")
+        .arg("Shortcuts restored")
+        .arg(format!("Put back {} from the profile", keys.join(", ")))
+        .status()
+        .context("can't run notify-send")?;
+    if !status.success() {
+        bail!("notify-send failed with {status}");
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::{fmt, process::Command};
+
+use crate::{backend::SettingsBackend, gsettings::MANAGED_SCHEMAS};
+
+/// What maintainers ask for first in a bug report.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub app_version: &'static str,
+    pub gnome_shell_version: Option<String>,
+    pub session_type: String,
+    pub desktop: String,
+    pub backend: &'static str,
+    // (schema, installed)
+    pub schemas: Vec<(&'static str, bool)>,
+}
+
+impl Environment {
+    pub fn detect(backend: &dyn SettingsBackend) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION"),
+            gnome_shell_version: gnome_shell_version(),
+            session_type: env_or_unknown("XDG_SESSION_TYPE"),
+            desktop: env_or_unknown("XDG_CURRENT_DESKTOP"),
+            backend: backend.name(),
+            schemas: MANAGED_SCHEMAS
+                .iter()
+                .map(|&schema| (schema, backend.has_schema(schema)))
+                .collect(),
+        }
+    }
+}
+
+/// Why settings written from here probably won't reach GNOME, None when
+/// this looks like a GNOME session. `desktop` is XDG_CURRENT_DESKTOP, a
+/// colon separated list like `ubuntu:GNOME`; `shell_running` whether
+/// GNOME Shell answers on the session bus, None when that can't be asked.
+pub fn not_gnome_reason(desktop: Option<&str>, shell_running: Option<bool>) -> Option<String> {
+    let is_gnome =
+        desktop.is_some_and(|d| d.split(':').any(|part| part.eq_ignore_ascii_case("gnome")));
+    match (is_gnome, shell_running) {
+        (true, _) | (false, Some(true)) => None,
+        (false, _) => Some(match desktop.filter(|d| !d.is_empty()) {
+            Some(desktop) => format!("the desktop is {desktop}, not GNOME"),
+            None => "no GNOME session was found".into(),
+        }),
+    }
+}
+
+/// `not_gnome_reason` for the session this runs in.
+pub fn detect_not_gnome() -> Option<String> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").ok();
+    not_gnome_reason(desktop.as_deref(), shell_running())
+}
+
+/// Why settings written from here would end up in another session or none,
+/// None for a local graphical session. `var` reads an environment
+/// variable, `runtime_bus` is whether `$XDG_RUNTIME_DIR/bus` exists, the
+/// session bus found when DBUS_SESSION_BUS_ADDRESS isn't set.
+pub fn remote_reason(var: impl Fn(&str) -> Option<String>, runtime_bus: bool) -> Option<String> {
+    let set = |name: &str| var(name).is_some_and(|v| !v.is_empty());
+    if set("SSH_CONNECTION") || set("SSH_CLIENT") || set("SSH_TTY") {
+        return Some("this runs over SSH".into());
+    }
+    if !set("DISPLAY") && !set("WAYLAND_DISPLAY") {
+        return Some("there is no graphical session".into());
+    }
+    if !set("DBUS_SESSION_BUS_ADDRESS") && !runtime_bus {
+        return Some("there is no session bus".into());
+    }
+    None
+}
+
+/// `remote_reason` for the session this runs in.
+pub fn detect_remote() -> Option<String> {
+    let runtime_bus = std::env::var_os("XDG_RUNTIME_DIR")
+        .is_some_and(|dir| std::path::Path::new(&dir).join("bus").exists());
+    remote_reason(|name| std::env::var(name).ok(), runtime_bus)
+}
+
+/// Why writes should wait for an explicit go-ahead, None when they can
+/// happen right away.
+pub fn write_guard_reason() -> Option<String> {
+    detect_remote().or_else(detect_not_gnome)
+}
+
+// GNOME Shell owns org.gnome.Shell on the session bus while it runs
+#[cfg(feature = "portal")]
+fn shell_running() -> Option<bool> {
+    let conn = zbus::blocking::Connection::session().ok()?;
+    let dbus = zbus::blocking::fdo::DBusProxy::new(&conn).ok()?;
+    let name = zbus::names::BusName::try_from("org.gnome.Shell").ok()?;
+    dbus.name_has_owner(name).ok()
+}
+
+#[cfg(not(feature = "portal"))]
+fn shell_running() -> Option<bool> {
+    None
+}
+
+fn env_or_unknown(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| "unknown".into())
+}
+
+// `gnome-shell --version` prints e.g. "GNOME Shell 45.2"
+fn gnome_shell_version() -> Option<String> {
+    let output = Command::new("gnome-shell").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let version = stdout.trim().trim_start_matches("GNOME Shell").trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "app version: {}", self.app_version)?;
+        writeln!(
+            f,
+            "GNOME Shell: {}",
+            self.gnome_shell_version.as_deref().unwrap_or("not found")
+        )?;
+        writeln!(f, "session type: {}", self.session_type)?;
+        writeln!(f, "desktop: {}", self.desktop)?;
+        writeln!(f, "backend: {}", self.backend)?;
+        for (schema, installed) in &self.schemas {
+            let state = if *installed { "installed" } else { "missing" };
+            writeln!(f, "schema {schema}: {state}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+
+use super::{dconf_dir, Exporter};
+use crate::profile::Profile;
+
+/// Tasks for the `community.general.dconf` module, values stay GVariant
+/// text like the module expects.
+pub struct Ansible;
+
+// YAML single quoted scalars only escape quotes
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+impl Exporter for Ansible {
+    fn id(&self) -> &'static str {
+        "ansible"
+    }
+
+    fn name(&self) -> &'static str {
+        "Ansible tasks"
+    }
+
+    fn extension(&self) -> &'static str {
+        "yml"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        let mut tasks = String::from("---\n");
+        for v in &profile.values {
+            tasks.push_str(&format!(
+                "- name: Set {key}\n  community.general.dconf:\n    key: {path}\n    value: {value}\n",
+                key = v.key,
+                path = quote(&format!("/{}/{}", dconf_dir(&v.schema), v.key)),
+                value = quote(&v.value),
+            ));
+        }
+        Ok(tasks)
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use super::{dconf_dir, Exporter};
+use crate::profile::Profile;
+
+/// The keyfile format of `dconf dump`, loaded with `dconf load /`.
+pub struct Dconf;
+
+impl Exporter for Dconf {
+    fn id(&self) -> &'static str {
+        "dconf"
+    }
+
+    fn name(&self) -> &'static str {
+        "dconf keyfile"
+    }
+
+    fn extension(&self) -> &'static str {
+        "ini"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        let mut groups: BTreeMap<String, Vec<(&str, &str)>> = BTreeMap::new();
+        for v in &profile.values {
+            groups
+                .entry(dconf_dir(&v.schema))
+                .or_default()
+                .push((&v.key, &v.value));
+        }
+        let mut keyfile = String::new();
+        for (dir, values) in groups {
+            keyfile.push_str(&format!("[{dir}]\n"));
+            for (key, value) in values {
+                keyfile.push_str(&format!("{key}={value}\n"));
+            }
+            keyfile.push('\n');
+        }
+        Ok(keyfile)
+    }
+}
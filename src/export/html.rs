@@ -0,0 +1,25 @@
+use anyhow::Result;
+
+use super::Exporter;
+use crate::{keyboard_map, profile::Profile};
+
+/// A standalone HTML page showing every binding on a keyboard.
+pub struct KeyboardMap;
+
+impl Exporter for KeyboardMap {
+    fn id(&self) -> &'static str {
+        "keyboard-map"
+    }
+
+    fn name(&self) -> &'static str {
+        "Keyboard map (HTML)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        Ok(keyboard_map::html("GNOME workspace shortcuts", profile))
+    }
+}
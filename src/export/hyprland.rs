@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use super::{workspace_bindings, Exporter, WorkspaceAction};
+use crate::profile::Profile;
+
+/// `bind` lines for the workspace bindings, the other GNOME actions have
+/// no Hyprland equivalent.
+pub struct Hyprland;
+
+impl Exporter for Hyprland {
+    fn id(&self) -> &'static str {
+        "hyprland"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hyprland config"
+    }
+
+    fn extension(&self) -> &'static str {
+        "conf"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        let mut config = String::new();
+        for (action, accelerator) in workspace_bindings(profile) {
+            let m = accelerator.modifiers;
+            let mods: Vec<&str> = [
+                (m.super_key, "SUPER"),
+                (m.ctrl, "CTRL"),
+                (m.alt, "ALT"),
+                (m.shift, "SHIFT"),
+            ]
+            .iter()
+            .filter(|(held, _)| *held)
+            .map(|(_, name)| *name)
+            .collect();
+            let (dispatcher, target) = match action {
+                WorkspaceAction::Switch(n) => ("workspace", n),
+                WorkspaceAction::Move(n) => ("movetoworkspace", n),
+            };
+            let target = target.map_or("previous".to_string(), |n| n.to_string());
+            config.push_str(&format!(
+                "bind = {}, {}, {dispatcher}, {target}\n",
+                mods.join(" "),
+                accelerator.key
+            ));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,105 @@
+//! A profile in the formats of other tools. Every format is one file here
+//! and an entry in [`EXPORTERS`], the Export menu and `--export` list
+//! whatever is registered.
+
+mod ansible;
+mod dconf;
+mod html;
+mod hyprland;
+mod nix;
+mod script;
+mod sway;
+
+use std::path::PathBuf;
+
+pub use script::gsettings_command;
+
+use anyhow::{bail, Result};
+
+use crate::{accelerator::Accelerator, gsettings::WM_KEYBINDINGS_SCHEMA, profile::Profile};
+
+pub trait Exporter: Sync {
+    /// Picks the format on the command line, e.g. `nix`.
+    fn id(&self) -> &'static str;
+    /// Shown in the Export menu.
+    fn name(&self) -> &'static str;
+    fn extension(&self) -> &'static str;
+    fn export(&self, profile: &Profile) -> Result<String>;
+}
+
+pub static EXPORTERS: [&dyn Exporter; 7] = [
+    &script::Script,
+    &dconf::Dconf,
+    &nix::Nix,
+    &ansible::Ansible,
+    &sway::Sway,
+    &hyprland::Hyprland,
+    &html::KeyboardMap,
+];
+
+pub fn find(id: &str) -> Result<&'static dyn Exporter> {
+    match EXPORTERS.iter().find(|e| e.id() == id) {
+        Some(exporter) => Ok(*exporter),
+        None => {
+            let ids: Vec<&str> = EXPORTERS.iter().map(|e| e.id()).collect();
+            bail!("unknown format '{id}', expected one of {}", ids.join(", "))
+        }
+    }
+}
+
+/// Where the GUI writes an export, in the home directory.
+pub fn default_path(exporter: &dyn Exporter) -> PathBuf {
+    PathBuf::from(std::env::var_os("HOME").unwrap_or_default()).join(format!(
+        "gnome-workspace-shortcuts-{}.{}",
+        exporter.id(),
+        exporter.extension()
+    ))
+}
+
+// the dconf path GNOME stores a schema under, relocatable schemas carry
+// theirs after the colon
+fn dconf_dir(schema: &str) -> String {
+    match schema.split_once(':') {
+        Some((_, path)) => path.trim_matches('/').to_string(),
+        None => schema.replace('.', "/"),
+    }
+}
+
+// workspace switch and move bindings, what tiling compositors have
+// equivalents for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WorkspaceAction {
+    // None is the last workspace used
+    Switch(Option<usize>),
+    Move(Option<usize>),
+}
+
+fn workspace_bindings(profile: &Profile) -> Vec<(WorkspaceAction, Accelerator)> {
+    let mut bindings = vec![];
+    for v in profile
+        .values
+        .iter()
+        .filter(|v| v.schema == WM_KEYBINDINGS_SCHEMA)
+    {
+        let (action, target): (fn(Option<usize>) -> WorkspaceAction, &str) =
+            if let Some(target) = v.key.strip_prefix("switch-to-workspace-") {
+                (WorkspaceAction::Switch, target)
+            } else if let Some(target) = v.key.strip_prefix("move-to-workspace-") {
+                (WorkspaceAction::Move, target)
+            } else {
+                continue;
+            };
+        let target = match target {
+            "last" => None,
+            n => match n.parse() {
+                Ok(n) => Some(n),
+                // left, right, up and down
+                Err(_) => continue,
+            },
+        };
+        for accelerator in Accelerator::list_from_gsettings(&v.value).unwrap_or_default() {
+            bindings.push((action(target), accelerator));
+        }
+    }
+    bindings
+}
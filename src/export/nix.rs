@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use super::{dconf_dir, Exporter};
+use crate::{
+    gsettings::{parse_string, parse_string_array},
+    profile::Profile,
+};
+
+/// `dconf.settings` for home-manager.
+pub struct Nix;
+
+fn string(s: &str) -> String {
+    let escaped = s
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace("${", "\\${");
+    format!("\"{escaped}\"")
+}
+
+// the GVariant types the managed keys use, None for anything else
+fn value(gvariant: &str) -> Option<String> {
+    let gvariant = gvariant.trim();
+    if gvariant == "true" || gvariant == "false" || gvariant.parse::<i32>().is_ok() {
+        return Some(gvariant.into());
+    }
+    if let Some(n) = gvariant.strip_prefix("uint32 ") {
+        let n: u32 = n.trim().parse().ok()?;
+        return Some(format!("lib.hm.gvariant.mkUint32 {n}"));
+    }
+    if gvariant.starts_with('\'') || gvariant.starts_with('"') {
+        return parse_string(gvariant).ok().map(|s| string(&s));
+    }
+    let items = parse_string_array(gvariant).ok()?;
+    if items.is_empty() {
+        return Some("lib.hm.gvariant.mkEmptyArray lib.hm.gvariant.type.string".into());
+    }
+    let items: Vec<String> = items.iter().map(|s| string(s)).collect();
+    Some(format!("[ {} ]", items.join(" ")))
+}
+
+impl Exporter for Nix {
+    fn id(&self) -> &'static str {
+        "nix"
+    }
+
+    fn name(&self) -> &'static str {
+        "Nix (home-manager)"
+    }
+
+    fn extension(&self) -> &'static str {
+        "nix"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        let mut dirs: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for v in &profile.values {
+            let line = match value(&v.value) {
+                Some(value) => format!("{} = {value};", string(&v.key)),
+                None => format!("# {} = {}; can't be converted", v.key, v.value),
+            };
+            dirs.entry(dconf_dir(&v.schema)).or_default().push(line);
+        }
+        let mut nix = String::from("{ lib, ... }:\n{\n  dconf.settings = {\n");
+        for (dir, lines) in dirs {
+            nix.push_str(&format!("    {} = {{\n", string(&dir)));
+            for line in lines {
+                nix.push_str(&format!("      {line}\n"));
+            }
+            nix.push_str("    };\n");
+        }
+        nix.push_str("  };\n}\n");
+        Ok(nix)
+    }
+}
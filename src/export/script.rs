@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+use super::Exporter;
+use crate::profile::Profile;
+
+/// A shell script of `gsettings set` calls.
+pub struct Script;
+
+// keybinding lists are full of single quotes, double quotes read better
+// as long as there's nothing the shell would expand
+fn quote(s: &str) -> String {
+    if s.contains('\'') && !s.contains(['"', '$', '`', '\\']) {
+        return format!("\"{s}\"");
+    }
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// The `gsettings set` call writing `value`, quoted for the shell.
+pub fn gsettings_command(schema: &str, key: &str, value: &str) -> String {
+    format!(
+        "gsettings set {} {} {}",
+        quote(schema),
+        quote(key),
+        quote(value)
+    )
+}
+
+impl Exporter for Script {
+    fn id(&self) -> &'static str {
+        "script"
+    }
+
+    fn name(&self) -> &'static str {
+        "Shell script"
+    }
+
+    fn extension(&self) -> &'static str {
+        "sh"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        let mut script = String::from("#!/bin/sh\nset -e\n");
+        for v in &profile.values {
+            script.push_str(&gsettings_command(&v.schema, &v.key, &v.value));
+            script.push('\n');
+        }
+        Ok(script)
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+
+use super::{workspace_bindings, Exporter, WorkspaceAction};
+use crate::profile::Profile;
+
+/// `bindsym` lines for the workspace bindings, the other GNOME actions
+/// have no sway equivalent.
+pub struct Sway;
+
+impl Exporter for Sway {
+    fn id(&self) -> &'static str {
+        "sway"
+    }
+
+    fn name(&self) -> &'static str {
+        "Sway config"
+    }
+
+    fn extension(&self) -> &'static str {
+        "conf"
+    }
+
+    fn export(&self, profile: &Profile) -> Result<String> {
+        let mut config = String::new();
+        for (action, accelerator) in workspace_bindings(profile) {
+            let m = accelerator.modifiers;
+            let mut combo: Vec<&str> = [
+                (m.super_key, "Mod4"),
+                (m.ctrl, "Control"),
+                (m.alt, "Mod1"),
+                (m.shift, "Shift"),
+            ]
+            .iter()
+            .filter(|(held, _)| *held)
+            .map(|(_, name)| *name)
+            .collect();
+            combo.push(&accelerator.key);
+            let command = match action {
+                WorkspaceAction::Switch(Some(n)) => format!("workspace number {n}"),
+                WorkspaceAction::Switch(None) => "workspace back_and_forth".into(),
+                WorkspaceAction::Move(Some(n)) => format!("move container to workspace number {n}"),
+                WorkspaceAction::Move(None) => "move container to workspace back_and_forth".into(),
+            };
+            config.push_str(&format!("bindsym {} {command}\n", combo.join("+")));
+        }
+        Ok(config)
+    }
+}
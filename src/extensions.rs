@@ -0,0 +1,57 @@
+//! GNOME Shell extensions: where they are installed and which are enabled.
+
+use anyhow::Result;
+
+use crate::{
+    backend::SettingsBackend,
+    gsettings::{format_string_array, parse_string_array, SHELL_SCHEMA},
+    transaction::Transaction,
+    xdg,
+};
+
+// GNOME Shell has no setting for the popup shown on every workspace switch,
+// this extension is the common way to turn it off
+pub const DISABLE_SWITCHER_POPUP_UUID: &str = "disable-workspace-switcher-popup@github.com";
+
+// GNOME stops at the first and last workspace, this extension makes the
+// relative workspace shortcuts wrap around instead
+pub const WRAPAROUND_UUID: &str = "workspace-switch-wraparound@theychx.org";
+
+pub fn is_installed(uuid: &str) -> bool {
+    xdg::data_dirs().iter().any(|dir| {
+        dir.join("gnome-shell/extensions")
+            .join(uuid)
+            .join("metadata.json")
+            .exists()
+    })
+}
+
+pub fn enabled_extensions(backend: &dyn SettingsBackend) -> Result<Vec<String>> {
+    parse_string_array(&backend.get(SHELL_SCHEMA, "enabled-extensions")?)
+}
+
+pub fn is_enabled(backend: &dyn SettingsBackend, uuid: &str) -> Result<bool> {
+    Ok(enabled_extensions(backend)?.iter().any(|e| e == uuid))
+}
+
+/// Adding the extension to or removing it from `enabled-extensions`, to
+/// be proposed like any other write.
+pub fn stage_enabled(
+    backend: &dyn SettingsBackend,
+    uuid: &str,
+    enabled: bool,
+) -> Result<Transaction> {
+    let mut extensions = enabled_extensions(backend)?;
+    extensions.retain(|e| e != uuid);
+    if enabled {
+        extensions.push(uuid.into());
+    }
+    let mut transaction = Transaction::new();
+    transaction.stage(
+        backend,
+        SHELL_SCHEMA,
+        "enabled-extensions",
+        &format_string_array(&extensions),
+    )?;
+    Ok(transaction)
+}
@@ -0,0 +1,92 @@
+//! GNOME Shell switches workspaces with a three-finger horizontal swipe on
+//! Wayland. It has no setting for it, extensions are the only way to change
+//! or extend the gestures, so this reports which of the known ones are
+//! installed and enabled.
+
+use anyhow::Result;
+
+use crate::{
+    backend::SettingsBackend,
+    extensions::{enabled_extensions, is_installed},
+    gsettings::SHELL_SCHEMA,
+};
+
+pub struct GestureExtension {
+    pub uuid: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    // the built-in workspace swipe stops working while it's enabled
+    pub disables_builtin: bool,
+}
+
+pub const GESTURE_EXTENSIONS: &[GestureExtension] = &[
+    GestureExtension {
+        uuid: "gestureImprovements@gestures",
+        name: "Gesture Improvements",
+        description: "four-finger workspace switching and more gestures",
+        disables_builtin: false,
+    },
+    GestureExtension {
+        uuid: "disable-gestures-2021@verycrazydog.gmail.com",
+        name: "Disable Gestures 2021",
+        description: "turns the built-in touchpad gestures off",
+        disables_builtin: true,
+    },
+    GestureExtension {
+        uuid: "x11gestures@joseexposito.github.io",
+        name: "X11 Gestures",
+        description: "the Wayland gestures on X11, through Touchégg",
+        disables_builtin: false,
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionState {
+    pub installed: bool,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GestureInfo {
+    pub wayland: bool,
+    pub user_extensions_disabled: bool,
+    // same order as GESTURE_EXTENSIONS
+    pub extensions: Vec<ExtensionState>,
+}
+
+impl GestureInfo {
+    pub fn read(backend: &dyn SettingsBackend) -> Result<Self> {
+        let enabled = enabled_extensions(backend)?;
+        let user_extensions_disabled = backend
+            .get(SHELL_SCHEMA, "disable-user-extensions")
+            .is_ok_and(|v| v.trim() == "true");
+        let extensions = GESTURE_EXTENSIONS
+            .iter()
+            .map(|ext| ExtensionState {
+                installed: is_installed(ext.uuid),
+                enabled: enabled.iter().any(|uuid| uuid == ext.uuid),
+            })
+            .collect();
+        Ok(Self {
+            wayland: std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland"),
+            user_extensions_disabled,
+            extensions,
+        })
+    }
+
+    // an extension only runs if it's installed, enabled and extensions
+    // aren't switched off as a whole
+    fn is_active(&self, i: usize) -> bool {
+        let state = self.extensions[i];
+        state.installed && state.enabled && !self.user_extensions_disabled
+    }
+
+    /// Whether the built-in three-finger workspace swipe is in effect.
+    pub fn builtin_swipe_active(&self) -> bool {
+        self.wayland
+            && !GESTURE_EXTENSIONS
+                .iter()
+                .enumerate()
+                .any(|(i, ext)| ext.disables_builtin && self.is_active(i))
+    }
+}
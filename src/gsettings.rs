@@ -0,0 +1,377 @@
+use anyhow::{bail, Result};
+
+use crate::{accelerator::Accelerator, backend::SettingsBackend};
+#[cfg(not(feature = "native-settings"))]
+use std::{
+    process::{Command, Output},
+    time::Instant,
+};
+
+// an empty string array, the annotation is needed because gsettings can't
+// infer the type of `[]`
+pub const EMPTY_KEYBINDING: &str = "@as []";
+pub const WM_KEYBINDINGS_SCHEMA: &str = "org.gnome.desktop.wm.keybindings";
+pub const WM_PREFERENCES_SCHEMA: &str = "org.gnome.desktop.wm.preferences";
+pub const SHELL_KEYBINDINGS_SCHEMA: &str = "org.gnome.shell.keybindings";
+pub const MUTTER_SCHEMA: &str = "org.gnome.mutter";
+pub const MUTTER_KEYBINDINGS_SCHEMA: &str = "org.gnome.mutter.keybindings";
+pub const SHELL_SCHEMA: &str = "org.gnome.shell";
+pub const MANAGED_SCHEMAS: [&str; 5] = [
+    WM_KEYBINDINGS_SCHEMA,
+    WM_PREFERENCES_SCHEMA,
+    SHELL_KEYBINDINGS_SCHEMA,
+    MUTTER_SCHEMA,
+    MUTTER_KEYBINDINGS_SCHEMA,
+];
+
+/// The items of a GVariant string array like `['a', "b"]` or `@as []`.
+pub fn parse_string_array(value: &str) -> Result<Vec<String>> {
+    let value = value.trim();
+    let value = value.strip_prefix("@as").unwrap_or(value).trim();
+    let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+        bail!("'{value}' is not an array");
+    };
+
+    let mut strings = vec![];
+    let mut chars = items.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(quote) = chars.next() else {
+            break;
+        };
+        if quote != '\'' && quote != '"' {
+            bail!("expected a string in '{value}'");
+        }
+
+        let mut item = String::new();
+        loop {
+            match chars.next() {
+                Some('\\') => match chars.next() {
+                    Some(c) => item.push(c),
+                    None => bail!("unterminated string in '{value}'"),
+                },
+                Some(c) if c == quote => break,
+                Some(c) => item.push(c),
+                None => bail!("unterminated string in '{value}'"),
+            }
+        }
+        strings.push(item);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            Some(',') | None => {}
+            Some(c) => bail!("unexpected '{c}' in '{value}'"),
+        }
+    }
+    Ok(strings)
+}
+
+/// A single GVariant string like `'Terminal'` or `"it's"`.
+pub fn parse_string(value: &str) -> Result<String> {
+    match parse_string_array(&format!("[{}]", value.trim()))?.as_slice() {
+        [s] => Ok(s.clone()),
+        _ => bail!("'{}' is not a string", value.trim()),
+    }
+}
+
+/// The GVariant text of a single string, quoted like gsettings prints it.
+pub fn format_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// The GVariant text of a string array, `@as []` when empty.
+pub fn format_string_array(items: &[String]) -> String {
+    if items.is_empty() {
+        return EMPTY_KEYBINDING.into();
+    }
+    let quoted: Vec<String> = items.iter().map(|item| format_string(item)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// Whether two values printed by gsettings mean the same, e.g. `[]` and
+/// `@as []`, or `5` and `uint32 5`. Anything else is compared as text.
+pub fn same_value(a: &str, b: &str) -> bool {
+    match (parse_string_array(a), parse_string_array(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => strip_number_type(a) == strip_number_type(b),
+    }
+}
+
+/// `schema key value` lines as printed by `gsettings list-recursively`.
+pub fn parse_list_recursively(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let (_schema, key, value) = (parts.next()?, parts.next()?, parts.next()?);
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+// numbers that aren't int32 are printed with their type
+fn strip_number_type(value: &str) -> &str {
+    let value = value.trim();
+    match value.split_once(' ') {
+        Some(("byte" | "int16" | "uint16" | "uint32" | "int64" | "uint64", n)) => n,
+        _ => value,
+    }
+}
+
+pub struct GSettings;
+
+impl GSettings {
+    pub fn get(schema: &str, key: &str) -> Result<String> {
+        #[cfg(feature = "native-settings")]
+        {
+            Ok(crate::settings::get(schema, key)?)
+        }
+        #[cfg(not(feature = "native-settings"))]
+        {
+            let output = Self::run("get", schema, key, None)?;
+            Self::check_output(&output, false)?;
+            Ok(String::from_utf8(output.stdout)?)
+        }
+    }
+
+    pub fn set(schema: &str, key: &str, value: &str) -> Result<()> {
+        #[cfg(feature = "native-settings")]
+        {
+            Ok(crate::settings::set(schema, key, value)?)
+        }
+        #[cfg(not(feature = "native-settings"))]
+        {
+            let output = Self::run("set", schema, key, Some(value))?;
+            Self::check_output(&output, true)
+        }
+    }
+
+    /// The value the schema ships with, what `gsettings reset` would leave.
+    /// The memory backend doesn't see dconf, so it only has the defaults.
+    pub fn default_value(schema: &str, key: &str) -> Result<String> {
+        #[cfg(feature = "native-settings")]
+        {
+            Ok(crate::settings::default_value(schema, key)?)
+        }
+        #[cfg(not(feature = "native-settings"))]
+        {
+            let output = Command::new("gsettings")
+                .env("GSETTINGS_BACKEND", "memory")
+                .arg("get")
+                .arg(schema)
+                .arg(key)
+                .output()?;
+            Self::check_output(&output, false)?;
+            Ok(String::from_utf8(output.stdout)?)
+        }
+    }
+
+    pub fn describe(schema: &str, key: &str) -> Result<String> {
+        #[cfg(feature = "native-settings")]
+        {
+            Ok(crate::settings::describe(schema, key)?)
+        }
+        #[cfg(not(feature = "native-settings"))]
+        {
+            let output = Command::new("gsettings")
+                .arg("describe")
+                .arg(schema)
+                .arg(key)
+                .output()?;
+            Self::check_output(&output, false)?;
+            Ok(String::from_utf8(output.stdout)?)
+        }
+    }
+
+    /// False for keys locked by an administrator through dconf, writes to
+    /// them are silently dropped.
+    pub fn is_writable(schema: &str, key: &str) -> Result<bool> {
+        #[cfg(feature = "native-settings")]
+        {
+            Ok(crate::settings::is_writable(schema, key)?)
+        }
+        #[cfg(not(feature = "native-settings"))]
+        {
+            let output = Command::new("gsettings")
+                .arg("writable")
+                .arg(schema)
+                .arg(key)
+                .output()?;
+            Self::check_output(&output, false)?;
+            match String::from_utf8(output.stdout)?.trim() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                other => bail!("gsettings writable printed '{other}'"),
+            }
+        }
+    }
+
+    #[cfg(not(feature = "native-settings"))]
+    fn run(op: &str, schema: &str, key: &str, value: Option<&str>) -> Result<Output> {
+        let mut command = Command::new("gsettings");
+        command.arg(op).arg(schema).arg(key);
+        if let Some(value) = value {
+            command.arg(value);
+        }
+
+        let start = Instant::now();
+        let output = command.output();
+        let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        match &output {
+            Ok(output) if output.status.success() => {
+                tracing::info!(
+                    op,
+                    schema,
+                    key,
+                    value,
+                    duration_ms,
+                    status = output.status.code(),
+                    "gsettings {op}"
+                );
+            }
+            Ok(output) => {
+                tracing::error!(
+                    op,
+                    schema,
+                    key,
+                    value,
+                    duration_ms,
+                    status = output.status.code(),
+                    stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+                    "gsettings {op} failed"
+                );
+            }
+            Err(e) => {
+                tracing::error!(op, schema, key, value, duration_ms, error = %e, "gsettings {op} failed to start");
+            }
+        }
+        Ok(output?)
+    }
+
+    // gsettings reports rejected values on stderr, and dconf warnings about
+    // writes that never got committed even come with a successful exit code,
+    // so writes treat any stderr output as failure
+    #[cfg(not(feature = "native-settings"))]
+    fn check_output(output: &Output, strict: bool) -> Result<()> {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        if !output.status.success() || (strict && !stderr.is_empty()) {
+            if stderr.is_empty() {
+                bail!("gsettings failed with {}", output.status);
+            }
+            bail!("{}", stderr);
+        }
+        Ok(())
+    }
+
+    pub fn list_keys(schema: &str) -> Result<Vec<String>> {
+        #[cfg(feature = "native-settings")]
+        {
+            Ok(crate::settings::list_keys(schema)?)
+        }
+        #[cfg(not(feature = "native-settings"))]
+        {
+            let output = Command::new("gsettings")
+                .arg("list-keys")
+                .arg(schema)
+                .output()?;
+            Self::check_output(&output, false)?;
+            Ok(String::from_utf8(output.stdout)?
+                .lines()
+                .map(str::to_string)
+                .collect())
+        }
+    }
+
+    /// (key, value) of every key of the schema, in one `gsettings` run.
+    #[cfg(not(feature = "native-settings"))]
+    pub fn list_recursively(schema: &str) -> Result<Vec<(String, String)>> {
+        let output = Command::new("gsettings")
+            .arg("list-recursively")
+            .arg(schema)
+            .output()?;
+        Self::check_output(&output, false)?;
+        Ok(parse_list_recursively(&String::from_utf8(output.stdout)?))
+    }
+
+    pub fn is_empty_keybinding(value: &str) -> bool {
+        matches!(value.trim(), "@as []" | "[]")
+    }
+
+    pub fn unbind(schema: &str, key: &str) -> Result<()> {
+        SettingsBackend::unbind(&GSettings, schema, key)
+    }
+
+    // id is 1-9
+
+    pub fn disable_switch_to_application_shortcuts() -> Result<()> {
+        for i in 1..10 {
+            Self::unbind(
+                SHELL_KEYBINDINGS_SCHEMA,
+                &format!("switch-to-application-{i}"),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn get_switch_to_application_keybinding(id: u32) -> Result<String> {
+        Self::get(
+            SHELL_KEYBINDINGS_SCHEMA,
+            &format!("switch-to-application-{id}"),
+        )
+    }
+
+    pub fn set_number_of_workspaces(num: usize) -> Result<()> {
+        Self::set(WM_PREFERENCES_SCHEMA, "num-workspaces", &num.to_string())
+    }
+
+    pub fn get_number_of_workspaces() -> Result<usize> {
+        Ok(Self::get(WM_PREFERENCES_SCHEMA, "num-workspaces")?
+            .trim()
+            .parse()?)
+    }
+
+    pub fn get_dynamic_workspaces() -> Result<bool> {
+        Ok(Self::get(MUTTER_SCHEMA, "dynamic-workspaces")?
+            .trim()
+            .parse()?)
+    }
+
+    pub fn set_dynamic_workspaces(enabled: bool) -> Result<()> {
+        Self::set(MUTTER_SCHEMA, "dynamic-workspaces", &enabled.to_string())
+    }
+
+    /// One of `click`, `sloppy` or `mouse`.
+    pub fn get_focus_mode() -> Result<String> {
+        let value = Self::get(WM_PREFERENCES_SCHEMA, "focus-mode")?;
+        parse_string(&value)
+    }
+
+    pub fn set_focus_mode(mode: &str) -> Result<()> {
+        Self::set(WM_PREFERENCES_SCHEMA, "focus-mode", &format!("'{mode}'"))
+    }
+
+    pub fn get_auto_raise() -> Result<bool> {
+        Ok(Self::get(WM_PREFERENCES_SCHEMA, "auto-raise")?
+            .trim()
+            .parse()?)
+    }
+
+    pub fn set_auto_raise(enabled: bool) -> Result<()> {
+        Self::set(WM_PREFERENCES_SCHEMA, "auto-raise", &enabled.to_string())
+    }
+
+    pub fn get_wm_keybinding(gsettings_key: &str) -> Result<String> {
+        Self::get(WM_KEYBINDINGS_SCHEMA, gsettings_key)
+    }
+
+    /// The parsed accelerators of a wm keybinding, empty when it's unbound.
+    pub fn wm_keybinding(gsettings_key: &str) -> Result<Vec<Accelerator>> {
+        Accelerator::list_from_gsettings(&Self::get_wm_keybinding(gsettings_key)?)
+    }
+
+    pub fn set_wm_keybinding(gsettings_key: &str, gsettings_value: &str) -> Result<()> {
+        Self::set(WM_KEYBINDINGS_SCHEMA, gsettings_key, gsettings_value)
+    }
+}
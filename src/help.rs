@@ -0,0 +1,71 @@
+//! The keybinding syntax help shown next to the rows, and a plain reading
+//! of a row's current value.
+
+use crate::{accelerator::Accelerator, gsettings::GSettings, keysym::Keysyms};
+
+const SYNTAX: &str = include_str!("../keybinding-syntax.md");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading(String),
+    Text(String),
+    // a value that can be pasted into gsettings or a row as is
+    Example(String),
+}
+
+/// The embedded help: `# ` lines are headings, indented lines examples.
+pub fn syntax() -> Vec<Block> {
+    SYNTAX
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            if let Some(heading) = line.strip_prefix("# ") {
+                Block::Heading(heading.into())
+            } else if let Some(example) = line.strip_prefix("    ") {
+                Block::Example(example.into())
+            } else {
+                Block::Text(line.into())
+            }
+        })
+        .collect()
+}
+
+/// What a gsettings value means, one line per binding.
+pub fn explain(value: &str, keysyms: &Keysyms) -> Vec<String> {
+    if GSettings::is_empty_keybinding(value) {
+        return vec!["Unbound, no key triggers this action.".into()];
+    }
+    let accelerators = match Accelerator::list_from_gsettings(value) {
+        Ok(accelerators) => accelerators,
+        Err(e) => return vec![format!("GNOME can't read this value: {:#}", e)],
+    };
+    let mut lines = vec![];
+    if accelerators.len() > 1 {
+        lines.push(format!(
+            "{} bindings, any of them triggers this action.",
+            accelerators.len()
+        ));
+    }
+    for a in &accelerators {
+        let m = a.modifiers;
+        let held: Vec<&str> = [
+            (m.shift, "Shift"),
+            (m.ctrl, "Ctrl"),
+            (m.alt, "Alt"),
+            (m.super_key, "Super"),
+        ]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect();
+        let key = match keysyms.key(&a.key) {
+            Some(c) => format!("{} (the {c} key)", a.key),
+            None => a.key.clone(),
+        };
+        lines.push(if held.is_empty() {
+            format!("{}: press {key}", a.format())
+        } else {
+            format!("{}: hold {}, press {key}", a.format(), held.join("+"))
+        });
+    }
+    lines
+}
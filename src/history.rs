@@ -0,0 +1,108 @@
+//! The last value this app wrote to each key, to tell whether the system
+//! still has it or something else changed the key since.
+
+use std::{
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{gsettings::same_value, xdg};
+
+pub const HISTORY_FILE: &str = "history.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub schema: String,
+    pub key: String,
+    pub value: String,
+    // seconds since the epoch
+    pub at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncState {
+    NeverApplied,
+    Synced,
+    // the key was changed by something else after this app wrote it
+    Drifted,
+}
+
+/// Compare the last write of a key with its `current` value.
+pub fn sync_state(entry: Option<&Entry>, current: &str) -> SyncState {
+    match entry {
+        None => SyncState::NeverApplied,
+        Some(e) if same_value(&e.value, current) => SyncState::Synced,
+        Some(_) => SyncState::Drifted,
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    pub entries: Vec<Entry>,
+}
+
+impl History {
+    /// A missing or unreadable history starts empty.
+    pub fn load() -> Self {
+        let path = xdg::state_dir().join(HISTORY_FILE);
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str(&content) {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!("ignoring {}: {:#}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn last(&self, schema: &str, key: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.schema == schema && e.key == key)
+    }
+
+    /// Remember a successful write and save the history right away.
+    pub fn record(&mut self, schema: &str, key: &str, value: &str) -> Result<()> {
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.entries
+            .retain(|e| !(e.schema == schema && e.key == key));
+        self.entries.push(Entry {
+            schema: schema.into(),
+            key: key.into(),
+            value: value.trim().into(),
+            at,
+        });
+        fs::write(
+            xdg::create_file_path(xdg::state_dir(), HISTORY_FILE)?,
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let (hour, minute) = (secs % 86_400 / 3600, secs % 3600 / 60);
+
+    // days to a civil date, see Howard Hinnant's chrono-compatible algorithms
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}")
+}
@@ -0,0 +1,44 @@
+//! Shell commands a profile runs around its apply, e.g. a notification or
+//! restarting a companion extension. Their output goes to the log.
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    // a failing pre_apply stops the apply before anything is written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_apply: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_apply: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_apply.is_none() && self.post_apply.is_none()
+    }
+}
+
+/// Run `command` with `sh -c`, logging what it prints. Exiting with an
+/// error status is an error.
+pub fn run(hook: &str, command: &str) -> Result<()> {
+    tracing::info!(hook, command, "running hook");
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .with_context(|| format!("can't run the {hook} hook"))?;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        tracing::info!(hook, "{}", line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        tracing::warn!(hook, "{}", line);
+    }
+    if !output.status.success() {
+        bail!("the {hook} hook failed with {}", output.status);
+    }
+    Ok(())
+}
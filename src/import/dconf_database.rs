@@ -0,0 +1,25 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::{dconf_keyfile::DconfKeyfile, Importer};
+use crate::{dconf, profile::Profile};
+
+/// A dconf database file, e.g. ~/.config/dconf/user copied from another
+/// machine.
+pub struct DconfDatabase;
+
+impl Importer for DconfDatabase {
+    fn name(&self) -> &'static str {
+        "dconf database"
+    }
+
+    // the signature of GVDB files
+    fn detect(&self, data: &[u8]) -> bool {
+        data.starts_with(b"GVariant")
+    }
+
+    fn import(&self, path: &Path, _data: &[u8]) -> Result<Profile> {
+        DconfKeyfile::profile(&dconf::dump_database(path, "/")?)
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::{schema_of_dir, text, Importer};
+use crate::{
+    dconf,
+    profile::{managed_keys, Profile, ProfileValue},
+};
+
+/// What `dconf dump /` prints, or `dconf dump` of a single directory like
+/// `/org/gnome/desktop/wm/keybindings/`.
+pub struct DconfKeyfile;
+
+impl DconfKeyfile {
+    // groups are relative to the dumped directory. A dump of a single
+    // directory has its keys in [/], which doesn't say where it was taken,
+    // so the schema is the managed one with that key.
+    pub(super) fn profile(dump: &str) -> Result<Profile> {
+        let managed = managed_keys();
+        let values = dconf::parse_dump(dump)?
+            .into_iter()
+            .map(|entry| {
+                let schema = if entry.group.trim_matches('/').is_empty() {
+                    managed
+                        .iter()
+                        .find(|(_, key)| *key == entry.key)
+                        .map(|(schema, _)| schema.to_string())
+                        .unwrap_or_default()
+                } else {
+                    schema_of_dir(&entry.group)
+                };
+                ProfileValue {
+                    schema,
+                    key: entry.key,
+                    value: entry.value,
+                }
+            })
+            .collect();
+        Ok(Profile {
+            values,
+            ..Profile::default()
+        })
+    }
+}
+
+impl Importer for DconfKeyfile {
+    fn name(&self) -> &'static str {
+        "dconf dump"
+    }
+
+    // the first group is a dconf directory like [org/gnome/desktop/wm/keybindings]
+    // or [/]
+    fn detect(&self, data: &[u8]) -> bool {
+        let Ok(text) = text(data) else {
+            return false;
+        };
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'));
+        first
+            .and_then(|l| l.strip_prefix('[')?.strip_suffix(']'))
+            .is_some_and(|group| group.contains('/') && !group.contains('"'))
+    }
+
+    fn import(&self, _path: &Path, data: &[u8]) -> Result<Profile> {
+        Self::profile(text(data)?)
+    }
+}
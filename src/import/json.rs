@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::{text, Importer};
+use crate::profile::Profile;
+
+/// Profiles as saved by this app.
+pub struct Json;
+
+impl Importer for Json {
+    fn name(&self) -> &'static str {
+        "JSON profile"
+    }
+
+    fn detect(&self, data: &[u8]) -> bool {
+        data.trim_ascii_start().starts_with(b"{")
+    }
+
+    fn import(&self, _path: &Path, data: &[u8]) -> Result<Profile> {
+        Profile::from_json(text(data)?)
+    }
+}
@@ -0,0 +1,154 @@
+//! Reading a profile out of whatever file is picked for import. Every
+//! format is one file here and an entry in [`IMPORTERS`], the first
+//! importer recognizing the file reads it.
+
+mod dconf_database;
+mod dconf_keyfile;
+mod json;
+mod script;
+mod toml;
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    backend::SettingsBackend,
+    custom_shortcuts::{self, CUSTOM_KEYBINDING_SCHEMA, MEDIA_KEYS_SCHEMA},
+    gsettings::same_value,
+    profile::{managed_keys, Profile},
+    transaction::Transaction,
+};
+
+pub trait Importer: Sync {
+    fn name(&self) -> &'static str;
+    /// Whether `data` looks like this format.
+    fn detect(&self, data: &[u8]) -> bool;
+    fn import(&self, path: &Path, data: &[u8]) -> Result<Profile>;
+}
+
+// TOML last, it's the only format recognized by parsing all of it
+pub static IMPORTERS: [&dyn Importer; 5] = [
+    &json::Json,
+    &dconf_database::DconfDatabase,
+    &script::Script,
+    &dconf_keyfile::DconfKeyfile,
+    &toml::Toml,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Imported {
+    // name of the detected format
+    pub format: &'static str,
+    // only the keys this app manages
+    pub profile: Profile,
+    // values in the file left out for not being managed here
+    pub skipped: usize,
+}
+
+/// What [`Imported::stage`] staged and what it had to leave out.
+#[derive(Debug)]
+pub struct Staged {
+    pub transaction: Transaction,
+    // "key in schema: reason" of every imported key left out
+    pub failed: Vec<String>,
+}
+
+impl Imported {
+    /// Stage the values the backend doesn't already have, and the defaults
+    /// a minimal profile stands for. Keys this GNOME version doesn't know
+    /// are left out and listed in `failed`, they'd fail the whole
+    /// transaction otherwise.
+    pub fn stage(&self, backend: &dyn SettingsBackend) -> Staged {
+        let mut transaction = Transaction::new();
+        let mut failed = vec![];
+        let defaults = self.profile.unmentioned_defaults(backend);
+        for v in self.profile.values.iter().chain(&defaults) {
+            let res = match backend.get(&v.schema, &v.key) {
+                Ok(current) if same_value(&current, &v.value) => continue,
+                Ok(_) => transaction.stage(backend, &v.schema, &v.key, &v.value),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = res {
+                tracing::warn!(
+                    schema = v.schema,
+                    key = v.key,
+                    "skipping imported key: {:#}",
+                    e
+                );
+                failed.push(format!("{} in {}: {:#}", v.key, v.schema, e));
+            }
+        }
+        Staged {
+            transaction,
+            failed,
+        }
+    }
+}
+
+pub fn import_file(path: &Path) -> Result<Imported> {
+    let data = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    import(path, &data)
+}
+
+/// Detect the format of `data` and read it. `path` is only opened again by
+/// importers that need a file, like dconf databases.
+pub fn import(path: &Path, data: &[u8]) -> Result<Imported> {
+    let (format, profile) = read(path, data)?;
+    let total = profile.values.len();
+    let profile = managed_only(profile);
+    Ok(Imported {
+        format,
+        skipped: total - profile.values.len(),
+        profile,
+    })
+}
+
+/// The name of the detected format and everything in the file, managed
+/// here or not.
+pub fn read(path: &Path, data: &[u8]) -> Result<(&'static str, Profile)> {
+    let Some(importer) = IMPORTERS.iter().find(|i| i.detect(data)) else {
+        let names: Vec<&str> = IMPORTERS.iter().map(|i| i.name()).collect();
+        bail!(
+            "{} is none of the formats that can be imported: {}",
+            path.display(),
+            names.join(", ")
+        );
+    };
+    let profile = importer
+        .import(path, data)
+        .with_context(|| format!("{} is not a valid {}", path.display(), importer.name()))?;
+    Ok((importer.name(), profile))
+}
+
+/// Whether an imported value is one of the keys this app manages.
+pub fn is_managed(schema: &str, key: &str) -> bool {
+    managed_keys()
+        .iter()
+        .any(|(s, k)| *s == schema && *k == key)
+        || (schema == MEDIA_KEYS_SCHEMA && key == "custom-keybindings")
+        || schema.starts_with(&format!("{CUSTOM_KEYBINDING_SCHEMA}:"))
+}
+
+// a dconf dump or a script can hold settings of any application
+fn managed_only(mut profile: Profile) -> Profile {
+    profile.values.retain(|v| is_managed(&v.schema, &v.key));
+    profile
+}
+
+fn text(data: &[u8]) -> Result<&str> {
+    std::str::from_utf8(data).context("not a text file")
+}
+
+const CUSTOM_KEYBINDINGS_DIR: &str =
+    "org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/";
+
+// the schema stored under a dconf directory, the inverse of what the
+// dconf exporter writes
+fn schema_of_dir(dir: &str) -> String {
+    let dir = dir.trim_matches('/');
+    if dir.starts_with(CUSTOM_KEYBINDINGS_DIR) {
+        return custom_shortcuts::schema_at(&format!("/{dir}/"));
+    }
+    dir.replace('/', ".")
+}
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use super::{text, Importer};
+use crate::profile::Profile;
+
+/// Shell scripts of `gsettings set` calls, like the script exporter
+/// writes. Other commands are ignored.
+pub struct Script;
+
+// split a command line into words, with the single and double quotes and
+// backslashes a generated script uses
+fn words(line: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => words.extend(word.take()),
+            '\'' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => w.push(c),
+                        None => bail!("unterminated ' in {line}"),
+                    }
+                }
+            }
+            '"' => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => w.extend(chars.next()),
+                        Some(c) => w.push(c),
+                        None => bail!("unterminated \" in {line}"),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+impl Importer for Script {
+    fn name(&self) -> &'static str {
+        "gsettings script"
+    }
+
+    fn detect(&self, data: &[u8]) -> bool {
+        text(data).is_ok_and(|text| {
+            text.lines()
+                .any(|l| l.trim_start().starts_with("gsettings set "))
+        })
+    }
+
+    fn import(&self, _path: &Path, data: &[u8]) -> Result<Profile> {
+        let mut profile = Profile::default();
+        for (i, line) in text(data)?.lines().enumerate() {
+            if !line.trim_start().starts_with("gsettings set ") {
+                continue;
+            }
+            match words(line)?.as_slice() {
+                [_, _, schema, key, value] => profile.set(schema, key, value),
+                _ => bail!("line {}: expected gsettings set SCHEMA KEY VALUE", i + 1),
+            }
+        }
+        Ok(profile)
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use toml_edit::{Document, Item, Table, Value};
+
+use super::{text, Importer};
+use crate::profile::Profile;
+
+/// A table per schema, e.g.
+///
+/// ```toml
+/// ["org.gnome.desktop.wm.keybindings"]
+/// switch-to-workspace-1 = ["<Super>1"]
+/// ```
+///
+/// Strings are GVariant text as `gsettings` prints it, arrays of strings,
+/// booleans and integers are converted.
+pub struct Toml;
+
+fn gvariant(value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::String(s) => s.value().clone(),
+        Value::Integer(i) => i.value().to_string(),
+        Value::Boolean(b) => b.value().to_string(),
+        Value::Array(items) => {
+            let mut strings = vec![];
+            for item in items {
+                let Value::String(s) = item else {
+                    bail!("only arrays of strings can be converted");
+                };
+                strings.push(format!("'{}'", s.value().replace('\'', "\\'")));
+            }
+            if strings.is_empty() {
+                "@as []".into()
+            } else {
+                format!("[{}]", strings.join(", "))
+            }
+        }
+        _ => bail!("can't convert a {}", value.type_name()),
+    })
+}
+
+// dotted table names are nested tables, [org.gnome.mutter] is the same
+// schema as ["org.gnome.mutter"]
+fn collect(table: &Table, schema: &str, profile: &mut Profile) -> Result<()> {
+    for (name, item) in table.iter() {
+        match item {
+            Item::Table(nested) => {
+                let nested_schema = match schema {
+                    "" => name.to_string(),
+                    _ => format!("{schema}.{name}"),
+                };
+                collect(nested, &nested_schema, profile)?;
+            }
+            Item::Value(Value::InlineTable(_)) | Item::ArrayOfTables(_) => {
+                bail!("{schema}.{name}: expected a value")
+            }
+            Item::Value(value) if !schema.is_empty() => {
+                let value = gvariant(value).with_context(|| format!("{schema} {name}"))?;
+                profile.set(schema, name, &value)
+            }
+            Item::Value(_) => bail!("{name} is outside of a schema table"),
+            Item::None => {}
+        }
+    }
+    Ok(())
+}
+
+impl Importer for Toml {
+    fn name(&self) -> &'static str {
+        "TOML"
+    }
+
+    fn detect(&self, data: &[u8]) -> bool {
+        text(data).is_ok_and(|text| Document::parse(text).is_ok())
+    }
+
+    fn import(&self, _path: &Path, data: &[u8]) -> Result<Profile> {
+        let document = Document::parse(text(data)?)?;
+        let mut profile = Profile::default();
+        collect(document.as_table(), "", &mut profile)?;
+        Ok(profile)
+    }
+}
@@ -0,0 +1,37 @@
+//! Whether the app switchers and the dash only show what's on the current
+//! workspace. GNOME Shell has keys for its switchers, the docks have their
+//! own. Only keys that exist on this system are offered.
+
+use crate::{
+    backend::SettingsBackend,
+    switcher_popup::{self, PopupSetting},
+};
+
+// (schema, key, label)
+const CANDIDATES: &[(&str, &str, &str)] = &[
+    (
+        "org.gnome.shell.app-switcher",
+        "current-workspace-only",
+        "Alt+Tab only shows apps of the current workspace",
+    ),
+    (
+        "org.gnome.shell.window-switcher",
+        "current-workspace-only",
+        "The window switcher only shows windows of the current workspace",
+    ),
+    (
+        "org.gnome.shell.extensions.dash-to-dock",
+        "isolate-workspaces",
+        "Dash to Dock only shows apps of the current workspace",
+    ),
+    (
+        "org.gnome.shell.extensions.dash-to-panel",
+        "isolate-workspaces",
+        "Dash to Panel only shows apps of the current workspace",
+    ),
+];
+
+/// The candidate settings this system has, with their current value.
+pub fn available(backend: &dyn SettingsBackend) -> Vec<PopupSetting> {
+    switcher_popup::available_among(backend, CANDIDATES)
+}
@@ -0,0 +1,95 @@
+//! Keys that sit in different places on ISO and ANSI keyboards, so a
+//! binding working on a laptop can be missing on an external keyboard.
+
+use std::process::Command;
+
+use crate::{accelerator::Accelerator, backend::SettingsBackend};
+
+pub const INPUT_SOURCES_SCHEMA: &str = "org.gnome.desktop.input-sources";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutVariant {
+    // an extra key left of Z and a taller Return key
+    Iso,
+    Ansi,
+}
+
+impl LayoutVariant {
+    /// From the XKB model set with `localectl`, or when there is none a
+    /// guess from the first GNOME input source.
+    pub fn detect(backend: &dyn SettingsBackend) -> Option<Self> {
+        let model = Command::new("localectl")
+            .arg("status")
+            .output()
+            .ok()
+            .and_then(|output| parse_localectl_model(&String::from_utf8_lossy(&output.stdout)));
+        if let Some(variant) = model.and_then(|model| Self::from_model(&model)) {
+            return Some(variant);
+        }
+        let sources = backend.get(INPUT_SOURCES_SCHEMA, "sources").ok()?;
+        Some(Self::from_layout(&first_xkb_layout(&sources)?))
+    }
+
+    pub fn from_model(model: &str) -> Option<Self> {
+        match model {
+            "pc102" | "pc105" => Some(LayoutVariant::Iso),
+            "pc101" | "pc104" => Some(LayoutVariant::Ansi),
+            _ => None,
+        }
+    }
+
+    // US keyboards are ANSI, nearly every other layout is sold on ISO ones
+    pub fn from_layout(layout: &str) -> Self {
+        match layout.split('+').next().unwrap_or(layout) {
+            "us" => LayoutVariant::Ansi,
+            _ => LayoutVariant::Iso,
+        }
+    }
+}
+
+/// `X11 Model: pc105` as printed by `localectl status`.
+pub fn parse_localectl_model(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let model = line.trim().strip_prefix("X11 Model:")?.trim();
+        (!model.is_empty()).then(|| model.to_string())
+    })
+}
+
+/// The layout of the first `('xkb', 'de+nodeadkeys')` input source.
+pub fn first_xkb_layout(sources: &str) -> Option<String> {
+    let rest = sources.split_once("('xkb', '")?.1;
+    let layout = rest.split('\'').next()?;
+    (!layout.is_empty()).then(|| layout.to_string())
+}
+
+/// Where the key of a keysym sits, for keysyms whose place differs
+/// between ISO and ANSI keyboards.
+pub fn note(keysym: &str, variant: LayoutVariant) -> Option<&'static str> {
+    match (keysym, variant) {
+        ("less" | "greater", LayoutVariant::Iso) => {
+            Some("the key left of Z, ANSI keyboards don't have it")
+        }
+        ("less" | "greater", LayoutVariant::Ansi) => {
+            Some("Shift+comma here, ISO keyboards have a separate key left of Z")
+        }
+        ("backslash" | "bar", LayoutVariant::Iso) => {
+            Some("left of Return or Z depending on the layout, above Return on ANSI keyboards")
+        }
+        ("backslash" | "bar", LayoutVariant::Ansi) => {
+            Some("the key above Return, it moves on ISO keyboards")
+        }
+        ("numbersign", LayoutVariant::Iso) => {
+            Some("possibly the key left of Return, which ANSI keyboards don't have")
+        }
+        _ => None,
+    }
+}
+
+/// Notes for every accelerator in a gsettings keybinding value.
+pub fn notes(value: &str, variant: LayoutVariant) -> Vec<String> {
+    Accelerator::list_from_gsettings(value)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|a| Some(format!("{}: {}", a.format(), note(&a.key, variant)?)))
+        .collect()
+}
@@ -0,0 +1,54 @@
+//! Key repeat settings, tuned alongside shortcuts in keyboard-driven
+//! setups.
+
+use anyhow::{Context, Result};
+
+use crate::backend::SettingsBackend;
+
+pub const KEYBOARD_SCHEMA: &str = "org.gnome.desktop.peripherals.keyboard";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRepeat {
+    pub enabled: bool,
+    // milliseconds before repeating starts
+    pub delay: u32,
+    // milliseconds between repeats
+    pub interval: u32,
+}
+
+// gsettings prints uint32 values with their type, e.g. `uint32 500`
+fn parse_uint(value: &str) -> Result<u32> {
+    let value = value.trim();
+    let number = value.strip_prefix("uint32").unwrap_or(value).trim();
+    number
+        .parse()
+        .with_context(|| format!("'{value}' is not a uint32"))
+}
+
+impl KeyRepeat {
+    pub fn read(backend: &dyn SettingsBackend) -> Result<Self> {
+        Ok(Self {
+            enabled: backend.get(KEYBOARD_SCHEMA, "repeat")?.trim().parse()?,
+            delay: parse_uint(&backend.get(KEYBOARD_SCHEMA, "delay")?)?,
+            interval: parse_uint(&backend.get(KEYBOARD_SCHEMA, "repeat-interval")?)?,
+        })
+    }
+
+    /// Write the keys that differ from `old`.
+    pub fn write_changes(&self, old: &Self, backend: &dyn SettingsBackend) -> Result<()> {
+        if self.enabled != old.enabled {
+            backend.set(KEYBOARD_SCHEMA, "repeat", &self.enabled.to_string())?;
+        }
+        if self.delay != old.delay {
+            backend.set(KEYBOARD_SCHEMA, "delay", &format!("uint32 {}", self.delay))?;
+        }
+        if self.interval != old.interval {
+            backend.set(
+                KEYBOARD_SCHEMA,
+                "repeat-interval",
+                &format!("uint32 {}", self.interval),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+//! A standalone HTML page with a keyboard diagram of a profile's bindings,
+//! for sharing a scheme with people who don't run this app.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    accelerator::Accelerator,
+    custom_shortcuts::CUSTOM_KEYBINDING_SCHEMA,
+    gsettings::{parse_string, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA},
+    profile::Profile,
+};
+
+// US layout, keysym names as they appear in accelerators
+const ROWS: [&[&str]; 5] = [
+    &[
+        "Escape", "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+    ],
+    &[
+        "1",
+        "2",
+        "3",
+        "4",
+        "5",
+        "6",
+        "7",
+        "8",
+        "9",
+        "0",
+        "minus",
+        "equal",
+        "BackSpace",
+    ],
+    &[
+        "Tab", "q", "w", "e", "r", "t", "y", "u", "i", "o", "p", "Home", "End",
+    ],
+    &[
+        "a",
+        "s",
+        "d",
+        "f",
+        "g",
+        "h",
+        "j",
+        "k",
+        "l",
+        "Return",
+        "Page_Up",
+        "Up",
+        "Page_Down",
+    ],
+    &[
+        "z", "x", "c", "v", "b", "n", "m", "comma", "period", "space", "Left", "Down", "Right",
+    ],
+];
+
+#[derive(Debug, Serialize)]
+struct Binding {
+    modifiers: String,
+    action: String,
+}
+
+// "switch-to-workspace-3" reads better as "switch to workspace 3"
+fn action_label(key: &str) -> String {
+    key.replace('-', " ")
+}
+
+fn key_label(key: &str) -> &str {
+    match key {
+        "minus" => "-",
+        "equal" => "=",
+        "comma" => ",",
+        "period" => ".",
+        "BackSpace" => "⌫",
+        "Return" => "⏎",
+        "Page_Up" => "PgUp",
+        "Page_Down" => "PgDn",
+        "Up" => "↑",
+        "Down" => "↓",
+        "Left" => "←",
+        "Right" => "→",
+        "Escape" => "Esc",
+        "space" => "Space",
+        other => other,
+    }
+}
+
+/// Keysym (lowercase) to the bindings on it.
+fn bindings(profile: &Profile) -> BTreeMap<String, Vec<Binding>> {
+    let mut keys: BTreeMap<String, Vec<Binding>> = BTreeMap::new();
+    let mut add = |accelerator: Accelerator, action: String| {
+        let modifiers = accelerator.modifiers.format();
+        keys.entry(accelerator.key.to_lowercase())
+            .or_default()
+            .push(Binding {
+                modifiers: if modifiers.is_empty() {
+                    "no modifier".into()
+                } else {
+                    modifiers
+                },
+                action,
+            });
+    };
+
+    // names of custom shortcuts by schema path, their binding is stored next to it
+    let custom_names: BTreeMap<&str, String> = profile
+        .values
+        .iter()
+        .filter(|v| v.key == "name" && v.schema.starts_with(CUSTOM_KEYBINDING_SCHEMA))
+        .filter_map(|v| Some((v.schema.as_str(), parse_string(&v.value).ok()?)))
+        .collect();
+
+    for v in &profile.values {
+        if v.schema == WM_KEYBINDINGS_SCHEMA || v.schema == SHELL_KEYBINDINGS_SCHEMA {
+            for a in Accelerator::list_from_gsettings(&v.value).unwrap_or_default() {
+                add(a, action_label(&v.key));
+            }
+        } else if v.key == "binding" && v.schema.starts_with(CUSTOM_KEYBINDING_SCHEMA) {
+            let Some(a) = parse_string(&v.value)
+                .ok()
+                .and_then(|b| Accelerator::parse(&b).ok())
+            else {
+                continue;
+            };
+            let name = custom_names.get(v.schema.as_str()).cloned();
+            add(
+                a,
+                format!("run {}", name.unwrap_or_else(|| "custom shortcut".into())),
+            );
+        }
+    }
+    keys
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; background: #f6f5f4; color: #241f31; }
+.row { display: flex; gap: 4px; margin-bottom: 4px; }
+.key { width: 56px; height: 48px; border: 1px solid #c0bfbc; border-radius: 6px;
+       background: white; display: flex; align-items: center; justify-content: center;
+       position: relative; cursor: default; }
+.key.bound { background: #99c1f1; }
+.key.dim { opacity: 0.35; }
+.key .tip { display: none; position: absolute; top: 52px; left: 0; z-index: 1;
+            background: #241f31; color: white; padding: 6px; border-radius: 4px;
+            white-space: nowrap; font-size: 12px; text-align: left; }
+.key:hover .tip { display: block; }
+#filters button.active { font-weight: bold; }
+"#;
+
+// highlight only the keys with a binding for the picked modifiers
+const SCRIPT: &str = r#"
+const keys = JSON.parse(document.getElementById('bindings').textContent);
+function filter(modifiers) {
+  document.querySelectorAll('#filters button').forEach(b =>
+    b.classList.toggle('active', b.dataset.modifiers === modifiers));
+  document.querySelectorAll('.key').forEach(k => {
+    const list = keys[k.dataset.key] || [];
+    const hit = list.some(b => modifiers === '' || b.modifiers === modifiers);
+    k.classList.toggle('dim', !hit);
+  });
+}
+document.querySelectorAll('#filters button').forEach(b =>
+  b.addEventListener('click', () => filter(b.dataset.modifiers)));
+"#;
+
+pub fn html(title: &str, profile: &Profile) -> String {
+    let keys = bindings(profile);
+    let mut combos: Vec<&str> = keys
+        .values()
+        .flatten()
+        .map(|b| b.modifiers.as_str())
+        .collect();
+    combos.sort_unstable();
+    combos.dedup();
+
+    let mut body = String::new();
+    body.push_str("<div id=\"filters\"><button data-modifiers=\"\" class=\"active\">All</button>");
+    for combo in &combos {
+        body.push_str(&format!(
+            "<button data-modifiers=\"{0}\">{0}</button>",
+            escape(combo)
+        ));
+    }
+    body.push_str("</div>\n");
+
+    for row in ROWS {
+        body.push_str("<div class=\"row\">");
+        for key in row {
+            let id = key.to_lowercase();
+            let bound = keys.get(&id);
+            let class = if bound.is_some() { "key bound" } else { "key" };
+            body.push_str(&format!(
+                "<div class=\"{class}\" data-key=\"{}\">{}",
+                escape(&id),
+                escape(key_label(key))
+            ));
+            if let Some(list) = bound {
+                body.push_str("<div class=\"tip\">");
+                for b in list {
+                    body.push_str(&format!(
+                        "{} → {}<br>",
+                        escape(&b.modifiers),
+                        escape(&b.action)
+                    ));
+                }
+                body.push_str("</div>");
+            }
+            body.push_str("</div>");
+        }
+        body.push_str("</div>\n");
+    }
+
+    // `</` can't appear inside the script element
+    let json = serde_json::to_string(&keys)
+        .unwrap_or_else(|_| "{}".into())
+        .replace("</", "<\\/");
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\
+         <style>{STYLE}</style></head><body>\n<h1>{title}</h1>\n{body}\
+         <script type=\"application/json\" id=\"bindings\">{json}</script>\
+         <script>{SCRIPT}</script>\n</body></html>\n",
+        title = escape(title),
+    )
+}
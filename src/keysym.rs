@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+const KEYSYMS: &str = include_str!("../gnome-keysyms.txt");
+
+/// Maps typed characters to X keysym names and back, e.g. `!` <-> `exclam`.
+#[derive(Debug, Clone, Default)]
+pub struct Keysyms {
+    key_to_keysym: HashMap<String, String>,
+    keysym_to_key: HashMap<String, String>,
+    keysym_to_code: HashMap<String, u32>,
+}
+
+impl Keysyms {
+    pub fn load() -> Self {
+        let mut keysyms = Self::default();
+        for line in KEYSYMS.split('\n') {
+            let s: Vec<&str> = line.split_whitespace().collect();
+            if let Some(code) = s
+                .get(1)
+                .and_then(|c| u32::from_str_radix(c.strip_prefix("0x")?, 16).ok())
+            {
+                keysyms.keysym_to_code.insert(s[0].into(), code);
+            }
+            if s.len() >= 3 {
+                keysyms.key_to_keysym.insert(s[2].into(), s[0].into());
+                keysyms.keysym_to_key.insert(s[0].into(), s[2].into());
+            }
+        }
+        keysyms
+    }
+
+    pub fn keysym(&self, key: &str) -> Option<&str> {
+        self.key_to_keysym.get(key).map(String::as_str)
+    }
+
+    pub fn key(&self, keysym: &str) -> Option<&str> {
+        self.keysym_to_key.get(keysym).map(String::as_str)
+    }
+
+    /// The numeric X keysym of a name in the table, e.g. `exclam` -> 0x21.
+    pub fn code(&self, keysym: &str) -> Option<u32> {
+        self.keysym_to_code.get(keysym).copied()
+    }
+}
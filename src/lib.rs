@@ -0,0 +1,56 @@
+//! Settings backend of the GNOME workspace shortcuts menu, kept apart from
+//! the egui frontend so it can be tested without a display.
+
+pub mod accelerator;
+pub mod auto_move;
+pub mod backend;
+pub mod backup;
+pub mod conflicts;
+pub mod custom_shortcuts;
+pub mod dconf;
+pub mod desktop_entries;
+pub mod diagnostics;
+pub mod doctor;
+pub mod drift;
+pub mod enforce;
+pub mod environment;
+pub mod export;
+pub mod extensions;
+pub mod gestures;
+pub mod gsettings;
+pub mod help;
+pub mod history;
+pub mod hooks;
+pub mod import;
+pub mod isolation;
+pub mod key_location;
+pub mod keyboard;
+pub mod keyboard_map;
+pub mod keysym;
+pub mod lint;
+pub mod macros;
+pub mod migration;
+pub mod model;
+#[cfg(feature = "portal")]
+pub mod portal;
+pub mod preferences;
+pub mod preset;
+pub mod profile;
+#[cfg(feature = "native-settings")]
+pub mod settings;
+pub mod share;
+pub mod shortcut_dump;
+pub mod switcher_popup;
+pub mod transaction;
+pub mod undo;
+#[cfg(feature = "update-check")]
+pub mod update;
+pub mod watch;
+#[cfg(feature = "widget")]
+pub mod widget;
+pub mod worker;
+pub mod workspace;
+pub mod workspace_layout;
+#[cfg(feature = "x11")]
+pub mod x11;
+pub mod xdg;
@@ -0,0 +1,172 @@
+//! Style checks over the workspace shortcuts as a whole. Unlike the doctor
+//! checks nothing here is broken, the scheme is just harder to remember.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    accelerator::{Accelerator, Modifiers},
+    doctor::{Check, Fix, Severity},
+    gsettings::WM_KEYBINDINGS_SCHEMA,
+    profile::Profile,
+};
+
+// GNOME defaults outside the managed keys, (accelerator, key, description)
+const GNOME_DEFAULTS: &[(&str, &str, &str)] = &[
+    ("<Super>a", "toggle-application-view", "show all apps"),
+    ("<Super>s", "toggle-quick-settings", "quick settings"),
+    ("<Super>v", "toggle-message-tray", "notifications"),
+    ("<Super>l", "screensaver", "lock screen"),
+    ("<Super>h", "minimize", "hide window"),
+    ("<Super>Up", "maximize", "maximize window"),
+    ("<Super>Down", "unmaximize", "restore window"),
+    ("<Super>Tab", "switch-applications", "switch applications"),
+    ("<Alt>Tab", "switch-applications", "switch applications"),
+    ("<Alt>F4", "close", "close window"),
+    ("<Super>space", "switch-input-source", "switch input source"),
+    (
+        "<Super>Page_Up",
+        "switch-to-workspace-left",
+        "previous workspace",
+    ),
+    (
+        "<Super>Page_Down",
+        "switch-to-workspace-right",
+        "next workspace",
+    ),
+];
+
+// (key, first accelerator) of the bound switch-to-/move-to-workspace-1..10
+fn bound(profile: &Profile, prefix: &str) -> Vec<(String, Accelerator)> {
+    (1..=10)
+        .filter_map(|i| {
+            let key = format!("{prefix}-{i}");
+            let value = profile.get(WM_KEYBINDINGS_SCHEMA, &key)?;
+            let accelerator = Accelerator::from_gsettings(value).ok()??;
+            Some((key, accelerator))
+        })
+        .collect()
+}
+
+fn set(key: &str, accelerator: &Accelerator) -> Fix {
+    Fix::Set {
+        schema: WM_KEYBINDINGS_SCHEMA.into(),
+        key: key.into(),
+        value: accelerator.to_gsettings(),
+    }
+}
+
+fn check(name: &str, detail: String) -> Check {
+    Check {
+        name: name.into(),
+        severity: Severity::Warning,
+        detail,
+        fix: None,
+    }
+}
+
+/// Lint the workspace shortcuts of `profile`. Every finding is a warning,
+/// most come with a fix.
+pub fn run(profile: &Profile) -> Vec<Check> {
+    let mut checks = vec![];
+    for prefix in ["switch-to-workspace", "move-to-workspace"] {
+        checks.extend(mixed_modifiers(&bound(profile, prefix)));
+    }
+    checks.extend(missing_moves(profile));
+    checks.extend(shadowed_defaults(profile));
+    checks
+}
+
+fn mixed_modifiers(bindings: &[(String, Accelerator)]) -> Vec<Check> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for (_, a) in bindings {
+        *counts.entry(a.modifiers.format()).or_default() += 1;
+    }
+    if counts.len() < 2 {
+        return vec![];
+    }
+    // ties go to the modifiers of the lower workspace
+    let most = counts.values().copied().max().unwrap_or_default();
+    let Some(majority) = bindings
+        .iter()
+        .map(|(_, a)| a.modifiers)
+        .find(|m| counts[&m.format()] == most)
+    else {
+        return vec![];
+    };
+
+    bindings
+        .iter()
+        .filter(|(_, a)| a.modifiers != majority)
+        .map(|(key, a)| {
+            let fixed = Accelerator::new(majority, &a.key);
+            let detail = format!(
+                "{key} uses {}, most others use {}",
+                a.format(),
+                majority.format()
+            );
+            Check {
+                fix: Some(set(key, &fixed)),
+                ..check("mixed modifiers", detail)
+            }
+        })
+        .collect()
+}
+
+fn missing_moves(profile: &Profile) -> Vec<Check> {
+    let moves: BTreeMap<String, Accelerator> =
+        bound(profile, "move-to-workspace").into_iter().collect();
+    let mut checks = vec![];
+    for (key, a) in bound(profile, "switch-to-workspace") {
+        if !a.key.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let move_key = key.replacen("switch-to", "move-to", 1);
+        if moves.contains_key(&move_key) {
+            continue;
+        }
+        let mut c = check(
+            "missing move",
+            format!("{key} is on {} but {move_key} is unbound", a.format()),
+        );
+        // Shift is the usual way to turn a switch into a move
+        if !a.modifiers.shift {
+            let modifiers = Modifiers {
+                shift: true,
+                ..a.modifiers
+            };
+            c.fix = Some(set(&move_key, &Accelerator::new(modifiers, &a.key)));
+        }
+        checks.push(c);
+    }
+    checks
+}
+
+fn shadowed_defaults(profile: &Profile) -> Vec<Check> {
+    let defaults: Vec<(Accelerator, &str, &str)> = GNOME_DEFAULTS
+        .iter()
+        .filter_map(|(a, key, what)| Some((Accelerator::parse(a).ok()?, *key, *what)))
+        .collect();
+    let mut checks = vec![];
+    for prefix in ["switch-to-workspace", "move-to-workspace"] {
+        for (key, a) in bound(profile, prefix) {
+            // compared parsed, gsettings values may spell modifiers differently
+            let Some((_, default_key, what)) = defaults.iter().find(|(d, _, _)| *d == a) else {
+                continue;
+            };
+            checks.push(Check {
+                fix: Some(Fix::Unbind {
+                    schema: WM_KEYBINDINGS_SCHEMA.into(),
+                    key: key.clone(),
+                }),
+                ..check(
+                    "shadows default",
+                    format!(
+                        "{key} uses {}, GNOME's default for {default_key} ({what})",
+                        a.format()
+                    ),
+                )
+            });
+        }
+    }
+    checks
+}
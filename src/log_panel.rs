@@ -0,0 +1,182 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eframe::egui::{self, Ui};
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub elapsed: Duration,
+    pub level: Level,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl LogEntry {
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+type Entries = Arc<Mutex<VecDeque<LogEntry>>>;
+
+/// tracing layer keeping the most recent events in memory for the log panel
+pub struct LogCollector {
+    start: Instant,
+    entries: Entries,
+}
+
+impl LogCollector {
+    pub fn new() -> (Self, Entries) {
+        let entries = Entries::default();
+        let collector = Self {
+            start: Instant::now(),
+            entries: entries.clone(),
+        };
+        (collector, entries)
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.into();
+        } else {
+            self.fields.push((field.name().into(), value.into()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .push((field.name().into(), format!("{:?}", value)));
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogCollector {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(LogEntry {
+            elapsed: self.start.elapsed(),
+            level: *event.metadata().level(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+pub struct LogPanel {
+    entries: Entries,
+    // entries more verbose than this are hidden
+    max_level: Level,
+    key_filter: String,
+}
+
+impl LogPanel {
+    pub fn new(entries: Entries) -> Self {
+        Self {
+            entries,
+            max_level: Level::INFO,
+            key_filter: "".into(),
+        }
+    }
+
+    /// All collected entries, one per line, regardless of the filters.
+    pub fn to_text(&self) -> String {
+        let entries = self.entries.lock().unwrap();
+        let mut text = String::new();
+        for entry in entries.iter() {
+            text.push_str(&format!(
+                "{:>8.3}s {} {}",
+                entry.elapsed.as_secs_f32(),
+                entry.level.as_str(),
+                entry.message
+            ));
+            for (n, v) in &entry.fields {
+                text.push_str(&format!(" {n}={v}"));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Level");
+            egui::ComboBox::from_id_source("log_level")
+                .selected_text(self.max_level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LEVELS {
+                        ui.selectable_value(&mut self.max_level, level, level.as_str());
+                    }
+                });
+            ui.label("Key");
+            ui.text_edit_singleline(&mut self.key_filter);
+            if ui.button("Clear").clicked() {
+                self.entries.lock().unwrap().clear();
+            }
+        });
+
+        let entries = self.entries.lock().unwrap();
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Grid::new("log_entries").striped(true).show(ui, |ui| {
+                    let visible = entries.iter().filter(|e| {
+                        e.level <= self.max_level
+                            && (self.key_filter.is_empty()
+                                || e.field("key").is_some_and(|k| k.contains(&self.key_filter)))
+                    });
+                    for entry in visible {
+                        ui.monospace(format!("{:>8.3}s", entry.elapsed.as_secs_f32()));
+                        ui.monospace(entry.level.as_str());
+                        ui.label(&entry.message);
+                        let fields: Vec<String> = entry
+                            .fields
+                            .iter()
+                            .map(|(n, v)| format!("{n}={v}"))
+                            .collect();
+                        ui.monospace(fields.join(" "));
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,234 @@
+//! Recorded sequences of setting operations, replayed in order. Unlike a
+//! profile a macro keeps the order of its steps, and a step can be made
+//! conditional by adding a `when` to it in the macro file.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::SettingsBackend,
+    backup,
+    gsettings::{same_value, GSettings, SHELL_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA},
+    xdg::config_dir,
+};
+
+const MACRO_DIR: &str = "macros";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum Operation {
+    Set {
+        schema: String,
+        key: String,
+        value: String,
+    },
+    Unbind {
+        schema: String,
+        key: String,
+    },
+    SetWorkspaceCount {
+        count: usize,
+    },
+    // switch-to-application-1..9
+    ClearAppShortcuts,
+}
+
+impl Operation {
+    /// (schema, key) of every key the operation writes.
+    pub fn targets(&self) -> Vec<(String, String)> {
+        match self {
+            Operation::Set { schema, key, .. } | Operation::Unbind { schema, key } => {
+                vec![(schema.clone(), key.clone())]
+            }
+            Operation::SetWorkspaceCount { .. } => {
+                vec![(WM_PREFERENCES_SCHEMA.into(), "num-workspaces".into())]
+            }
+            Operation::ClearAppShortcuts => (1..10)
+                .map(|id| {
+                    (
+                        SHELL_KEYBINDINGS_SCHEMA.into(),
+                        format!("switch-to-application-{id}"),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Operation::Set { key, value, .. } => format!("set {key} to {value}"),
+            Operation::Unbind { key, .. } => format!("unbind {key}"),
+            Operation::SetWorkspaceCount { count } => format!("set {count} workspaces"),
+            Operation::ClearAppShortcuts => "clear the switch-to-application shortcuts".into(),
+        }
+    }
+
+    // old values are backed up like for any other write
+    fn run(&self, backend: &dyn SettingsBackend) -> Result<()> {
+        for (schema, key) in self.targets() {
+            backup::backup_value(&schema, &key, &backend.get(&schema, &key)?)?;
+        }
+        match self {
+            Operation::Set { schema, key, value } => backend.set_confirmed(schema, key, value),
+            Operation::Unbind { schema, key } => backend.unbind(schema, key),
+            Operation::SetWorkspaceCount { count } => {
+                backend.set_confirmed(WM_PREFERENCES_SCHEMA, "num-workspaces", &count.to_string())
+            }
+            Operation::ClearAppShortcuts => {
+                for (schema, key) in self.targets() {
+                    backend.unbind(&schema, &key)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Condition {
+    Equals {
+        schema: String,
+        key: String,
+        value: String,
+    },
+    Unbound {
+        schema: String,
+        key: String,
+    },
+    // e.g. only touch an extension's settings when it's installed
+    HasSchema {
+        schema: String,
+    },
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// A key that can't be read counts as not matching.
+    pub fn holds(&self, backend: &dyn SettingsBackend) -> bool {
+        match self {
+            Condition::Equals { schema, key, value } => backend
+                .get(schema, key)
+                .is_ok_and(|current| same_value(&current, value)),
+            Condition::Unbound { schema, key } => backend
+                .get(schema, key)
+                .is_ok_and(|current| GSettings::is_empty_keybinding(&current)),
+            Condition::HasSchema { schema } => backend.has_schema(schema),
+            Condition::Not(condition) => !condition.holds(backend),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    #[serde(flatten)]
+    pub operation: Operation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Replay {
+    // descriptions of the steps that ran, in order
+    pub ran: Vec<String>,
+    // descriptions of the steps whose condition didn't hold
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    pub steps: Vec<Step>,
+}
+
+impl Macro {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            steps: vec![],
+        }
+    }
+
+    pub fn record(&mut self, operation: Operation) {
+        self.steps.push(Step {
+            operation,
+            when: None,
+        });
+    }
+
+    /// Where a macro called `name` is saved.
+    pub fn path_of(name: &str) -> PathBuf {
+        config_dir().join(MACRO_DIR).join(format!("{name}.json"))
+    }
+
+    /// Names of the saved macros, sorted.
+    pub fn list() -> Vec<String> {
+        let Ok(entries) = fs::read_dir(config_dir().join(MACRO_DIR)) else {
+            return vec![];
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|e| {
+                let path = e.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// A saved macro by name, or a macro file when `name_or_path` looks
+    /// like a path.
+    pub fn find(name_or_path: &str) -> Result<Self> {
+        if name_or_path.contains('/') || name_or_path.ends_with(".json") {
+            return Self::load(Path::new(name_or_path));
+        }
+        let path = Self::path_of(name_or_path);
+        if !path.exists() {
+            bail!("there is no macro called '{name_or_path}'");
+        }
+        Self::load(&path)
+    }
+
+    pub fn save(&self) -> Result<PathBuf> {
+        if self.name.is_empty() || self.name.contains('/') {
+            bail!("'{}' can't be used as a macro name", self.name);
+        }
+        let path = Self::path_of(&self.name);
+        fs::create_dir_all(config_dir().join(MACRO_DIR))?;
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Run the steps in order, stopping at the first failing one. Conditions
+    /// are checked right before their step, so they see what earlier steps
+    /// wrote.
+    pub fn replay(&self, backend: &dyn SettingsBackend) -> Result<Replay> {
+        let mut replay = Replay::default();
+        for (i, step) in self.steps.iter().enumerate() {
+            let description = step.operation.describe();
+            if step.when.as_ref().is_some_and(|c| !c.holds(backend)) {
+                replay.skipped.push(description);
+                continue;
+            }
+            step.operation
+                .run(backend)
+                .with_context(|| format!("step {} ({description})", i + 1))?;
+            replay.ran.push(description);
+        }
+        Ok(replay)
+    }
+}
@@ -0,0 +1,127 @@
+//! Taking over a scheme exported on another GNOME version. Keys GNOME
+//! renamed or moved since are written to their new place, keys neither
+//! this GNOME nor [`RENAMED`] knows are reported instead of failing the
+//! whole import.
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    backend::SettingsBackend,
+    custom_shortcuts::MEDIA_KEYS_SCHEMA,
+    gsettings::{
+        format_string_array, parse_string, parse_string_array, same_value, MUTTER_SCHEMA,
+        SHELL_KEYBINDINGS_SCHEMA,
+    },
+    import,
+    keyboard::KEYBOARD_SCHEMA,
+    profile::Profile,
+    transaction::Transaction,
+};
+
+const OLD_KEYBOARD_SCHEMA: &str = "org.gnome.settings-daemon.peripherals.keyboard";
+
+// (schema, key)
+type Key = (&'static str, &'static str);
+
+/// (old, new) of the keys that moved.
+pub const RENAMED: [(Key, Key); 8] = [
+    // GNOME 3.14 moved the keyboard settings to gsettings-desktop-schemas
+    ((OLD_KEYBOARD_SCHEMA, "repeat"), (KEYBOARD_SCHEMA, "repeat")),
+    ((OLD_KEYBOARD_SCHEMA, "delay"), (KEYBOARD_SCHEMA, "delay")),
+    (
+        (OLD_KEYBOARD_SCHEMA, "repeat-interval"),
+        (KEYBOARD_SCHEMA, "repeat-interval"),
+    ),
+    // the shell overrides of mutter settings are gone
+    (
+        ("org.gnome.shell.overrides", "dynamic-workspaces"),
+        (MUTTER_SCHEMA, "dynamic-workspaces"),
+    ),
+    // GNOME 42 moved the screenshot keys to the shell's screenshot UI
+    (
+        (MEDIA_KEYS_SCHEMA, "screenshot"),
+        (SHELL_KEYBINDINGS_SCHEMA, "screenshot"),
+    ),
+    (
+        (MEDIA_KEYS_SCHEMA, "window-screenshot"),
+        (SHELL_KEYBINDINGS_SCHEMA, "screenshot-window"),
+    ),
+    (
+        (MEDIA_KEYS_SCHEMA, "area-screenshot"),
+        (SHELL_KEYBINDINGS_SCHEMA, "show-screenshot-ui"),
+    ),
+    (
+        (MEDIA_KEYS_SCHEMA, "screencast"),
+        (SHELL_KEYBINDINGS_SCHEMA, "show-screen-recording-ui"),
+    ),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Migration {
+    pub transaction: Transaction,
+    // ("schema key" in the file, "schema key" written) of moved keys
+    pub renamed: Vec<(String, String)>,
+    // "schema key" of values that have no place on this GNOME
+    pub unmappable: Vec<String>,
+    // values of settings this app doesn't manage
+    pub skipped: usize,
+}
+
+/// Read an exported scheme in any of the import formats and plan it.
+pub fn migrate_file(path: &Path, backend: &dyn SettingsBackend) -> Result<Migration> {
+    let data = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    let (_, profile) = import::read(path, &data)?;
+    Ok(plan(&profile, backend))
+}
+
+/// Stage the values of `profile` the backend doesn't already have, moved
+/// to their new key where this GNOME lacks the old one.
+pub fn plan(profile: &Profile, backend: &dyn SettingsBackend) -> Migration {
+    let mut migration = Migration::default();
+    for v in &profile.values {
+        let moved = RENAMED
+            .iter()
+            .find(|(old, _)| *old == (v.schema.as_str(), v.key.as_str()))
+            .map(|(_, new)| *new);
+        if moved.is_none() && !import::is_managed(&v.schema, &v.key) {
+            migration.skipped += 1;
+            continue;
+        }
+        let mut value = v.value.clone();
+        let (schema, key, current) = match backend.get(&v.schema, &v.key) {
+            Ok(current) => (v.schema.as_str(), v.key.as_str(), current),
+            Err(e) => {
+                let found = moved
+                    .and_then(|(schema, key)| Some((schema, key, backend.get(schema, key).ok()?)));
+                let Some(found) = found else {
+                    tracing::info!(v.schema, v.key, "not migrating: {:#}", e);
+                    migration.unmappable.push(format!("{} {}", v.schema, v.key));
+                    continue;
+                };
+                migration.renamed.push((
+                    format!("{} {}", v.schema, v.key),
+                    format!("{} {}", found.0, found.1),
+                ));
+                // media keys used to be a single accelerator string
+                if parse_string_array(&value).is_err() && parse_string_array(&found.2).is_ok() {
+                    if let Ok(single) = parse_string(&value) {
+                        let items: Vec<String> =
+                            [single].into_iter().filter(|s| !s.is_empty()).collect();
+                        value = format_string_array(&items);
+                    }
+                }
+                found
+            }
+        };
+        if same_value(&current, &value) {
+            continue;
+        }
+        if let Err(e) = migration.transaction.stage(backend, schema, key, &value) {
+            tracing::warn!(schema, key, "not migrating: {:#}", e);
+            migration.unmappable.push(format!("{schema} {key}"));
+        }
+    }
+    migration
+}
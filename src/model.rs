@@ -0,0 +1,699 @@
+//! What the editor window edits, without drawing any of it: the keybinding
+//! rows, the switch-to-application values they're checked against and the
+//! data behind the dialogs. The egui frontend draws this and turns input
+//! into calls on it, so the logic can be tested without a display.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+
+use crate::{
+    accelerator::{Accelerator, Modifiers},
+    backend::SettingsBackend,
+    conflicts::ConflictIndex,
+    export::{self, EXPORTERS},
+    gsettings::{
+        format_string_array, parse_string_array, same_value, GSettings, MUTTER_KEYBINDINGS_SCHEMA,
+        SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA,
+    },
+    key_location::{self, LayoutVariant},
+    keysym::Keysyms,
+    migration::Migration,
+    profile::{
+        APP_KEYBINDINGS, MAXIMIZE_KEYBINDINGS, MONITOR_KEYBINDINGS, NAVIGATION_KEYBINDINGS,
+        SYSTEM_KEYBINDINGS, TILING_KEYBINDINGS, WINDOW_KEYBINDINGS,
+    },
+    transaction::Transaction,
+};
+
+// mutter refuses to create more than 36 workspaces
+pub const MIN_WORKSPACES: usize = 1;
+pub const MAX_WORKSPACES: usize = 36;
+
+// (key, label) of keybinding rows
+pub type RowList = &'static [(&'static str, &'static str)];
+
+// (heading, hint, schema, rows) of the keybindings shown below the
+// workspace rows
+pub const ROW_GROUPS: [(&str, &str, &str, RowList); 7] = [
+    (
+        "Navigation",
+        "Relative to the current workspace, last is the one at the end.",
+        WM_KEYBINDINGS_SCHEMA,
+        &NAVIGATION_KEYBINDINGS,
+    ),
+    (
+        "Windows",
+        "Stacking and size of windows.",
+        WM_KEYBINDINGS_SCHEMA,
+        &WINDOW_KEYBINDINGS,
+    ),
+    (
+        "Maximize",
+        "Maximized windows keep their decorations, fullscreen ones cover the top bar.",
+        WM_KEYBINDINGS_SCHEMA,
+        &MAXIMIZE_KEYBINDINGS,
+    ),
+    (
+        "Tiling",
+        "Toggling again puts the window back to its previous size.",
+        MUTTER_KEYBINDINGS_SCHEMA,
+        &TILING_KEYBINDINGS,
+    ),
+    (
+        "Monitors",
+        "Moves the focused window to the neighbouring monitor, keeping its size.",
+        WM_KEYBINDINGS_SCHEMA,
+        &MONITOR_KEYBINDINGS,
+    ),
+    (
+        "System",
+        "Moves the keyboard focus between the top bar, the dock and windows.",
+        WM_KEYBINDINGS_SCHEMA,
+        &SYSTEM_KEYBINDINGS,
+    ),
+    (
+        "Applications",
+        "Opens the app pinned to the dash at that position, wins over workspace rows using the same keys.",
+        SHELL_KEYBINDINGS_SCHEMA,
+        &APP_KEYBINDINGS,
+    ),
+];
+
+pub fn parse_number_of_workspaces(s: &str) -> Result<usize, String> {
+    let num: usize = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", s.trim()))?;
+    if !(MIN_WORKSPACES..=MAX_WORKSPACES).contains(&num) {
+        return Err(format!(
+            "must be between {MIN_WORKSPACES} and {MAX_WORKSPACES}"
+        ));
+    }
+    Ok(num)
+}
+
+// result of the last write from this row
+#[derive(Debug, Clone, Default)]
+pub enum RowStatus {
+    #[default]
+    None,
+    Applied,
+    Failed(String),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RowEvent {
+    // modifier or key edited, nothing written yet
+    Changed,
+    // the label was clicked, for selecting rows
+    Clicked,
+    Overwrite,
+    Unbind,
+}
+
+pub(crate) const LOCKED_NOTE: &str = "managed by your administrator";
+
+/// A row's write, taken out of the row so it can run off the UI thread.
+#[derive(Debug, Clone)]
+pub struct RowWrite {
+    pub schema: String,
+    pub key: String,
+    // None unbinds the key
+    pub value: Option<String>,
+}
+
+impl RowWrite {
+    /// Write and read the value back.
+    pub fn run(&self, backend: &dyn SettingsBackend) -> Result<String> {
+        match &self.value {
+            Some(value) => backend.set_confirmed(&self.schema, &self.key, value)?,
+            None => backend.unbind(&self.schema, &self.key)?,
+        }
+        backend.get(&self.schema, &self.key)
+    }
+}
+
+// the accelerators of a gsettings string array, and the entries that don't
+// parse as one
+fn split_entries(value: &str) -> Result<(Vec<Accelerator>, Vec<String>)> {
+    let mut accelerators = vec![];
+    let mut foreign = vec![];
+    for item in parse_string_array(value)? {
+        if item.is_empty() {
+            continue;
+        }
+        match Accelerator::parse(&item) {
+            Ok(accelerator) => accelerators.push(accelerator),
+            Err(_) => foreign.push(item),
+        }
+    }
+    Ok((accelerators, foreign))
+}
+
+/// One editable keybinding of any schema: the current value, the modifier
+/// and key being edited and the result of the last write.
+#[derive(Debug, Clone)]
+pub struct KeybindingRow {
+    pub schema: String,
+    pub gsettings_key: String,
+    pub label: String,
+    pub gsettings_value: String,
+    pub modifiers: Modifiers,
+    pub keybinding: String,
+    // the bindings after the first, written back behind the edited one
+    pub extra: Vec<Accelerator>,
+    // entries GNOME takes but the parser doesn't, e.g. with <Hyper>, kept
+    // verbatim and written back after the others
+    pub foreign: Vec<String>,
+    pub converted_keybinding: String,
+    pub status: RowStatus,
+    // (icon, hover text) shown in front of the label
+    pub badge: Option<(String, String)>,
+    // highlights the label, selection itself is up to the caller
+    pub selected: bool,
+    // names the other keys bound to the same accelerator, the label turns
+    // red while set
+    pub conflict: Option<String>,
+    // where keys of the binding sit when that differs between ISO and
+    // ANSI keyboards
+    pub location: Option<String>,
+    // the next key press goes into the edit fields
+    pub capturing: bool,
+    // locked by an administrator, shown but not editable
+    pub locked: bool,
+    // a read or write of the key is still running in the background
+    pub pending: bool,
+}
+
+impl KeybindingRow {
+    pub fn new(schema: &str, gsettings_key: &str, label: &str) -> Self {
+        Self {
+            schema: schema.into(),
+            gsettings_key: gsettings_key.into(),
+            label: label.into(),
+            gsettings_value: "".into(),
+            modifiers: Modifiers::default(),
+            keybinding: "".into(),
+            extra: vec![],
+            foreign: vec![],
+            converted_keybinding: "".into(),
+            status: RowStatus::None,
+            badge: None,
+            selected: false,
+            conflict: None,
+            location: None,
+            capturing: false,
+            locked: false,
+            pending: false,
+        }
+    }
+
+    /// Take over a value read from gsettings into the edit fields.
+    pub fn load(&mut self, gsettings_value: String, keysyms: &Keysyms) {
+        self.gsettings_value = gsettings_value;
+
+        let (mut accelerators, foreign) = match split_entries(&self.gsettings_value) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!(key = self.gsettings_key, "can't parse keybinding: {:#}", e);
+                (vec![], vec![])
+            }
+        };
+        if !foreign.is_empty() {
+            tracing::info!(
+                key = self.gsettings_key,
+                ?foreign,
+                "keeping bindings that can't be edited here"
+            );
+        }
+        self.foreign = foreign;
+        let first = (!accelerators.is_empty()).then(|| accelerators.remove(0));
+        self.extra = accelerators;
+        self.edit(first.as_ref(), keysyms);
+    }
+
+    // put `accelerator` into the edit fields, None clears them
+    fn edit(&mut self, accelerator: Option<&Accelerator>, keysyms: &Keysyms) {
+        let (modifiers, keysym) = match accelerator {
+            Some(a) => (a.modifiers, a.key.as_str()),
+            None => (Modifiers::default(), ""),
+        };
+        self.modifiers = modifiers;
+
+        self.keybinding = match keysyms.key(keysym) {
+            Some(key) => key.to_string(),
+            None => keysym.to_string(),
+        };
+        self.update_converted_keybinding(keysyms);
+    }
+
+    // the binding in the edit fields, None while no key is entered
+    fn edited(&self, keysyms: &Keysyms) -> Option<Accelerator> {
+        if self.is_incomplete_edit() {
+            return None;
+        }
+        let keysym = match keysyms.keysym(&self.keybinding) {
+            Some(keysym) => keysym.to_string(),
+            None => self.keybinding.to_string(),
+        };
+        Some(Accelerator::new(self.modifiers, &keysym))
+    }
+
+    /// Read the current value from the backend.
+    pub fn reload(&mut self, backend: &dyn SettingsBackend, keysyms: &Keysyms) -> Result<()> {
+        let value = backend.get(&self.schema, &self.gsettings_key)?;
+        self.load(value, keysyms);
+        Ok(())
+    }
+
+    /// Write what an Overwrite or Unbind event asks for and read the result
+    /// back. Other events are ignored.
+    pub fn apply(&mut self, event: RowEvent, backend: &dyn SettingsBackend, keysyms: &Keysyms) {
+        if let Some(write) = self.write_for(event) {
+            let res = write.run(backend);
+            self.finish_write(res, keysyms);
+        }
+    }
+
+    /// The write an Overwrite or Unbind event asks for, which doesn't need
+    /// the row to run. None for other events and for a locked row, which
+    /// is marked failed instead.
+    pub fn write_for(&mut self, event: RowEvent) -> Option<RowWrite> {
+        if self.locked {
+            self.status = RowStatus::Failed(format!("{} is {LOCKED_NOTE}", self.gsettings_key));
+            return None;
+        }
+        let value = match event {
+            RowEvent::Changed | RowEvent::Clicked => return None,
+            RowEvent::Overwrite => Some(self.converted_keybinding.clone()),
+            RowEvent::Unbind => None,
+        };
+        Some(RowWrite {
+            schema: self.schema.clone(),
+            key: self.gsettings_key.clone(),
+            value,
+        })
+    }
+
+    /// Take over the value a [`RowWrite`] read back.
+    pub fn finish_write(&mut self, res: Result<String>, keysyms: &Keysyms) {
+        let res = res.map(|value| self.load(value, keysyms));
+        self.set_result(res);
+    }
+
+    pub fn set_result(&mut self, res: Result<()>) {
+        self.status = match res {
+            Ok(()) => RowStatus::Applied,
+            Err(e) => RowStatus::Failed(format!("{:#}", e)),
+        };
+    }
+
+    pub(crate) fn update_converted_keybinding(&mut self, keysyms: &Keysyms) {
+        let entries: Vec<String> = self
+            .edited(keysyms)
+            .iter()
+            .chain(&self.extra)
+            .map(Accelerator::format)
+            .chain(self.foreign.iter().cloned())
+            .collect();
+        self.converted_keybinding = format_string_array(&entries);
+    }
+
+    /// Keep the edited binding and start editing a new one in front of it.
+    pub fn add_binding(&mut self, keysyms: &Keysyms) {
+        if let Some(edited) = self.edited(keysyms) {
+            self.extra.insert(0, edited);
+        }
+        self.edit(None, keysyms);
+    }
+
+    /// Edit the extra binding `i`, the edited one takes its place.
+    pub fn edit_extra(&mut self, i: usize, keysyms: &Keysyms) {
+        let picked = self.extra.remove(i);
+        if let Some(edited) = self.edited(keysyms) {
+            self.extra.insert(i, edited);
+        }
+        self.edit(Some(&picked), keysyms);
+    }
+
+    pub fn remove_extra(&mut self, i: usize, keysyms: &Keysyms) {
+        self.extra.remove(i);
+        self.update_converted_keybinding(keysyms);
+    }
+
+    /// Put a captured combination into the edit fields.
+    pub fn assign(&mut self, modifiers: Modifiers, key: &str, keysyms: &Keysyms) {
+        if self.locked {
+            return;
+        }
+        self.modifiers = modifiers;
+        self.keybinding = key.into();
+        self.update_converted_keybinding(keysyms);
+    }
+
+    /// Whether the edited bindings differ from the value last read.
+    pub fn is_modified(&self) -> bool {
+        let all = |value: &str| split_entries(value).ok();
+        all(&self.gsettings_value) != all(&self.converted_keybinding)
+    }
+
+    // an empty key would be written as ['<Super>'] or [''], neither of which
+    // GNOME understands
+    pub fn is_incomplete(&self) -> bool {
+        self.is_incomplete_edit() && self.extra.is_empty() && self.foreign.is_empty()
+    }
+
+    pub(crate) fn is_incomplete_edit(&self) -> bool {
+        self.keybinding.trim().is_empty()
+    }
+}
+
+/// The rows by position and the workspace settings they depend on.
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    pub rows: BTreeMap<usize, KeybindingRow>,
+    // switch-to-application-N by N, as last read
+    pub app_keybindings: BTreeMap<u32, String>,
+    // GNOME's values of the application rows, read once for re-enabling
+    pub app_defaults: BTreeMap<String, String>,
+    // (schema, key) an administrator locked, left out of every write
+    pub locked_keys: Vec<(String, String)>,
+    // as last read, None if it couldn't be
+    pub workspace_count: Option<usize>,
+    pub dynamic_workspaces: bool,
+    // every keybinding with the rows' unwritten edits in place of their
+    // value, and the value each row was last put in with
+    index: ConflictIndex,
+    indexed: BTreeMap<usize, String>,
+    layout: Option<LayoutVariant>,
+    // the conflict and location notes of the rows are out of date
+    stale: bool,
+}
+
+impl Model {
+    /// Every row the editor offers, none of them read yet.
+    pub fn new() -> Self {
+        // (gsettings key suffix, label suffix), last is with the navigation
+        let targets: Vec<(String, String)> = (1..=10)
+            .map(|i| (i.to_string(), format!("workspace {i}")))
+            .collect();
+
+        let rows = targets
+            .iter()
+            .map(|(key, label)| {
+                KeybindingRow::new(
+                    WM_KEYBINDINGS_SCHEMA,
+                    &format!("switch-to-workspace-{key}"),
+                    &format!("Switch to {label}"),
+                )
+            })
+            .chain(targets.iter().map(|(key, label)| {
+                KeybindingRow::new(
+                    WM_KEYBINDINGS_SCHEMA,
+                    &format!("move-to-workspace-{key}"),
+                    &format!("Move window to {label}"),
+                )
+            }))
+            .chain(ROW_GROUPS.iter().flat_map(|(_, _, schema, rows)| {
+                rows.iter()
+                    .map(|(key, label)| KeybindingRow::new(schema, key, label))
+            }));
+        Self {
+            rows: rows.enumerate().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// The schemas of the rows, to list the keys of for
+    /// [`Model::drop_absent_rows`].
+    pub fn row_schemas(&self) -> BTreeSet<String> {
+        self.rows.values().map(|row| row.schema.clone()).collect()
+    }
+
+    // Rows of keys this GNOME version doesn't have are hidden, instead of
+    // showing an error on each. `known` are the keys by schema, schemas
+    // that couldn't be listed keep theirs.
+    pub fn drop_absent_rows(&mut self, known: &BTreeMap<String, Vec<String>>) {
+        self.rows.retain(|_, row| {
+            let keys = known.get(&row.schema).map_or(&[][..], Vec::as_slice);
+            let present = keys.is_empty() || keys.contains(&row.gsettings_key);
+            if !present {
+                tracing::info!(
+                    row.schema,
+                    row.gsettings_key,
+                    "hiding row, the key doesn't exist"
+                );
+            }
+            present
+        });
+    }
+
+    pub fn set_locked_keys(&mut self, locked_keys: Vec<(String, String)>) {
+        for row in self.rows.values_mut() {
+            row.locked = locked_keys
+                .iter()
+                .any(|(schema, key)| *schema == row.schema && *key == row.gsettings_key);
+        }
+        self.locked_keys = locked_keys;
+    }
+
+    /// Take over the values read for `(row, schema, key, value)`. Rows
+    /// regenerated since the read was queued are left alone.
+    pub fn take_reads(
+        &mut self,
+        values: Vec<(usize, String, String, Result<String>)>,
+        keysyms: &Keysyms,
+    ) {
+        self.stale = true;
+        for (k, schema, key, value) in values {
+            let Some(row) = self.rows.get_mut(&k) else {
+                continue;
+            };
+            if row.schema != schema || row.gsettings_key != key {
+                continue;
+            }
+            row.pending = false;
+            match value {
+                Ok(value) => row.load(value, keysyms),
+                Err(e) => tracing::debug!(schema, key, "can't read: {:#}", e),
+            }
+        }
+    }
+
+    // name the dash app each switch-to-application row opens and pick up
+    // values written outside the rows, e.g. by the bulk disable
+    pub fn label_app_rows(
+        &mut self,
+        favorites: &[String],
+        names: &BTreeMap<String, String>,
+        keysyms: &Keysyms,
+    ) {
+        self.stale = true;
+        for (i, (key, label)) in APP_KEYBINDINGS.iter().enumerate() {
+            let Some(row) = self
+                .rows
+                .values_mut()
+                .find(|row| row.schema == SHELL_KEYBINDINGS_SCHEMA && row.gsettings_key == *key)
+            else {
+                continue;
+            };
+            row.label = match favorites.get(i) {
+                Some(id) => format!("{label}: {}", names.get(id).unwrap_or(id)),
+                None => format!("{label}: nothing pinned"),
+            };
+            match self.app_keybindings.get(&(i as u32 + 1)) {
+                Some(value) if !row.is_modified() && !same_value(value, &row.gsettings_value) => {
+                    row.load(value.clone(), keysyms)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // switch-to-application-1..9 default to <Super>1..9 and win over the
+    // workspace keybindings, so a workspace row using <Super>N is shadowed
+    pub fn conflicting_app_shortcut(&self, k: usize) -> Option<u32> {
+        let selection = self.rows.get(&k)?;
+        let super_only = Modifiers {
+            super_key: true,
+            ..Default::default()
+        };
+        if selection.modifiers != super_only {
+            return None;
+        }
+        let id: u32 = selection.keybinding.parse().ok()?;
+        let value = self.app_keybindings.get(&id)?;
+        value.contains(&format!("'<Super>{id}'")).then_some(id)
+    }
+
+    // (workspace, count) when a bound row targets a workspace past the
+    // configured count. With dynamic workspaces the count isn't fixed.
+    pub fn missing_workspace(&self, k: usize) -> Option<(usize, usize)> {
+        if self.dynamic_workspaces {
+            return None;
+        }
+        let row = self.rows.get(&k)?;
+        let workspace: usize = row.gsettings_key.rsplit('-').next()?.parse().ok()?;
+        let count = self.workspace_count?;
+        let bound = !GSettings::is_empty_keybinding(&row.gsettings_value)
+            && !row.gsettings_value.trim().is_empty();
+        (bound && workspace > count).then_some((workspace, count))
+    }
+
+    // Rows that would be skipped or written broken, so an apply all either
+    // writes every edit or none.
+    pub fn invalid_rows(&self) -> Vec<String> {
+        self.rows
+            .values()
+            .filter(|row| row.is_modified())
+            .filter_map(|row| {
+                if row.is_incomplete() {
+                    Some(format!("{} has no key, use Clear to unbind it", row.label))
+                } else if let Err(e) = split_entries(&row.converted_keybinding) {
+                    Some(format!("{}: {:#}", row.label, e))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Stage every complete edit that isn't written yet.
+    pub fn stage_edits(
+        &self,
+        backend: &dyn SettingsBackend,
+        transaction: &mut Transaction,
+    ) -> Result<()> {
+        for row in self.rows.values() {
+            if row.is_incomplete() || !row.is_modified() {
+                continue;
+            }
+            transaction.stage(
+                backend,
+                &row.schema,
+                &row.gsettings_key,
+                &row.converted_keybinding,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Start over from the keybindings as read, e.g. by
+    /// [`ConflictIndex::scan`].
+    pub fn set_conflicts(&mut self, conflicts: ConflictIndex) {
+        self.index = conflicts;
+        self.indexed.clear();
+        self.stale = true;
+    }
+
+    /// Take over a value read or written outside the rows. A row of the
+    /// key keeps its own value in the index, an edit in progress wins.
+    pub fn update_conflict(&mut self, schema: &str, key: &str, value: &str) {
+        let has_row = self
+            .rows
+            .values()
+            .any(|row| row.schema == schema && row.gsettings_key == key);
+        if !has_row {
+            self.index.update(schema, key, value);
+        }
+        self.stale = true;
+    }
+
+    pub fn set_layout(&mut self, layout: Option<LayoutVariant>) {
+        self.layout = layout;
+        // the location notes of every row
+        self.indexed.clear();
+        self.stale = true;
+    }
+
+    /// Rows were edited, read or written, their notes are recomputed by
+    /// the next [`Model::annotate`].
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    // Flag rows sharing an accelerator with any other key and rows using
+    // keys placed differently on ISO and ANSI keyboards, edits that aren't
+    // written yet included. Only rows whose value changed since the last
+    // call are put into the index again, nothing runs while the rows are
+    // as they were.
+    pub fn annotate(&mut self) {
+        if !self.stale {
+            return;
+        }
+        self.stale = false;
+        for (k, row) in &mut self.rows {
+            let pending = if row.is_incomplete() || !row.is_modified() {
+                &row.gsettings_value
+            } else {
+                &row.converted_keybinding
+            };
+            if self.indexed.get(k) == Some(pending) {
+                continue;
+            }
+            self.index.update(&row.schema, &row.gsettings_key, pending);
+            let notes = match self.layout {
+                Some(layout) => key_location::notes(pending, layout),
+                None => vec![],
+            };
+            row.location = (!notes.is_empty()).then(|| notes.join("\n"));
+            self.indexed.insert(*k, pending.clone());
+        }
+        for (k, row) in &mut self.rows {
+            let others = self
+                .index
+                .others(&row.schema, &row.gsettings_key, &self.indexed[k]);
+            row.conflict = (!others.is_empty()).then(|| {
+                let names: Vec<String> = others
+                    .iter()
+                    .map(|(schema, key)| format!("{key} in {schema}"))
+                    .collect();
+                format!("Same shortcut as {}", names.join(", "))
+            });
+        }
+    }
+}
+
+/// Scheme exported on another GNOME version to migrate, and what checking
+/// it found.
+#[derive(Default)]
+pub struct MigrationDialog {
+    pub path: String,
+    // what Check found, cleared when the path is edited
+    pub plan: Option<Migration>,
+}
+
+/// Format and target of an export, the path follows the format until it's
+/// edited.
+pub struct ExportDialog {
+    pub exporter: usize,
+    pub path: String,
+    // only the keys that differ from GNOME's defaults
+    pub minimal: bool,
+    // pending portal save dialog
+    #[cfg(feature = "portal")]
+    pub chooser: Option<std::sync::mpsc::Receiver<Result<Option<std::path::PathBuf>>>>,
+}
+
+impl ExportDialog {
+    pub fn new() -> Self {
+        Self {
+            exporter: 0,
+            path: export::default_path(EXPORTERS[0]).display().to_string(),
+            minimal: false,
+            #[cfg(feature = "portal")]
+            chooser: None,
+        }
+    }
+}
+
+impl Default for ExportDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
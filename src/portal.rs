@@ -0,0 +1,244 @@
+//! Ask the xdg-desktop-portal GlobalShortcuts interface whether the
+//! compositor would hand out an accelerator. Binding through the portal needs
+//! a session and may show a confirmation dialog, so this is only run on
+//! request. The FileChooser interface picks where exports are saved.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::accelerator::Accelerator;
+use zbus::{
+    blocking::{proxy::Builder, Connection, Proxy},
+    export::serde::Serialize,
+    proxy::CacheProperties,
+    zvariant::{DynamicType, OwnedObjectPath, OwnedValue, Value},
+};
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const GLOBAL_SHORTCUTS_INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+const FILE_CHOOSER_INTERFACE: &str = "org.freedesktop.portal.FileChooser";
+
+static TOKEN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeResult {
+    // no portal, or one without GlobalShortcuts
+    Unavailable(String),
+    // the compositor assigned the trigger, with its own description of it
+    Free(String),
+    // the compositor refused to assign the preferred trigger
+    Reserved,
+    // the user dismissed the portal dialog
+    Cancelled,
+}
+
+pub fn is_wayland() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// The shortcut in the XDG shortcuts format the portal expects for
+/// `preferred_trigger`, e.g. `SHIFT+LOGO+1`.
+pub fn preferred_trigger(accelerator: &Accelerator) -> String {
+    let m = accelerator.modifiers;
+    let mut parts = vec![];
+    for (set, name) in [
+        (m.ctrl, "CTRL"),
+        (m.alt, "ALT"),
+        (m.shift, "SHIFT"),
+        (m.super_key, "LOGO"),
+    ] {
+        if set {
+            parts.push(name);
+        }
+    }
+    parts.push(&accelerator.key);
+    parts.join("+")
+}
+
+fn token() -> String {
+    let n = TOKEN_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("gwsm{}_{}", std::process::id(), n)
+}
+
+fn proxy<'a>(conn: &Connection, path: &'a str, interface: &'a str) -> Result<Proxy<'a>> {
+    Ok(Builder::new(conn)
+        .destination(PORTAL_DESTINATION)?
+        .path(path)?
+        .interface(interface)?
+        .cache_properties(CacheProperties::No)
+        .build()?)
+}
+
+// portal calls return right away and deliver their result later through a
+// Response signal on a request object, whose path is derived from our unique
+// name and the handle token
+fn call_request(
+    conn: &Connection,
+    interface: &str,
+    method: &str,
+    token: &str,
+    body: &(impl Serialize + DynamicType),
+) -> Result<(u32, HashMap<String, OwnedValue>)> {
+    let sender = conn
+        .unique_name()
+        .context("no unique name on the session bus")?
+        .trim_start_matches(':')
+        .replace('.', "_");
+    let request_path = format!("{PORTAL_PATH}/request/{sender}/{token}");
+
+    let request = proxy(conn, &request_path, "org.freedesktop.portal.Request")?;
+    let mut responses = request.receive_signal("Response")?;
+
+    let portal = proxy(conn, PORTAL_PATH, interface)?;
+    let handle: OwnedObjectPath = portal.call(method, body)?;
+    if handle.as_str() != request_path {
+        bail!("portal answered on unexpected request {}", handle.as_str());
+    }
+
+    let response = responses
+        .next()
+        .context("portal closed the request without a response")?;
+    Ok(response.body().deserialize()?)
+}
+
+pub fn probe(accelerator: &Accelerator) -> Result<ProbeResult> {
+    let conn = match Connection::session() {
+        Ok(conn) => conn,
+        Err(e) => return Ok(ProbeResult::Unavailable(format!("no session bus: {e}"))),
+    };
+    let portal = proxy(&conn, PORTAL_PATH, GLOBAL_SHORTCUTS_INTERFACE)?;
+    if let Err(e) = portal.get_property::<u32>("version") {
+        return Ok(ProbeResult::Unavailable(format!(
+            "GlobalShortcuts portal not available: {e}"
+        )));
+    }
+
+    let session_token = token();
+    let request_token = token();
+    let options: HashMap<&str, Value> = HashMap::from([
+        ("handle_token", Value::from(request_token.as_str())),
+        ("session_handle_token", Value::from(session_token.as_str())),
+    ]);
+    let (response, results) = call_request(
+        &conn,
+        GLOBAL_SHORTCUTS_INTERFACE,
+        "CreateSession",
+        &request_token,
+        &(options,),
+    )?;
+    if response != 0 {
+        return Ok(ProbeResult::Cancelled);
+    }
+    let session: String = results
+        .get("session_handle")
+        .context("portal returned no session handle")?
+        .try_clone()?
+        .try_into()?;
+    let session = OwnedObjectPath::try_from(session)?;
+
+    let request_token = token();
+    let trigger = preferred_trigger(accelerator);
+    let shortcut: HashMap<&str, Value> = HashMap::from([
+        ("description", Value::from("Availability check")),
+        ("preferred_trigger", Value::from(trigger.as_str())),
+    ]);
+    let options: HashMap<&str, Value> =
+        HashMap::from([("handle_token", Value::from(request_token.as_str()))]);
+    let bound = call_request(
+        &conn,
+        GLOBAL_SHORTCUTS_INTERFACE,
+        "BindShortcuts",
+        &request_token,
+        &(&session, vec![("probe", shortcut)], "", options),
+    );
+
+    // close the session in any case, it would keep the shortcut alive
+    let close = proxy(&conn, session.as_str(), "org.freedesktop.portal.Session")
+        .and_then(|p| Ok(p.call_method("Close", &())?));
+    if let Err(e) = close {
+        tracing::warn!("closing portal session failed: {:#}", e);
+    }
+
+    let (response, results) = bound?;
+    if response != 0 {
+        return Ok(ProbeResult::Cancelled);
+    }
+    let shortcuts: Vec<(String, HashMap<String, OwnedValue>)> = results
+        .get("shortcuts")
+        .context("portal returned no shortcuts")?
+        .try_clone()?
+        .try_into()?;
+    let description = shortcuts
+        .iter()
+        .find(|(id, _)| id == "probe")
+        .and_then(|(_, props)| props.get("trigger_description"))
+        .and_then(|v| String::try_from(v.try_clone().ok()?).ok())
+        .unwrap_or_default();
+
+    Ok(if description.is_empty() {
+        ProbeResult::Reserved
+    } else {
+        ProbeResult::Free(description)
+    })
+}
+
+/// The path of a `file://` URI, with percent escapes decoded.
+pub fn path_of_uri(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // an authority, if any, ends at the first slash of the path
+    let path = &rest[rest.find('/')?..];
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    use std::os::unix::ffi::OsStringExt;
+    Some(PathBuf::from(std::ffi::OsString::from_vec(decoded)))
+}
+
+/// Ask where to save a file through the portal's save dialog, `None` when
+/// the user cancels it. Blocks until the dialog is closed.
+pub fn save_file(title: &str, current_name: &str) -> Result<Option<PathBuf>> {
+    let conn = Connection::session().context("no session bus")?;
+    let request_token = token();
+    let options: HashMap<&str, Value> = HashMap::from([
+        ("handle_token", Value::from(request_token.as_str())),
+        ("current_name", Value::from(current_name)),
+    ]);
+    let (response, results) = call_request(
+        &conn,
+        FILE_CHOOSER_INTERFACE,
+        "SaveFile",
+        &request_token,
+        &("", title, options),
+    )?;
+    match response {
+        0 => {}
+        1 => return Ok(None),
+        _ => bail!("the save dialog failed"),
+    }
+    let uris: Vec<String> = results
+        .get("uris")
+        .context("portal returned no file")?
+        .try_clone()?
+        .try_into()?;
+    let uri = uris.first().context("portal returned no file")?;
+    path_of_uri(uri)
+        .map(Some)
+        .with_context(|| format!("{uri} is not a local file"))
+}
@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use eframe::egui;
+use gnome_workspace_shortcuts_menu::workspace;
+
+// often enough to feel instant, rare enough not to keep D-Bus busy
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// "Switch to workspace N" drills, checked by watching which workspace
+/// becomes active.
+pub struct Practice {
+    // (workspace, accelerator switching to it) of every bound switch row
+    targets: Vec<(usize, String)>,
+    target: usize,
+    // the workspace the exercise started on, a change away from it counts
+    start: usize,
+    prompted: Instant,
+    hits: usize,
+    misses: usize,
+    last_result: Option<String>,
+    error: Option<String>,
+    last_poll: Instant,
+    seed: u64,
+}
+
+impl Practice {
+    /// `None` without any bound switch shortcut to practice.
+    pub fn new(targets: Vec<(usize, String)>) -> Option<Self> {
+        if targets.is_empty() {
+            return None;
+        }
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(1)
+            | 1;
+        let mut practice = Self {
+            targets,
+            target: 0,
+            start: 0,
+            prompted: Instant::now(),
+            hits: 0,
+            misses: 0,
+            last_result: None,
+            error: None,
+            last_poll: Instant::now(),
+            seed,
+        };
+        practice.next_exercise();
+        Some(practice)
+    }
+
+    // xorshift, good enough for picking exercises
+    fn random(&mut self, n: usize) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed % n as u64) as usize
+    }
+
+    fn next_exercise(&mut self) {
+        match workspace::current() {
+            Ok(current) => {
+                self.start = current;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+        // never ask for the workspace that's already active
+        let candidates: Vec<usize> = (0..self.targets.len())
+            .filter(|&i| self.targets[i].0 != self.start)
+            .collect();
+        if !candidates.is_empty() {
+            self.target = candidates[self.random(candidates.len())];
+        }
+        self.prompted = Instant::now();
+    }
+
+    fn binding_of(&self, workspace: usize) -> Option<&str> {
+        self.targets
+            .iter()
+            .find(|(w, _)| *w == workspace)
+            .map(|(_, binding)| binding.as_str())
+    }
+
+    fn poll(&mut self) {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return;
+        }
+        self.last_poll = Instant::now();
+        let current = match workspace::current() {
+            Ok(current) => current,
+            Err(e) => {
+                self.error = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        if current == self.start {
+            return;
+        }
+        let (target, binding) = self.targets[self.target].clone();
+        let secs = self.prompted.elapsed().as_secs_f32();
+        self.last_result = Some(if current == target {
+            self.hits += 1;
+            format!("✔ Workspace {target} in {secs:.1}s")
+        } else {
+            self.misses += 1;
+            let pressed = self.binding_of(current).unwrap_or("something else");
+            format!("✖ That was workspace {current} ({pressed}), {binding} goes to {target}")
+        });
+        self.next_exercise();
+    }
+
+    /// Returns false once the window is closed.
+    pub fn show(&mut self, ctx: &egui::Context) -> bool {
+        if self.error.is_none() {
+            self.poll();
+        }
+        let mut open = true;
+        egui::Window::new("Practice")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.heading(format!("Switch to workspace {}", self.targets[self.target].0));
+                ui.weak("Tip: choose \"Always on Visible Workspace\" in this window's title bar menu so it follows you.");
+                if ui.small_button("Show answer").clicked() {
+                    self.last_result = Some(format!("Hint: {}", self.targets[self.target].1));
+                }
+                if let Some(result) = &self.last_result {
+                    ui.label(result);
+                }
+                ui.label(format!("{} right, {} wrong", self.hits, self.misses));
+                if let Some(e) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                    if ui.button("Retry").clicked() {
+                        self.next_exercise();
+                    }
+                }
+            });
+        if self.error.is_none() {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+        open
+    }
+}
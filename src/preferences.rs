@@ -0,0 +1,99 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::xdg::config_dir;
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+/// How long an apply waits for confirmation before it's reverted.
+pub const REVERT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Kinds of writes the confirmation policy can ask about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirm {
+    // a single row written with Overwrite
+    Overwrite,
+    // anything writing several keys at once: presets, imports, batch actions
+    BulkApply,
+    Clear,
+    // back to the value from before this app first changed a key
+    Reset,
+}
+
+/// Which writes are previewed before they happen. A write that is more
+/// than one kind, like clearing several rows, asks when any of them does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmPolicy {
+    pub overwrite: bool,
+    pub bulk_apply: bool,
+    pub clear: bool,
+    pub reset: bool,
+}
+
+impl Default for ConfirmPolicy {
+    // what the app did before this was configurable
+    fn default() -> Self {
+        Self {
+            overwrite: false,
+            bulk_apply: true,
+            clear: false,
+            reset: false,
+        }
+    }
+}
+
+impl ConfirmPolicy {
+    pub fn requires(&self, kinds: &[Confirm]) -> bool {
+        kinds.iter().any(|kind| match kind {
+            Confirm::Overwrite => self.overwrite,
+            Confirm::BulkApply => self.bulk_apply,
+            Confirm::Clear => self.clear,
+            Confirm::Reset => self.reset,
+        })
+    }
+}
+
+/// Settings of the app itself, as opposed to the GNOME settings it edits.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    // opt-in, only honoured when built with the update-check feature
+    pub check_for_updates: bool,
+    // revert bulk applies that aren't confirmed within REVERT_TIMEOUT
+    pub confirm_bulk_applies: bool,
+    // profile file the drift check compares the system with
+    pub active_profile: Option<PathBuf>,
+    pub confirm: ConfirmPolicy,
+    // stronger borders, larger targets and text next to every status color
+    pub high_contrast: bool,
+}
+
+impl Preferences {
+    pub fn path() -> PathBuf {
+        config_dir().join(PREFERENCES_FILE)
+    }
+
+    /// Missing or unreadable preferences fall back to the defaults.
+    pub fn load() -> Self {
+        let path = Self::path();
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str(&content) {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                tracing::warn!("ignoring {}: {:#}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(config_dir())?;
+        fs::write(Self::path(), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    accelerator::{Accelerator, Modifiers},
+    backend::SettingsBackend,
+    gsettings::{EMPTY_KEYBINDING, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA},
+    transaction::Transaction,
+};
+
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    // (gsettings_key, gsettings_value) in org.gnome.desktop.wm.keybindings
+    pub bindings: Vec<(String, String)>,
+    // switch-to-application-1..9 default to <Super>1..9 and would shadow
+    // presets using <Super>N
+    pub clear_app_shortcuts: bool,
+}
+
+impl Preset {
+    /// `switch`+N switches to workspace N, `move_to`+N moves the window
+    /// there, workspace 10 is on the 0 key.
+    pub fn numbered(
+        name: &str,
+        switch: Modifiers,
+        move_to: Modifiers,
+        clear_app_shortcuts: bool,
+    ) -> Self {
+        let mut bindings = vec![];
+        for i in 1..=10 {
+            let key = i % 10;
+            bindings.push((
+                format!("switch-to-workspace-{i}"),
+                Accelerator::new(switch, &key.to_string()).to_gsettings(),
+            ));
+            bindings.push((
+                format!("move-to-workspace-{i}"),
+                Accelerator::new(move_to, &key.to_string()).to_gsettings(),
+            ));
+        }
+        Self {
+            name: name.into(),
+            description: format!(
+                "{}N switches to workspace N, {}N moves the window there",
+                switch.format(),
+                move_to.format()
+            ),
+            bindings,
+            clear_app_shortcuts,
+        }
+    }
+
+    /// $mod+N switches to workspace N, $mod+Shift+N moves the window there.
+    pub fn i3_style() -> Self {
+        let super_key = Modifiers {
+            super_key: true,
+            ..Default::default()
+        };
+        let shift_super = Modifiers {
+            shift: true,
+            ..super_key
+        };
+        Self::numbered("i3-style", super_key, shift_super, true)
+    }
+
+    /// Ctrl+Alt+N, the combination left alone by GNOME Shell and most apps.
+    pub fn ctrl_alt() -> Self {
+        let ctrl_alt = Modifiers {
+            ctrl: true,
+            alt: true,
+            ..Default::default()
+        };
+        let shift_ctrl_alt = Modifiers {
+            shift: true,
+            ..ctrl_alt
+        };
+        Self::numbered("Ctrl+Alt", ctrl_alt, shift_ctrl_alt, false)
+    }
+
+    /// Super+N to switch like i3, Super+Alt+N to move, keeps Shift free
+    /// for apps.
+    pub fn super_alt() -> Self {
+        let super_key = Modifiers {
+            super_key: true,
+            ..Default::default()
+        };
+        let super_alt = Modifiers {
+            alt: true,
+            ..super_key
+        };
+        Self::numbered("Super, Super+Alt to move", super_key, super_alt, true)
+    }
+
+    /// A preset from a template like
+    /// `switch=<Super>{n}; move=<Shift><Super>{n}; n=1..10`. `{n}` is the
+    /// number key of the workspace, 0 for workspace 10, and `n` defaults to
+    /// every workspace. Leaving out `switch` or `move` keeps those bindings.
+    pub fn from_template(template: &str) -> Result<Self> {
+        let (mut switch, mut move_to, mut range) = (None, None, 1..=10);
+        for part in template.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((name, value)) = part.split_once('=') else {
+                bail!("'{part}' should look like name=value");
+            };
+            let value = value.trim();
+            match name.trim() {
+                "switch" => switch = Some(value),
+                "move" => move_to = Some(value),
+                "n" => {
+                    let bounds = value
+                        .split_once("..")
+                        .and_then(|(a, b)| Some((a.trim().parse().ok()?, b.trim().parse().ok()?)));
+                    match bounds {
+                        Some((a, b)) if 1 <= a && a <= b && b <= 10 => range = a..=b,
+                        _ => bail!("n should be a range within 1..10, not '{value}'"),
+                    }
+                }
+                other => bail!("unknown '{other}', expected switch, move or n"),
+            }
+        }
+        if switch.is_none() && move_to.is_none() {
+            bail!("the template needs a switch or a move binding");
+        }
+
+        let mut bindings = vec![];
+        let mut clear_app_shortcuts = false;
+        for i in range {
+            let key = (i % 10).to_string();
+            for (prefix, pattern) in [("switch", switch), ("move", move_to)] {
+                let Some(pattern) = pattern else {
+                    continue;
+                };
+                let accelerator = Accelerator::parse(&pattern.replace("{n}", &key))
+                    .with_context(|| format!("{prefix} binding of workspace {i}"))?;
+                // the <Super>N application shortcuts would shadow it
+                clear_app_shortcuts |= accelerator.modifiers
+                    == Modifiers {
+                        super_key: true,
+                        ..Default::default()
+                    };
+                bindings.push((
+                    format!("{prefix}-to-workspace-{i}"),
+                    accelerator.to_gsettings(),
+                ));
+            }
+        }
+        Ok(Self {
+            name: "template".into(),
+            description: template.trim().into(),
+            bindings,
+            clear_app_shortcuts,
+        })
+    }
+
+    /// The presets offered in the Presets panel.
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::i3_style(), Self::ctrl_alt(), Self::super_alt()]
+    }
+
+    pub fn stage(&self, backend: &dyn SettingsBackend) -> Result<Transaction> {
+        let mut transaction = Transaction::new();
+        if self.clear_app_shortcuts {
+            for id in 1..10 {
+                transaction.stage(
+                    backend,
+                    SHELL_KEYBINDINGS_SCHEMA,
+                    &format!("switch-to-application-{id}"),
+                    EMPTY_KEYBINDING,
+                )?;
+            }
+        }
+        for (key, value) in &self.bindings {
+            transaction.stage(backend, WM_KEYBINDINGS_SCHEMA, key, value)?;
+        }
+        Ok(transaction)
+    }
+}
@@ -0,0 +1,416 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::SettingsBackend,
+    custom_shortcuts::{self, CUSTOM_KEYBINDING_KEYS, MEDIA_KEYS_SCHEMA},
+    gsettings::{
+        same_value, MUTTER_KEYBINDINGS_SCHEMA, MUTTER_SCHEMA, SHELL_KEYBINDINGS_SCHEMA,
+        WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    hooks::Hooks,
+    keyboard::KEYBOARD_SCHEMA,
+    transaction::Transaction,
+    xdg::config_dir,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileValue {
+    pub schema: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// The values of every key this app manages, as read from the backend.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub values: Vec<ProfileValue>,
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    // only the values differing from GNOME's defaults, applying it resets
+    // the managed keys it leaves out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub minimal: bool,
+}
+
+/// (key, label) of the wm keybindings outside the workspace rows. The
+/// panel switchers move the keyboard focus between the top bar, the dock
+/// and windows, and default to Ctrl+Alt+Tab.
+pub const SYSTEM_KEYBINDINGS: [(&str, &str); 2] = [
+    ("switch-panels", "Switch system controls"),
+    ("switch-panels-backward", "Switch system controls backward"),
+];
+
+/// (key, label) of the wm keybindings for stacking and sizing windows,
+/// show-desktop is missing from some GNOME versions.
+pub const WINDOW_KEYBINDINGS: [(&str, &str); 7] = [
+    ("raise", "Raise window above others"),
+    ("lower", "Lower window below others"),
+    (
+        "raise-or-lower",
+        "Raise window if covered, otherwise lower it",
+    ),
+    ("always-on-top", "Toggle window always on top"),
+    ("show-desktop", "Hide all windows"),
+    ("maximize-horizontally", "Maximize window horizontally"),
+    ("maximize-vertically", "Maximize window vertically"),
+];
+
+/// (key, label) of the wm keybindings filling the screen with a window.
+pub const MAXIMIZE_KEYBINDINGS: [(&str, &str); 4] = [
+    ("maximize", "Maximize window"),
+    ("unmaximize", "Restore window size"),
+    ("toggle-maximized", "Toggle window maximized"),
+    ("toggle-fullscreen", "Toggle window fullscreen"),
+];
+
+/// (key, label) of the org.gnome.mutter.keybindings putting a window on
+/// one half of the screen.
+pub const TILING_KEYBINDINGS: [(&str, &str); 2] = [
+    ("toggle-tiled-left", "Tile window to the left half"),
+    ("toggle-tiled-right", "Tile window to the right half"),
+];
+
+/// (key, label) of the wm keybindings moving a window to the neighbouring
+/// monitor, they do nothing with a single monitor.
+pub const MONITOR_KEYBINDINGS: [(&str, &str); 4] = [
+    (
+        "move-to-monitor-left",
+        "Move window to the monitor on the left",
+    ),
+    (
+        "move-to-monitor-right",
+        "Move window to the monitor on the right",
+    ),
+    ("move-to-monitor-up", "Move window to the monitor above"),
+    ("move-to-monitor-down", "Move window to the monitor below"),
+];
+
+/// (key, label) of the wm keybindings moving relative to the current
+/// workspace. Up and down only do something with a grid of workspaces.
+pub const NAVIGATION_KEYBINDINGS: [(&str, &str); 10] = [
+    (
+        "switch-to-workspace-left",
+        "Switch to workspace on the left",
+    ),
+    (
+        "switch-to-workspace-right",
+        "Switch to workspace on the right",
+    ),
+    ("switch-to-workspace-up", "Switch to workspace above"),
+    ("switch-to-workspace-down", "Switch to workspace below"),
+    ("switch-to-workspace-last", "Switch to the last workspace"),
+    (
+        "move-to-workspace-left",
+        "Move window one workspace to the left",
+    ),
+    (
+        "move-to-workspace-right",
+        "Move window one workspace to the right",
+    ),
+    ("move-to-workspace-up", "Move window one workspace up"),
+    ("move-to-workspace-down", "Move window one workspace down"),
+    (
+        "move-to-workspace-last",
+        "Move window to the last workspace",
+    ),
+];
+
+/// (key, label) of the org.gnome.shell.keybindings opening the apps pinned
+/// to the dash, <Super>1..9 by default.
+pub const APP_KEYBINDINGS: [(&str, &str); 9] = [
+    ("switch-to-application-1", "Open dash app 1"),
+    ("switch-to-application-2", "Open dash app 2"),
+    ("switch-to-application-3", "Open dash app 3"),
+    ("switch-to-application-4", "Open dash app 4"),
+    ("switch-to-application-5", "Open dash app 5"),
+    ("switch-to-application-6", "Open dash app 6"),
+    ("switch-to-application-7", "Open dash app 7"),
+    ("switch-to-application-8", "Open dash app 8"),
+    ("switch-to-application-9", "Open dash app 9"),
+];
+
+/// (schema, key) of everything the app reads or writes.
+pub fn managed_keys() -> Vec<(&'static str, String)> {
+    let mut keys = vec![
+        (WM_PREFERENCES_SCHEMA, "num-workspaces".to_string()),
+        (MUTTER_SCHEMA, "dynamic-workspaces".to_string()),
+        // the key opening the overview, also a modifier of many bindings
+        (MUTTER_SCHEMA, "overlay-key".to_string()),
+        (WM_PREFERENCES_SCHEMA, "focus-mode".to_string()),
+        (WM_PREFERENCES_SCHEMA, "auto-raise".to_string()),
+        (KEYBOARD_SCHEMA, "repeat".to_string()),
+        (KEYBOARD_SCHEMA, "delay".to_string()),
+        (KEYBOARD_SCHEMA, "repeat-interval".to_string()),
+    ];
+    for i in 1..=10 {
+        keys.push((WM_KEYBINDINGS_SCHEMA, format!("switch-to-workspace-{i}")));
+        keys.push((WM_KEYBINDINGS_SCHEMA, format!("move-to-workspace-{i}")));
+    }
+    for (key, _) in NAVIGATION_KEYBINDINGS
+        .iter()
+        .chain(&WINDOW_KEYBINDINGS)
+        .chain(&MAXIMIZE_KEYBINDINGS)
+        .chain(&MONITOR_KEYBINDINGS)
+        .chain(&SYSTEM_KEYBINDINGS)
+    {
+        keys.push((WM_KEYBINDINGS_SCHEMA, key.to_string()));
+    }
+    for (key, _) in TILING_KEYBINDINGS {
+        keys.push((MUTTER_KEYBINDINGS_SCHEMA, key.to_string()));
+    }
+    for (key, _) in APP_KEYBINDINGS {
+        keys.push((SHELL_KEYBINDINGS_SCHEMA, key.to_string()));
+    }
+    keys
+}
+
+impl Profile {
+    /// Read all managed keys and the custom shortcuts from GNOME Settings.
+    /// Keys the backend can't read are left out, a missing schema shouldn't
+    /// make the rest unavailable.
+    pub fn capture(backend: &dyn SettingsBackend) -> Self {
+        let mut keys: Vec<(String, String)> = managed_keys()
+            .into_iter()
+            .map(|(schema, key)| (schema.to_string(), key))
+            .collect();
+        keys.push((MEDIA_KEYS_SCHEMA.into(), "custom-keybindings".into()));
+        // the list itself is captured below, a missing one is logged there
+        for path in custom_shortcuts::paths(backend).unwrap_or_default() {
+            for key in CUSTOM_KEYBINDING_KEYS {
+                keys.push((custom_shortcuts::schema_at(&path), key.into()));
+            }
+        }
+
+        let mut values = vec![];
+        for (schema, key) in keys {
+            match backend.get(&schema, &key) {
+                Ok(value) => values.push(ProfileValue {
+                    schema,
+                    key,
+                    value: value.trim().into(),
+                }),
+                Err(e) => tracing::warn!(schema, key, "can't read: {:#}", e),
+            }
+        }
+        Self {
+            values,
+            ..Self::default()
+        }
+    }
+
+    /// Stage writing every value the backend doesn't already have. The
+    /// profile's hooks run around applying it.
+    pub fn stage(&self, backend: &dyn SettingsBackend) -> Result<Transaction> {
+        let mut transaction = Transaction::new();
+        transaction.hooks = self.hooks.clone();
+        for v in &self.values {
+            let current = backend.get(&v.schema, &v.key);
+            if current.is_ok_and(|c| same_value(&c, &v.value)) {
+                continue;
+            }
+            transaction.stage(backend, &v.schema, &v.key, &v.value)?;
+        }
+        for v in self.unmentioned_defaults(backend) {
+            let current = backend.get(&v.schema, &v.key);
+            if current.is_ok_and(|c| same_value(&c, &v.value)) {
+                continue;
+            }
+            transaction.stage(backend, &v.schema, &v.key, &v.value)?;
+        }
+        Ok(transaction)
+    }
+
+    /// Only the values differing from the schema defaults, for sharing what
+    /// was actually changed. A value whose default can't be read is kept.
+    pub fn minimal(&self, backend: &dyn SettingsBackend) -> Self {
+        let values = self
+            .values
+            .iter()
+            .filter(|v| {
+                backend
+                    .default_value(&v.schema, &v.key)
+                    .map_or(true, |default| !same_value(&default, &v.value))
+            })
+            .cloned()
+            .collect();
+        Self {
+            values,
+            hooks: self.hooks.clone(),
+            minimal: true,
+        }
+    }
+
+    /// The defaults of the managed keys a minimal profile leaves out, none
+    /// for a full profile.
+    pub fn unmentioned_defaults(&self, backend: &dyn SettingsBackend) -> Vec<ProfileValue> {
+        if !self.minimal {
+            return vec![];
+        }
+        managed_keys()
+            .into_iter()
+            .filter(|(schema, key)| self.get(schema, key).is_none())
+            .filter_map(|(schema, key)| {
+                let value = backend.default_value(schema, &key).ok()?;
+                Some(ProfileValue {
+                    schema: schema.into(),
+                    key,
+                    value: value.trim().into(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn get(&self, schema: &str, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|v| v.schema == schema && v.key == key)
+            .map(|v| v.value.as_str())
+    }
+
+    pub fn set(&mut self, schema: &str, key: &str, value: &str) {
+        match self
+            .values
+            .iter_mut()
+            .find(|v| v.schema == schema && v.key == key)
+        {
+            Some(v) => v.value = value.into(),
+            None => self.values.push(ProfileValue {
+                schema: schema.into(),
+                key: key.into(),
+                value: value.into(),
+            }),
+        }
+    }
+
+    /// What the profile becomes once `transaction` is applied, for
+    /// checking a bulk write before doing it.
+    pub fn with_changes(&self, transaction: &Transaction) -> Self {
+        let mut profile = self.clone();
+        for change in &transaction.changes {
+            profile.set(&change.schema, &change.key, &change.new_value);
+        }
+        profile
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("can't read {}", path.display()))?;
+        Self::from_json(&content).with_context(|| format!("{} is not a profile", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?).with_context(|| format!("can't write {}", path.display()))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+const PROFILE_DIR: &str = "profiles";
+
+/// Where the profile called `name` is saved.
+pub fn named_path(name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || name.contains('/') || name.starts_with('.') {
+        bail!("'{name}' can't be used as a profile name");
+    }
+    Ok(config_dir().join(PROFILE_DIR).join(format!("{name}.json")))
+}
+
+/// Names of the saved profiles, sorted.
+pub fn named_profiles() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(config_dir().join(PROFILE_DIR)) else {
+        return vec![];
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| {
+            let path = e.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+impl Profile {
+    pub fn load_named(name: &str) -> Result<Self> {
+        Self::load(&named_path(name)?)
+    }
+
+    /// Save under `name`, replacing a profile of the same name.
+    pub fn save_named(&self, name: &str) -> Result<PathBuf> {
+        let path = named_path(name)?;
+        fs::create_dir_all(config_dir().join(PROFILE_DIR))?;
+        self.save(&path)?;
+        Ok(path)
+    }
+}
+
+/// Fails instead of replacing an existing profile called `new`.
+pub fn rename_named(old: &str, new: &str) -> Result<()> {
+    let (from, to) = (named_path(old)?, named_path(new)?);
+    if to.exists() {
+        bail!("there already is a profile called '{}'", new.trim());
+    }
+    fs::rename(&from, &to).with_context(|| format!("can't rename {}", from.display()))
+}
+
+pub fn delete_named(name: &str) -> Result<()> {
+    let path = named_path(name)?;
+    fs::remove_file(&path).with_context(|| format!("can't delete {}", path.display()))
+}
+
+/// One key of two compared profiles, `None` where a profile lacks it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileDiff {
+    pub schema: String,
+    pub key: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl ProfileDiff {
+    pub fn is_equal(&self) -> bool {
+        self.left == self.right
+    }
+}
+
+/// Every key of either profile, in the order of `left` followed by the
+/// keys only `right` has.
+pub fn diff(left: &Profile, right: &Profile) -> Vec<ProfileDiff> {
+    let mut diffs: Vec<ProfileDiff> = left
+        .values
+        .iter()
+        .map(|v| ProfileDiff {
+            schema: v.schema.clone(),
+            key: v.key.clone(),
+            left: Some(v.value.clone()),
+            right: right.get(&v.schema, &v.key).map(str::to_string),
+        })
+        .collect();
+    for v in &right.values {
+        if left.get(&v.schema, &v.key).is_none() {
+            diffs.push(ProfileDiff {
+                schema: v.schema.clone(),
+                key: v.key.clone(),
+                left: None,
+                right: Some(v.value.clone()),
+            });
+        }
+    }
+    diffs
+}
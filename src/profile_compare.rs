@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use eframe::egui::{self, TextEdit, Ui};
+use gnome_workspace_shortcuts_menu::profile::{self, Profile, ProfileDiff};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Two profile files next to each other, with a pick per key for a merged
+/// profile.
+pub struct ProfileCompare {
+    left_path: String,
+    right_path: String,
+    diffs: Vec<ProfileDiff>,
+    picks: Vec<Side>,
+    only_differences: bool,
+    merged_path: String,
+}
+
+impl Default for ProfileCompare {
+    fn default() -> Self {
+        Self {
+            left_path: "".into(),
+            right_path: "".into(),
+            diffs: vec![],
+            picks: vec![],
+            only_differences: true,
+            merged_path: "".into(),
+        }
+    }
+}
+
+impl ProfileCompare {
+    fn load(&mut self) -> Result<()> {
+        let left = Profile::load(&PathBuf::from(self.left_path.trim()))?;
+        let right = Profile::load(&PathBuf::from(self.right_path.trim()))?;
+        self.diffs = profile::diff(&left, &right);
+        // a key only one side has is taken from that side
+        self.picks = self
+            .diffs
+            .iter()
+            .map(|d| {
+                if d.left.is_some() {
+                    Side::Left
+                } else {
+                    Side::Right
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn merged(&self) -> Profile {
+        let mut merged = Profile::default();
+        for (diff, side) in self.diffs.iter().zip(&self.picks) {
+            let value = match side {
+                Side::Left => &diff.left,
+                Side::Right => &diff.right,
+            };
+            if let Some(value) = value {
+                merged.set(&diff.schema, &diff.key, value);
+            }
+        }
+        merged
+    }
+
+    /// Returns a notice to show once the merged profile is saved.
+    pub fn show(&mut self, ui: &mut Ui) -> Result<Option<String>> {
+        let mut load = false;
+        egui::Grid::new("profile_compare_paths").show(ui, |ui| {
+            ui.label("Left");
+            ui.add(TextEdit::singleline(&mut self.left_path).desired_width(400.0));
+            ui.end_row();
+            ui.label("Right");
+            ui.add(TextEdit::singleline(&mut self.right_path).desired_width(400.0));
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            load = ui.button("Compare").clicked();
+            ui.checkbox(&mut self.only_differences, "Only differences");
+        });
+        if load {
+            self.load()?;
+        }
+        if self.diffs.is_empty() {
+            return Ok(None);
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+                egui::Grid::new("profile_compare")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (diff, pick) in self.diffs.iter().zip(self.picks.iter_mut()) {
+                            if self.only_differences && diff.is_equal() {
+                                continue;
+                            }
+                            ui.label(&diff.key).on_hover_text(&diff.schema);
+                            for (side, value) in
+                                [(Side::Left, &diff.left), (Side::Right, &diff.right)]
+                            {
+                                let text = value.as_deref().unwrap_or("(not set)");
+                                ui.add_enabled_ui(value.is_some(), |ui| {
+                                    ui.radio_value(pick, side, text);
+                                });
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        let mut save = false;
+        ui.horizontal(|ui| {
+            ui.label("Save merged profile as");
+            ui.add(TextEdit::singleline(&mut self.merged_path).desired_width(300.0));
+            save = ui
+                .add_enabled(
+                    !self.merged_path.trim().is_empty(),
+                    egui::Button::new("Save"),
+                )
+                .clicked();
+        });
+        if !save {
+            return Ok(None);
+        }
+        let path = PathBuf::from(self.merged_path.trim());
+        self.merged().save(&path)?;
+        Ok(Some(format!(
+            "Merged profile written to {}",
+            path.display()
+        )))
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::BTreeSet;
+
+use eframe::egui::Modifiers;
+
+/// Rows picked for a batch operation, by key of the row map. A plain click
+/// selects one row, Ctrl toggles one and Shift extends from the last click.
+#[derive(Debug, Default)]
+pub struct RowSelection {
+    pub rows: BTreeSet<usize>,
+    anchor: Option<usize>,
+}
+
+impl RowSelection {
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn contains(&self, k: usize) -> bool {
+        self.rows.contains(&k)
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.anchor = None;
+    }
+
+    /// `keys` are all rows in display order, for Shift ranges.
+    pub fn click(&mut self, k: usize, modifiers: Modifiers, keys: &[usize]) {
+        if modifiers.shift {
+            if let Some(anchor) = self.anchor {
+                let pos = |k| keys.iter().position(|&key| key == k);
+                if let (Some(a), Some(b)) = (pos(anchor), pos(k)) {
+                    let (from, to) = if a <= b { (a, b) } else { (b, a) };
+                    if !modifiers.command {
+                        self.rows.clear();
+                    }
+                    self.rows.extend(&keys[from..=to]);
+                    return;
+                }
+            }
+        }
+        if modifiers.command {
+            if !self.rows.remove(&k) {
+                self.rows.insert(k);
+            }
+        } else if self.rows.len() == 1 && self.rows.contains(&k) {
+            // clicking the only selected row again deselects it
+            self.rows.clear();
+        } else {
+            self.rows.clear();
+            self.rows.insert(k);
+        }
+        self.anchor = Some(k);
+    }
+}
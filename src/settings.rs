@@ -0,0 +1,204 @@
+//! In-process reads and writes through GIO, instead of spawning the
+//! `gsettings` binary for every key. Values keep the GVariant text format
+//! `gsettings` uses, so everything above the backend stays the same.
+
+use std::{fmt, thread, time::Instant};
+
+use gio::{glib, prelude::*};
+
+/// What can go wrong talking to GSettings, without parsing `gsettings`
+/// stderr.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsError {
+    MissingSchema(String),
+    MissingKey {
+        schema: String,
+        key: String,
+    },
+    InvalidValue {
+        schema: String,
+        key: String,
+        value: String,
+        reason: String,
+    },
+    // locked through dconf or rejected by the backend
+    NotWritable {
+        schema: String,
+        key: String,
+    },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::MissingSchema(schema) => write!(f, "No such schema “{schema}”"),
+            SettingsError::MissingKey { schema, key } => {
+                write!(f, "No such key “{key}” in schema “{schema}”")
+            }
+            SettingsError::InvalidValue {
+                schema,
+                key,
+                value,
+                reason,
+            } => write!(f, "{schema} {key}: can't use {value}: {reason}"),
+            SettingsError::NotWritable { schema, key } => {
+                write!(f, "{schema} {key} is not writable")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+// `schema:path` for relocatable schemas, like `gsettings` takes them
+fn open(schema: &str) -> Result<(gio::Settings, gio::SettingsSchema), SettingsError> {
+    let (id, path) = match schema.split_once(':') {
+        Some((id, path)) => (id, Some(path)),
+        None => (schema, None),
+    };
+    // Settings::new aborts the process on a missing schema, so look it up
+    // first
+    let found = gio::SettingsSchemaSource::default()
+        .and_then(|source| source.lookup(id, true))
+        .ok_or_else(|| SettingsError::MissingSchema(id.into()))?;
+    let settings = gio::Settings::new_full(&found, None::<&gio::SettingsBackend>, path);
+    Ok((settings, found))
+}
+
+fn key_of(
+    schema: &str,
+    found: &gio::SettingsSchema,
+    key: &str,
+) -> Result<gio::SettingsSchemaKey, SettingsError> {
+    if !found.has_key(key) {
+        return Err(SettingsError::MissingKey {
+            schema: schema.into(),
+            key: key.into(),
+        });
+    }
+    Ok(found.key(key))
+}
+
+/// The value with type annotations, as `gsettings get` prints it.
+pub fn get(schema: &str, key: &str) -> Result<String, SettingsError> {
+    let start = Instant::now();
+    let (settings, found) = open(schema)?;
+    key_of(schema, &found, key)?;
+    let value = settings.value(key).print(true).to_string();
+    let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+    tracing::info!(op = "get", schema, key, duration_ms, "gio get");
+    Ok(value)
+}
+
+/// The default the schema ships with.
+pub fn default_value(schema: &str, key: &str) -> Result<String, SettingsError> {
+    let (_, found) = open(schema)?;
+    let value = key_of(schema, &found, key)?.default_value();
+    Ok(value.print(true).to_string())
+}
+
+/// The summary of the key, or its description when it has none.
+pub fn describe(schema: &str, key: &str) -> Result<String, SettingsError> {
+    let (_, found) = open(schema)?;
+    let schema_key = key_of(schema, &found, key)?;
+    let text = schema_key
+        .summary()
+        .or_else(|| schema_key.description())
+        .map(|t| t.to_string())
+        .unwrap_or_default();
+    Ok(text)
+}
+
+/// False for keys locked through dconf.
+pub fn is_writable(schema: &str, key: &str) -> Result<bool, SettingsError> {
+    let (settings, found) = open(schema)?;
+    key_of(schema, &found, key)?;
+    Ok(settings.is_writable(key))
+}
+
+/// Parse `value` as the key's type and write it, waiting until the write
+/// reached dconf.
+pub fn set(schema: &str, key: &str, value: &str) -> Result<(), SettingsError> {
+    let start = Instant::now();
+    let (settings, found) = open(schema)?;
+    let value_type = key_of(schema, &found, key)?.value_type();
+    let invalid = |reason: String| SettingsError::InvalidValue {
+        schema: schema.into(),
+        key: key.into(),
+        value: value.into(),
+        reason,
+    };
+    let variant =
+        glib::Variant::parse(Some(&value_type), value).map_err(|e| invalid(e.to_string()))?;
+    if !settings.is_writable(key) {
+        return Err(SettingsError::NotWritable {
+            schema: schema.into(),
+            key: key.into(),
+        });
+    }
+    settings
+        .set_value(key, &variant)
+        .map_err(|e| invalid(e.to_string()))?;
+    gio::Settings::sync();
+    let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+    tracing::info!(op = "set", schema, key, value, duration_ms, "gio set");
+    Ok(())
+}
+
+pub fn list_keys(schema: &str) -> Result<Vec<String>, SettingsError> {
+    let (_, found) = open(schema)?;
+    Ok(found.list_keys().iter().map(|k| k.to_string()).collect())
+}
+
+/// A GLib main loop on its own thread delivering the `changed` signals of
+/// one schema, stopped when this is dropped.
+pub struct Subscription {
+    context: glib::MainContext,
+    main_loop: glib::MainLoop,
+}
+
+impl Drop for Subscription {
+    // A quit before the thread got to `run` would be lost. Queued on the
+    // loop's own context it runs once the loop does, however late.
+    fn drop(&mut self) {
+        let main_loop = self.main_loop.clone();
+        self.context.invoke(move || main_loop.quit());
+    }
+}
+
+/// Call `changed` with the key and its new value on every change of a key
+/// of `schema`, from the subscription's thread.
+pub fn watch(
+    schema: &str,
+    changed: impl Fn(String, String) + Send + 'static,
+) -> Result<Subscription, SettingsError> {
+    // a missing schema is reported here instead of on the thread
+    open(schema)?;
+    let schema = schema.to_string();
+    let context = glib::MainContext::new();
+    let main_loop = glib::MainLoop::new(Some(&context), false);
+    let running = main_loop.clone();
+    let subscription = Subscription {
+        context: context.clone(),
+        main_loop,
+    };
+    thread::spawn(move || {
+        let res = context.with_thread_default(|| {
+            let Ok((settings, found)) = open(&schema) else {
+                return;
+            };
+            settings.connect_changed(None, move |settings, key| {
+                changed(key.into(), settings.value(key).print(true).to_string());
+            });
+            // GSettings only signals changes of keys read since connecting
+            for key in found.list_keys() {
+                settings.value(&key);
+            }
+            running.run();
+        });
+        if let Err(e) = res {
+            tracing::error!(schema, "can't watch: {}", e);
+        }
+    });
+    Ok(subscription)
+}
@@ -0,0 +1,44 @@
+//! Compact text codes of a profile for pasting into chat: deflated JSON in
+//! URL-safe base64 behind a version prefix.
+
+use std::io::{Read, Write};
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+use crate::profile::Profile;
+
+const PREFIX: &str = "gwsm1:";
+// a pasted code can't make the import allocate without bounds
+const MAX_JSON_LEN: u64 = 1 << 20;
+
+pub fn encode(profile: &Profile) -> Result<String> {
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+    encoder.write_all(&serde_json::to_vec(profile)?)?;
+    Ok(format!(
+        "{PREFIX}{}",
+        URL_SAFE_NO_PAD.encode(encoder.finish()?)
+    ))
+}
+
+/// Whitespace is ignored, chat clients like to wrap long codes. Hooks are
+/// dropped, applying a pasted code must not run someone else's commands.
+pub fn decode(code: &str) -> Result<Profile> {
+    let code: String = code.split_whitespace().collect();
+    let Some(data) = code.strip_prefix(PREFIX) else {
+        bail!("not a share code, it should start with {PREFIX}");
+    };
+    let compressed = URL_SAFE_NO_PAD
+        .decode(data)
+        .context("share code is damaged")?;
+    let mut json = vec![];
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_JSON_LEN)
+        .read_to_end(&mut json)
+        .context("share code is damaged")?;
+    let mut profile: Profile =
+        serde_json::from_slice(&json).context("share code doesn't hold a profile")?;
+    profile.hooks = Default::default();
+    Ok(profile)
+}
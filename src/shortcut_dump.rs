@@ -0,0 +1,78 @@
+//! Every shortcut GNOME knows about, managed by this app or not, for
+//! documentation generators and onboarding wikis.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    accelerator::Accelerator,
+    backend::SettingsBackend,
+    conflicts::KEYBINDING_SCHEMAS,
+    custom_shortcuts::{self, MEDIA_KEYS_SCHEMA},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DumpedShortcut {
+    pub schema: String,
+    pub key: String,
+    // empty when the shortcut is disabled
+    pub accelerators: Vec<String>,
+    // from the schema, or the name of a custom shortcut
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+/// The keybinding keys of every keybinding schema and media-keys, followed
+/// by the custom shortcuts. Keys holding something other than accelerators
+/// are left out, so are schemas that aren't installed.
+pub fn collect(backend: &dyn SettingsBackend) -> Vec<DumpedShortcut> {
+    let mut shortcuts = vec![];
+    for schema in KEYBINDING_SCHEMAS.into_iter().chain([MEDIA_KEYS_SCHEMA]) {
+        let values = match backend.list_values(schema) {
+            Ok(values) => values,
+            Err(e) => {
+                tracing::debug!(schema, "not dumping: {:#}", e);
+                continue;
+            }
+        };
+        for (key, value) in values {
+            // the list of custom shortcut paths, they are dumped below
+            if schema == MEDIA_KEYS_SCHEMA && key == "custom-keybindings" {
+                continue;
+            }
+            let Ok(accelerators) = Accelerator::list_from_gsettings(&value) else {
+                continue;
+            };
+            let description = backend
+                .describe(schema, &key)
+                .ok()
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty());
+            shortcuts.push(DumpedShortcut {
+                schema: schema.into(),
+                accelerators: accelerators.iter().map(Accelerator::format).collect(),
+                key,
+                description,
+                command: None,
+            });
+        }
+    }
+    for custom in custom_shortcuts::read_all(backend).unwrap_or_default() {
+        shortcuts.push(DumpedShortcut {
+            schema: custom.schema(),
+            key: "binding".into(),
+            accelerators: [custom.binding.clone()]
+                .into_iter()
+                .filter(|b| !b.is_empty())
+                .collect(),
+            description: Some(custom.name),
+            command: Some(custom.command),
+        });
+    }
+    shortcuts
+}
+
+pub fn to_json(shortcuts: &[DumpedShortcut]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(shortcuts)?)
+}
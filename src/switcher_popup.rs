@@ -0,0 +1,121 @@
+//! Settings controlling the popup GNOME Shell shows on every workspace
+//! switch. Stock GNOME has none, its display time is hard-coded, so these
+//! come from extensions that patch the popup. Only keys that exist on this
+//! system are offered.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::{backend::SettingsBackend, transaction::Transaction};
+
+// (schema, key, label)
+const CANDIDATES: &[(&str, &str, &str)] = &[
+    (
+        "org.gnome.shell.extensions.workspace-switcher-manager",
+        "popup-visibility",
+        "Show the popup",
+    ),
+    (
+        "org.gnome.shell.extensions.workspace-switcher-manager",
+        "on-screen-time",
+        "Popup display time (ms)",
+    ),
+    (
+        "org.gnome.shell.extensions.workspace-switcher-manager",
+        "fade-out-time",
+        "Popup fade out time (ms)",
+    ),
+    (
+        "org.gnome.shell.extensions.just-perfection",
+        "workspace-switcher-should-show",
+        "Show the workspace switcher",
+    ),
+    (
+        "org.gnome.shell.extensions.just-perfection",
+        "workspace-popup",
+        "Show the workspace popup",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopupValue {
+    Bool(bool),
+    Int(i64),
+}
+
+impl PopupValue {
+    // gsettings prints unsigned and 64-bit integers with their type, e.g.
+    // `uint32 600`
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        match value {
+            "true" => return Ok(Self::Bool(true)),
+            "false" => return Ok(Self::Bool(false)),
+            _ => {}
+        }
+        let number = value.rsplit(' ').next().unwrap_or(value);
+        match number.parse() {
+            Ok(n) => Ok(Self::Int(n)),
+            Err(_) => bail!("unsupported value '{value}'"),
+        }
+    }
+
+    pub fn format(&self) -> String {
+        match self {
+            Self::Bool(b) => b.to_string(),
+            Self::Int(n) => n.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PopupSetting {
+    pub schema: &'static str,
+    pub key: &'static str,
+    pub label: &'static str,
+    pub value: PopupValue,
+}
+
+impl PopupSetting {
+    /// The write of the edited value, proposed like any other so it can be
+    /// previewed and undone.
+    pub fn stage(&self, backend: &dyn SettingsBackend) -> Result<Transaction> {
+        let mut transaction = Transaction::new();
+        transaction.stage(backend, self.schema, self.key, &self.value.format())?;
+        Ok(transaction)
+    }
+}
+
+/// The candidate settings this system has, with their current value.
+pub fn available(backend: &dyn SettingsBackend) -> Vec<PopupSetting> {
+    available_among(backend, CANDIDATES)
+}
+
+/// The (schema, key, label) of `candidates` this system has, for other
+/// extension settings that are only offered when installed.
+pub fn available_among(
+    backend: &dyn SettingsBackend,
+    candidates: &[(&'static str, &'static str, &'static str)],
+) -> Vec<PopupSetting> {
+    let mut settings = vec![];
+    let mut keys_of: HashMap<&str, Vec<String>> = HashMap::new();
+    for &(schema, key, label) in candidates {
+        let keys = keys_of
+            .entry(schema)
+            .or_insert_with(|| backend.list_keys(schema).unwrap_or_default());
+        if !keys.iter().any(|k| k == key) {
+            continue;
+        }
+        match backend.get(schema, key).and_then(|v| PopupValue::parse(&v)) {
+            Ok(value) => settings.push(PopupSetting {
+                schema,
+                key,
+                label,
+                value,
+            }),
+            Err(e) => tracing::warn!(schema, key, "can't read: {:#}", e),
+        }
+    }
+    settings
+}
@@ -0,0 +1,148 @@
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    backend::SettingsBackend,
+    backup,
+    hooks::{self, Hooks},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub schema: String,
+    pub key: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+/// A list of writes that are previewed together and applied in order. If one
+/// of them fails, the ones already written are restored to their old value.
+#[derive(Debug, Clone, Default)]
+pub struct Transaction {
+    pub changes: Vec<Change>,
+    // run by `apply` before and after writing, from the staged profile
+    pub hooks: Hooks,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stage a write, reading the current value so it can be shown in the
+    /// preview and restored on rollback.
+    pub fn stage(
+        &mut self,
+        backend: &dyn SettingsBackend,
+        schema: &str,
+        key: &str,
+        new_value: &str,
+    ) -> Result<()> {
+        let old_value = backend.get(schema, key)?.trim().to_string();
+        self.changes.push(Change {
+            schema: schema.into(),
+            key: key.into(),
+            old_value,
+            new_value: new_value.into(),
+        });
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Only the changes whose entry in `selected` is true, for applying part
+    /// of a previewed transaction.
+    pub fn only(&self, selected: &[bool]) -> Self {
+        let changes = self
+            .changes
+            .iter()
+            .zip(selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(c, _)| c.clone())
+            .collect();
+        Self {
+            changes,
+            hooks: self.hooks.clone(),
+        }
+    }
+
+    /// The writes putting every old value back, newest first.
+    pub fn inverse(&self) -> Self {
+        let changes = self
+            .changes
+            .iter()
+            .rev()
+            .map(|c| Change {
+                schema: c.schema.clone(),
+                key: c.key.clone(),
+                old_value: c.new_value.clone(),
+                new_value: c.old_value.clone(),
+            })
+            .collect();
+        // undoing a profile doesn't run its hooks again
+        Self {
+            changes,
+            ..Self::default()
+        }
+    }
+
+    /// A failing pre-apply hook stops the apply before the first write. A
+    /// failing post-apply hook is only logged, the writes are kept.
+    pub fn apply(&self, backend: &dyn SettingsBackend) -> Result<()> {
+        if let Some(command) = &self.hooks.pre_apply {
+            hooks::run("pre_apply", command)?;
+        }
+        for (i, change) in self.changes.iter().enumerate() {
+            let res = backup::backup_value(&change.schema, &change.key, &change.old_value)
+                .and_then(|()| {
+                    backend.set_confirmed(&change.schema, &change.key, &change.new_value)
+                })
+                .with_context(|| format!("failed to write {} {}", change.schema, change.key));
+
+            if let Err(e) = res {
+                return Err(match self.rollback(backend, i) {
+                    Ok(()) => anyhow!("{:#}, the earlier writes were rolled back", e),
+                    Err(rollback) => anyhow!("{:#}, and {:#}", e, rollback),
+                });
+            }
+        }
+        if let Some(command) = &self.hooks.post_apply {
+            if let Err(e) = hooks::run("post_apply", command) {
+                tracing::warn!("{:#}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restore every change to its old value after a successful `apply`,
+    /// continuing past failures so as much as possible is put back.
+    pub fn revert(&self, backend: &dyn SettingsBackend) -> Result<()> {
+        let mut failed = vec![];
+        for change in self.changes.iter().rev() {
+            if let Err(e) = backend.set(&change.schema, &change.key, &change.old_value) {
+                failed.push(format!("{} {}: {:#}", change.schema, change.key, e));
+            }
+        }
+        if !failed.is_empty() {
+            bail!("failed to revert {}", failed.join(", "));
+        }
+        Ok(())
+    }
+
+    // restore the first `count` changes, newest first, continuing past
+    // failures like `revert`
+    fn rollback(&self, backend: &dyn SettingsBackend, count: usize) -> Result<()> {
+        let mut failed = vec![];
+        for change in self.changes[..count].iter().rev() {
+            if let Err(e) = backend.set(&change.schema, &change.key, &change.old_value) {
+                tracing::error!(change.schema, change.key, "rollback failed: {:#}", e);
+                failed.push(format!("{} {}", change.schema, change.key));
+            }
+        }
+        if !failed.is_empty() {
+            bail!("failed to roll back {}", failed.join(", "));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,3773 @@
+//! The egui frontend: draws the [`Model`] and the sections around it, and
+//! turns input into settings writes through the backend and the worker.
+
+use crate::app_error::{AppError, StatusBar};
+use crate::auto_move_editor::AutoMoveEditor;
+#[cfg(any(feature = "portal", feature = "x11"))]
+use crate::availability::AvailabilityHints;
+use crate::cli::Jump;
+use crate::log_panel::LogPanel;
+use crate::practice::Practice;
+use crate::profile_compare::ProfileCompare;
+use crate::row_selection::RowSelection;
+use anyhow::{Context, Result};
+#[cfg(feature = "portal")]
+use gnome_workspace_shortcuts_menu::portal;
+#[cfg(feature = "update-check")]
+use gnome_workspace_shortcuts_menu::update;
+use gnome_workspace_shortcuts_menu::{
+    accelerator::{Accelerator, Modifiers},
+    backend::{self, PendingWrites, ReadOnly, Recording, SettingsBackend},
+    backup,
+    conflicts::ConflictIndex,
+    custom_shortcuts::{self, CustomShortcut},
+    desktop_entries, diagnostics,
+    doctor::{self, Check, Severity},
+    drift::{self, Drift},
+    environment::{self, Environment},
+    export::{self, Exporter, EXPORTERS},
+    extensions::{self, DISABLE_SWITCHER_POPUP_UUID, WRAPAROUND_UUID},
+    gestures::{GestureInfo, GESTURE_EXTENSIONS},
+    gsettings::{
+        parse_string, GSettings, EMPTY_KEYBINDING, MANAGED_SCHEMAS, MUTTER_SCHEMA,
+        SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    help::{self, Block},
+    history::{self, History, SyncState},
+    import::{self, IMPORTERS},
+    isolation,
+    key_location::LayoutVariant,
+    keyboard::{KeyRepeat, KEYBOARD_SCHEMA},
+    keysym::Keysyms,
+    lint,
+    macros::{Macro, Operation},
+    migration,
+    model::{
+        parse_number_of_workspaces, ExportDialog, MigrationDialog, Model, MAX_WORKSPACES,
+        ROW_GROUPS,
+    },
+    preferences::{Confirm, Preferences, REVERT_TIMEOUT},
+    preset::Preset,
+    profile::{self, Profile, APP_KEYBINDINGS},
+    share,
+    switcher_popup::{self, PopupSetting, PopupValue},
+    transaction::Transaction,
+    undo::{Snapshot, UndoStack},
+    watch::{Change, Watcher},
+    widget::{key_name, modifier_checkboxes, KeybindingRow, RowEvent, RowStatus, RowWrite},
+    worker::Worker,
+    workspace_layout::{Layout, COUNT_PRESETS},
+    xdg,
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use eframe::{
+    egui::{self, TextEdit, Ui},
+    epaint::Vec2,
+};
+
+pub struct MyApp {
+    // shared with the worker, which runs the reads and writes
+    backend: Arc<dyn SettingsBackend>,
+    worker: Worker<Done>,
+    // the rows and what they're checked against
+    model: Model,
+    keysyms: Keysyms,
+    // rows whose overwrite also clears the shadowing switch-to-application-N
+    clear_app_shortcut_conflicts: BTreeMap<usize, bool>,
+    // title and staged changes of the apply waiting for confirmation, with
+    // whether each change is selected for writing
+    pending_transaction: Option<(String, Transaction, Vec<bool>)>,
+    // last value written to each key, for the per-row sync icon
+    history: History,
+    // accelerators bound in every keybinding schema, kept up to date with
+    // writes and external changes
+    conflicts: ConflictIndex,
+    // writes of this session, for Ctrl+Z and Ctrl+Shift+Z
+    undo: UndoStack,
+    // applied transaction that is reverted unless kept before the deadline
+    unconfirmed_transaction: Option<(Transaction, Instant)>,
+    num_of_workspaces: String,
+    // failures of actions that don't belong to a row
+    status_bar: StatusBar,
+    // problems found by the startup checks, cleared on dismiss
+    health_issues: Vec<Check>,
+    // last outcome worth telling that isn't an error
+    notice: Option<String>,
+    disable_dynamic_workspaces: bool,
+    // the count was typed since the settings were read, with dynamic
+    // workspaces on that's the moment to offer fixed ones
+    count_edited: bool,
+    // strip or grid, for the preview next to the count
+    workspace_layout: Layout,
+    // expanded into bindings by Preset::from_template
+    preset_template: String,
+    // why writes are refused, None when the backend writes
+    write_guard: Option<String>,
+    // writes kept back for review while preview mode is on
+    preview_writes: Option<PendingWrites>,
+    // high contrast setting the current style was made for, None before the
+    // first frame
+    styled_high_contrast: Option<bool>,
+    // None when the key can't be read
+    focus_mode: Option<String>,
+    auto_raise: Option<bool>,
+    gestures: Option<GestureInfo>,
+    // None when the keyboard schema can't be read
+    key_repeat: Option<KeyRepeat>,
+    // whether the extension hiding the workspace switcher popup is enabled,
+    // None when it isn't installed
+    switcher_popup_hidden: Option<bool>,
+    // whether the wraparound extension is enabled, None when not installed
+    wraparound: Option<bool>,
+    // popup settings of extensions that happen to be installed
+    switcher_popup_settings: Vec<PopupSetting>,
+    // switcher and dock isolation settings that happen to be installed
+    isolation_settings: Vec<PopupSetting>,
+    // code of the current settings, None while the share window is closed
+    share_code: Option<String>,
+    // pasted into the share code import window, None while it's closed
+    share_import: Option<String>,
+    // path typed into the import window, None while it's closed
+    import_path: Option<String>,
+    // None while the migration window is closed
+    migration: Option<MigrationDialog>,
+    // None while the export window is closed
+    export_dialog: Option<ExportDialog>,
+    // None while the comparison window is closed
+    profile_compare: Option<ProfileCompare>,
+    // edited path of the active profile, saved to the preferences on use
+    active_profile_path: String,
+    // names of the profiles saved in the config directory
+    profile_names: Vec<String>,
+    // typed into the new profile name field
+    new_profile_name: String,
+    // (profile, edited name) while renaming
+    profile_rename: Option<(String, String)>,
+    // profile waiting for the delete to be confirmed
+    profile_delete: Option<String>,
+    // result of the last drift check, None while its window is closed
+    drift: Option<Vec<Drift>>,
+    // findings of the last lint run, None while the window is closed
+    lint_findings: Option<Vec<Check>>,
+    // requested on the command line, cleared once scrolled to
+    jump: Jump,
+    // started with --safe-mode and the settings haven't been loaded since
+    safe_mode: bool,
+    // reports keys changed outside this app, None until the first frame
+    // or when gsettings monitor can't run
+    watcher: Option<Watcher>,
+    watch_started: bool,
+    // None while the practice window is closed
+    practice: Option<Practice>,
+    // macro being recorded, None while not recording
+    recording: Option<Macro>,
+    // typed into the macro name field
+    macro_name: String,
+    // names of the saved macros
+    saved_macros: Vec<String>,
+    // rows batch operations work on
+    selection: RowSelection,
+    // row waiting for a key press in quick assign mode
+    quick_assign: Option<usize>,
+    // modifiers added to every captured key
+    quick_assign_base: Modifiers,
+    // modifiers the conflict strip shows the taken keys of
+    strip_modifiers: Modifiers,
+    // what "Set modifiers" puts on the selected rows
+    batch_modifiers: Modifiers,
+    // imported from GNOME Settings, None until imported
+    custom_shortcuts: Option<Vec<CustomShortcut>>,
+    // the rows being edited, in the order of custom_shortcuts
+    custom_edits: Vec<CustomShortcut>,
+    // the row for adding one, its path is picked when it's added
+    new_custom: CustomShortcut,
+    // only when the Auto Move Windows extension is enabled
+    auto_move: Option<AutoMoveEditor>,
+    log_panel: LogPanel,
+    show_log_panel: bool,
+    show_help_panel: bool,
+    // the row last edited or clicked, explained in the help panel
+    help_row: Option<usize>,
+    // detected when the About dialog is opened, None while it's closed
+    about: Option<Environment>,
+    preferences: Preferences,
+    // pending update check
+    #[cfg(feature = "update-check")]
+    update_check: Option<std::sync::mpsc::Receiver<Result<Option<update::Release>>>>,
+    #[cfg(feature = "update-check")]
+    available_update: Option<update::Release>,
+    #[cfg(any(feature = "portal", feature = "x11"))]
+    availability_hints: AvailabilityHints,
+}
+
+// what a job of the settings worker hands back to the UI thread
+enum Done {
+    // (row, schema, key, value) of every row read
+    Rows(Vec<(usize, String, String, Result<String>)>),
+    Applied {
+        title: String,
+        transaction: Transaction,
+        res: Result<()>,
+    },
+    // a single row written, with what the write changed for undo and the
+    // value read back
+    RowWritten {
+        k: usize,
+        title: String,
+        changes: Transaction,
+        res: Result<String>,
+        // the switch-to-application shortcut cleared along with it
+        cleared_app_shortcut: Option<(u32, Result<()>)>,
+    },
+    // values read for the sections outside the rows, taken over by running
+    // this on the UI thread
+    Read(Box<dyn FnOnce(&mut MyApp) + Send>),
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        Self {
+            backend: Arc::new(GSettings),
+            worker: Worker::start(|| {}),
+            model: Model::default(),
+            keysyms: Keysyms::default(),
+            clear_app_shortcut_conflicts: BTreeMap::new(),
+            pending_transaction: None,
+            history: History::default(),
+            conflicts: ConflictIndex::default(),
+            undo: UndoStack::default(),
+            unconfirmed_transaction: None,
+            num_of_workspaces: "4".into(),
+            status_bar: StatusBar::default(),
+            notice: None,
+            health_issues: vec![],
+            disable_dynamic_workspaces: true,
+            count_edited: false,
+            workspace_layout: Layout::Strip,
+            preset_template: String::new(),
+            styled_high_contrast: None,
+            write_guard: None,
+            preview_writes: None,
+            focus_mode: None,
+            auto_raise: None,
+            gestures: None,
+            key_repeat: None,
+            switcher_popup_hidden: None,
+            wraparound: None,
+            switcher_popup_settings: vec![],
+            isolation_settings: vec![],
+            custom_shortcuts: None,
+            custom_edits: vec![],
+            new_custom: CustomShortcut::default(),
+            auto_move: None,
+            share_code: None,
+            share_import: None,
+            import_path: None,
+            migration: None,
+            export_dialog: None,
+            profile_compare: None,
+            active_profile_path: "".into(),
+            profile_names: vec![],
+            new_profile_name: "".into(),
+            profile_rename: None,
+            profile_delete: None,
+            drift: None,
+            lint_findings: None,
+            jump: Jump::default(),
+            safe_mode: false,
+            watcher: None,
+            watch_started: false,
+            practice: None,
+            recording: None,
+            macro_name: "".into(),
+            saved_macros: vec![],
+            selection: RowSelection::default(),
+            quick_assign: None,
+            // Super, GNOME Shell grabs Super combinations before they get
+            // here, so it can't be captured from the key press
+            quick_assign_base: Modifiers {
+                super_key: true,
+                ..Default::default()
+            },
+            strip_modifiers: Modifiers {
+                super_key: true,
+                ..Default::default()
+            },
+            batch_modifiers: Modifiers::default(),
+            log_panel: LogPanel::new(Default::default()),
+            show_log_panel: false,
+            show_help_panel: false,
+            help_row: None,
+            about: None,
+            preferences: Preferences::default(),
+            #[cfg(feature = "update-check")]
+            update_check: None,
+            #[cfg(feature = "update-check")]
+            available_update: None,
+            #[cfg(any(feature = "portal", feature = "x11"))]
+            availability_hints: AvailabilityHints::default(),
+        }
+    }
+}
+
+// (id for --section, title) of the collapsible sections, in display order
+pub const SECTIONS: [(&str, &str); 13] = [
+    ("profile", "Active profile"),
+    ("profiles", "Profiles"),
+    ("presets", "Presets"),
+    ("macros", "Macros"),
+    ("custom", "Custom shortcuts"),
+    ("windows", "Window behavior"),
+    ("keyboard", "Keyboard"),
+    ("gestures", "Touchpad gestures"),
+    ("popup", "Workspace switch popup"),
+    ("isolation", "Workspace isolation"),
+    ("auto-move", "Auto Move Windows"),
+    ("confirm", "Confirmations"),
+    ("accessibility", "Accessibility"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum BatchAction {
+    Apply,
+    Clear,
+    Reset,
+    Export,
+    SelectNone,
+}
+
+// (focus-mode value, label)
+const FOCUS_MODES: [(&str, &str); 3] = [
+    ("click", "Click to focus"),
+    ("sloppy", "Focus follows mouse"),
+    ("mouse", "Focus follows mouse, unfocus on leaving"),
+];
+
+// white on black with thick borders, a yellow focus and hover color and
+// larger widgets and text
+fn high_contrast_style() -> egui::Style {
+    use egui::{Color32, Stroke};
+
+    let mut style = egui::Style {
+        visuals: egui::Visuals::dark(),
+        ..Default::default()
+    };
+    let visuals = &mut style.visuals;
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.panel_fill = Color32::BLACK;
+    visuals.window_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.faint_bg_color = Color32::from_gray(40);
+    visuals.window_stroke = Stroke::new(2.0, Color32::WHITE);
+    visuals.error_fg_color = Color32::from_rgb(255, 110, 110);
+    visuals.warn_fg_color = Color32::YELLOW;
+    visuals.hyperlink_color = Color32::from_rgb(120, 200, 255);
+    visuals.selection.bg_fill = Color32::from_rgb(0, 90, 200);
+    visuals.selection.stroke = Stroke::new(2.0, Color32::WHITE);
+    let widgets = &mut visuals.widgets;
+    for state in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        state.bg_stroke = Stroke::new(2.0, Color32::WHITE);
+        state.fg_stroke = Stroke::new(2.0, Color32::WHITE);
+    }
+    widgets.noninteractive.bg_fill = Color32::BLACK;
+    widgets.inactive.bg_fill = Color32::from_gray(30);
+    for state in [&mut widgets.hovered, &mut widgets.active] {
+        state.bg_stroke = Stroke::new(3.0, Color32::YELLOW);
+        state.bg_fill = Color32::from_gray(60);
+    }
+
+    style.spacing.interact_size.y = 30.0;
+    style.spacing.button_padding = Vec2::new(10.0, 6.0);
+    style.spacing.item_spacing = Vec2::new(10.0, 8.0);
+    style.spacing.icon_width = 20.0;
+    for font in style.text_styles.values_mut() {
+        font.size *= 1.2;
+    }
+    style
+}
+
+// (schema, key) of everything the app manages, for the startup backup and
+// resetting to the GNOME defaults
+fn restorable_keys() -> Vec<(String, String)> {
+    profile::managed_keys()
+        .into_iter()
+        .map(|(schema, key)| (schema.to_string(), key))
+        .collect()
+}
+
+// one small box per workspace, arranged like the shell will
+fn workspace_preview(ui: &mut Ui, layout: Layout, count: usize) {
+    const CELL: Vec2 = Vec2::new(18.0, 12.0);
+    const GAP: f32 = 3.0;
+    let (rows, columns) = layout.shape(count);
+    let size = Vec2::new(
+        columns as f32 * (CELL.x + GAP) - GAP,
+        rows as f32 * (CELL.y + GAP) - GAP,
+    );
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let stroke = ui.visuals().widgets.inactive.fg_stroke;
+    for i in 0..count {
+        let (row, column) = (i / columns, i % columns);
+        let min = rect.min + Vec2::new(column as f32 * (CELL.x + GAP), row as f32 * (CELL.y + GAP));
+        ui.painter()
+            .rect_stroke(egui::Rect::from_min_size(min, CELL), 2.0, stroke);
+    }
+    let shape = match layout {
+        Layout::Strip => format!("{count} workspaces in a row"),
+        Layout::Grid { .. } => format!("{count} workspaces in {rows} rows of {columns}"),
+    };
+    response.on_hover_text(shape);
+}
+
+// next to the logs in the state directory, which exists even without HOME
+fn diagnostics_path() -> Result<PathBuf> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    xdg::create_file_path(
+        xdg::state_dir(),
+        &format!("gnome-workspace-shortcuts-menu-diagnostics-{secs}.zip"),
+    )
+}
+
+// icon and hover text telling whether the system still has what this app
+// last wrote to a key
+fn sync_badge(last: Option<&history::Entry>, current: &str) -> (String, String) {
+    let (icon, text) = match (history::sync_state(last, current), last) {
+        (SyncState::Synced, Some(e)) => (
+            "●",
+            format!("Applied {} UTC, still set", history::format_timestamp(e.at)),
+        ),
+        (SyncState::Drifted, Some(e)) => (
+            "◐",
+            format!(
+                "Applied {} UTC as {}, changed since",
+                history::format_timestamp(e.at),
+                e.value
+            ),
+        ),
+        _ => ("○", "Never applied by this app".into()),
+    };
+    (icon.into(), text)
+}
+
+// dark modules on a light background with the quiet zone around them, so
+// phone cameras read it in dark mode too
+#[cfg(feature = "qr")]
+fn qr_code(ui: &mut Ui, text: &str) {
+    let code = match qrcode::QrCode::new(text.as_bytes()) {
+        Ok(code) => code,
+        Err(e) => {
+            ui.weak(format!("Too long for a QR code: {e}"));
+            return;
+        }
+    };
+    let width = code.width();
+    let quiet = 4;
+    let module = 3.0;
+    let side = (width + 2 * quiet) as f32 * module;
+    let (rect, _) = ui.allocate_exact_size(Vec2::splat(side), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+    for (i, color) in code.to_colors().iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let (x, y) = ((i % width + quiet) as f32, (i / width + quiet) as f32);
+        let min = rect.min + Vec2::new(x, y) * module;
+        painter.rect_filled(
+            egui::Rect::from_min_size(min, Vec2::splat(module)),
+            0.0,
+            egui::Color32::BLACK,
+        );
+    }
+}
+
+// Back switch-to-application-`id` up and unbind it, for a row that takes
+// over its <Super>N.
+fn clear_app_shortcut(backend: &dyn SettingsBackend, id: u32) -> Result<()> {
+    let key = format!("switch-to-application-{id}");
+    let old = backend.get(SHELL_KEYBINDINGS_SCHEMA, &key)?;
+    backup::backup_value(SHELL_KEYBINDINGS_SCHEMA, &key, &old)?;
+    backend.unbind(SHELL_KEYBINDINGS_SCHEMA, &key)
+}
+
+fn open_config_folder() -> Result<()> {
+    let dir = xdg::config_dir();
+    std::fs::create_dir_all(&dir)?;
+    spawn_detached(Command::new("xdg-open").arg(&dir))
+}
+
+fn open_gnome_keyboard_settings() -> Result<()> {
+    spawn_detached(Command::new("gnome-control-center").arg("keyboard"))
+}
+
+/// Start a program without blocking this window on it. It's waited for on
+/// its own thread, so it doesn't stay around as a zombie after it exits.
+fn spawn_detached(command: &mut Command) -> Result<()> {
+    let mut child = command.spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+impl MyApp {
+    pub fn new(
+        ctx: egui::Context,
+        log_panel: LogPanel,
+        jump: Jump,
+        safe_mode: bool,
+        force: bool,
+    ) -> Self {
+        let mut app = Self {
+            // finished jobs wake the UI up, nothing polls for them
+            worker: Worker::start(move || ctx.request_repaint()),
+            log_panel,
+            jump,
+            safe_mode,
+            ..Self::default()
+        };
+        app.preferences = Preferences::load();
+        app.history = History::load();
+        app.saved_macros = Macro::list();
+        app.profile_names = profile::named_profiles();
+        if let Some(path) = &app.preferences.active_profile {
+            app.active_profile_path = path.display().to_string();
+        }
+        #[cfg(feature = "update-check")]
+        if app.preferences.check_for_updates {
+            app.start_update_check();
+        }
+        app.keysyms = Keysyms::load();
+        app.model = Model::new();
+        if let Some(key) = &app.jump.focus {
+            if !app.model.rows.values().any(|row| row.gsettings_key == *key) {
+                app.status_bar.report(AppError::Validation(format!(
+                    "--focus: there is no row for {key}"
+                )));
+                app.jump.focus = None;
+            }
+        }
+        match environment::write_guard_reason() {
+            Some(reason) if force => tracing::info!("writing despite the session check: {reason}"),
+            Some(reason) => app.guard_writes(reason),
+            None => {}
+        }
+        if safe_mode {
+            tracing::info!("safe mode, not reading any settings");
+        } else {
+            app.load_settings();
+        }
+        app
+    }
+
+    // everything read from gsettings at startup
+    fn load_settings(&mut self) {
+        self.safe_mode = false;
+        let schemas = self.model.row_schemas();
+        self.read_in_background(move |backend| {
+            let known: BTreeMap<String, Vec<String>> = schemas
+                .into_iter()
+                .map(|schema| {
+                    let keys = backend.list_keys(&schema).unwrap_or_default();
+                    (schema, keys)
+                })
+                .collect();
+            move |app: &mut Self| app.model.drop_absent_rows(&known)
+        });
+        self.detect_locks();
+        self.read_in_background(|backend| {
+            let conflicts = ConflictIndex::scan(backend);
+            let layout = LayoutVariant::detect(backend);
+            move |app: &mut Self| {
+                app.model.set_conflicts(conflicts.clone());
+                app.conflicts = conflicts;
+                app.model.set_layout(layout);
+            }
+        });
+        self.get_gsettings_values_from_config();
+        self.read_workspace_settings();
+        self.read_app_keybindings();
+        self.read_window_behavior();
+        self.read_key_repeat();
+        self.read_gestures();
+        self.read_switcher_popup();
+        self.read_wraparound();
+        self.read_isolation();
+        self.read_in_background(|backend| {
+            let auto_move = AutoMoveEditor::load(backend);
+            move |app: &mut Self| app.auto_move = auto_move
+        });
+        self.run_health_checks();
+        self.read_in_background(|backend| {
+            match backup::snapshot(backend, &restorable_keys()) {
+                Ok(0) => {}
+                Ok(added) => tracing::info!(added, "backed up the original values"),
+                Err(e) => tracing::warn!("can't back up the original values: {:#}", e),
+            }
+            |_: &mut Self| {}
+        });
+    }
+
+    // Run `read` on the worker, after the jobs queued before it. What it
+    // returns runs on the UI thread once it's done, to take the values over.
+    fn read_in_background<F>(
+        &mut self,
+        read: impl FnOnce(&dyn SettingsBackend) -> F + Send + 'static,
+    ) where
+        F: FnOnce(&mut Self) + Send + 'static,
+    {
+        let backend = self.backend.clone();
+        self.worker
+            .run(move || Done::Read(Box::new(read(backend.as_ref()))));
+    }
+
+    fn detect_locks(&mut self) {
+        self.read_in_background(|backend| {
+            let locked_keys = backend::locked_keys(backend, &restorable_keys());
+            move |app: &mut Self| {
+                if !locked_keys.is_empty() {
+                    tracing::info!(count = locked_keys.len(), "keys are locked");
+                }
+                app.model.set_locked_keys(locked_keys);
+            }
+        });
+    }
+
+    fn read_app_keybindings(&mut self) {
+        // the defaults don't change while the app runs
+        let known_defaults: Vec<String> = self.model.app_defaults.keys().cloned().collect();
+        self.read_in_background(move |backend| {
+            let mut values = BTreeMap::new();
+            for id in 1..10 {
+                let key = format!("switch-to-application-{id}");
+                if let Ok(value) = backend.get(SHELL_KEYBINDINGS_SCHEMA, &key) {
+                    values.insert(id, value.trim().to_string());
+                }
+            }
+            let mut defaults = BTreeMap::new();
+            for (key, _) in APP_KEYBINDINGS {
+                if known_defaults.iter().any(|k| k == key) {
+                    continue;
+                }
+                match backend.default_value(SHELL_KEYBINDINGS_SCHEMA, key) {
+                    Ok(value) => {
+                        defaults.insert(key.to_string(), value.trim().to_string());
+                    }
+                    Err(e) => tracing::debug!(key, "no default: {:#}", e),
+                }
+            }
+            let favorites = match desktop_entries::dash_favorites(backend) {
+                Ok(favorites) => favorites,
+                Err(e) => {
+                    tracing::debug!("can't read the dash favorites: {:#}", e);
+                    vec![]
+                }
+            };
+            let names: BTreeMap<String, String> = if favorites.is_empty() {
+                BTreeMap::new()
+            } else {
+                desktop_entries::installed()
+                    .into_iter()
+                    .map(|e| (e.id, e.name))
+                    .collect()
+            };
+            move |app: &mut Self| {
+                app.model.app_keybindings.extend(values);
+                app.model.app_defaults.extend(defaults);
+                app.model.label_app_rows(&favorites, &names, &app.keysyms);
+            }
+        });
+    }
+
+    // unbound application rows can go back to GNOME's <Super>N
+    fn restore_default_button(&mut self, ui: &mut Ui, k: usize) {
+        let Some(row) = self.model.rows.get(&k) else {
+            return;
+        };
+        if row.schema != SHELL_KEYBINDINGS_SCHEMA
+            || !GSettings::is_empty_keybinding(&row.gsettings_value)
+        {
+            return;
+        }
+        let Some(default) = self.model.app_defaults.get(&row.gsettings_key).cloned() else {
+            return;
+        };
+        if GSettings::is_empty_keybinding(&default) {
+            return;
+        }
+        let (schema, key, old) = (
+            row.schema.clone(),
+            row.gsettings_key.clone(),
+            row.gsettings_value.clone(),
+        );
+        if !ui.small_button(format!("Re-enable {default}")).clicked() {
+            return;
+        }
+        let keys = [(schema.clone(), key.clone())];
+        let title = format!("Re-enable {key}");
+        self.undoable(
+            &title,
+            &keys,
+            move |backend| {
+                backup::backup_value(&schema, &key, &old)?;
+                backend.set_confirmed(&schema, &key, &default)
+            },
+            Self::report_write,
+        );
+        self.read_app_keybindings();
+    }
+
+    fn read_workspace_settings(&mut self) {
+        // through the backend, so preview mode shows the pending values
+        self.read_in_background(|backend| {
+            let count: Option<usize> = backend
+                .get(WM_PREFERENCES_SCHEMA, "num-workspaces")
+                .ok()
+                .and_then(|v| v.trim().parse().ok());
+            let dynamic = backend
+                .get(MUTTER_SCHEMA, "dynamic-workspaces")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(false);
+            let layout = Layout::detect(backend);
+            move |app: &mut Self| {
+                app.model.workspace_count = count;
+                if let Some(count) = count {
+                    app.num_of_workspaces = count.to_string();
+                }
+                app.model.dynamic_workspaces = dynamic;
+                app.count_edited = false;
+                app.workspace_layout = layout;
+            }
+        });
+    }
+
+    fn apply_workspace_settings(&mut self, num: usize) {
+        let keys = [
+            (MUTTER_SCHEMA.to_string(), "dynamic-workspaces".to_string()),
+            (
+                WM_PREFERENCES_SCHEMA.to_string(),
+                "num-workspaces".to_string(),
+            ),
+        ];
+        // the static count is ignored while dynamic workspaces are on, so
+        // turn them off first
+        let disable_dynamic = self.model.dynamic_workspaces && self.disable_dynamic_workspaces;
+        self.undoable(
+            &format!("Set {num} workspaces"),
+            &keys,
+            move |backend| {
+                if disable_dynamic {
+                    backend.set(MUTTER_SCHEMA, "dynamic-workspaces", "false")?;
+                }
+                backend.set(WM_PREFERENCES_SCHEMA, "num-workspaces", &num.to_string())
+            },
+            move |app, res| match res {
+                Ok(()) => {
+                    if disable_dynamic {
+                        app.record_step(Operation::Set {
+                            schema: MUTTER_SCHEMA.into(),
+                            key: "dynamic-workspaces".into(),
+                            value: "false".into(),
+                        });
+                    }
+                    app.record_step(Operation::SetWorkspaceCount { count: num });
+                }
+                Err(e) => app.status_bar.report(AppError::Settings(e)),
+            },
+        );
+        self.read_workspace_settings();
+    }
+
+    fn read_window_behavior(&mut self) {
+        self.read_in_background(|backend| {
+            let focus_mode = backend
+                .get(WM_PREFERENCES_SCHEMA, "focus-mode")
+                .and_then(|v| parse_string(&v))
+                .ok();
+            let auto_raise = backend
+                .get(WM_PREFERENCES_SCHEMA, "auto-raise")
+                .ok()
+                .and_then(|v| v.trim().parse().ok());
+            move |app: &mut Self| {
+                app.focus_mode = focus_mode;
+                app.auto_raise = auto_raise;
+            }
+        });
+    }
+
+    fn window_behavior_input(&mut self, ui: &mut Ui) {
+        let (Some(focus_mode), Some(auto_raise)) = (&self.focus_mode, self.auto_raise) else {
+            ui.weak("Window behavior settings are not available.");
+            return;
+        };
+
+        let mut new_focus_mode = focus_mode.clone();
+        let mut new_auto_raise = auto_raise;
+        ui.horizontal(|ui| {
+            ui.label("Focus mode");
+            let selected = FOCUS_MODES
+                .iter()
+                .find(|(mode, _)| *mode == new_focus_mode)
+                .map_or(new_focus_mode.as_str(), |(_, label)| label);
+            egui::ComboBox::from_id_source("focus_mode")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for (mode, label) in FOCUS_MODES {
+                        ui.selectable_value(&mut new_focus_mode, mode.to_string(), label);
+                    }
+                });
+            // auto-raise only applies when focus follows the mouse
+            ui.add_enabled(
+                new_focus_mode != "click",
+                egui::Checkbox::new(&mut new_auto_raise, "Raise focused windows"),
+            );
+        });
+
+        if &new_focus_mode == focus_mode && new_auto_raise == auto_raise {
+            return;
+        }
+        let focus_mode_changed = &new_focus_mode != focus_mode;
+        let keys = [
+            (WM_PREFERENCES_SCHEMA.to_string(), "focus-mode".to_string()),
+            (WM_PREFERENCES_SCHEMA.to_string(), "auto-raise".to_string()),
+        ];
+        // shown until the read after the write has the value
+        self.focus_mode = Some(new_focus_mode.clone());
+        self.auto_raise = Some(new_auto_raise);
+        self.undoable(
+            "Change window focus",
+            &keys,
+            move |backend| {
+                if focus_mode_changed {
+                    let value = format!("'{new_focus_mode}'");
+                    backend.set(WM_PREFERENCES_SCHEMA, "focus-mode", &value)
+                } else {
+                    let value = new_auto_raise.to_string();
+                    backend.set(WM_PREFERENCES_SCHEMA, "auto-raise", &value)
+                }
+            },
+            Self::report_write,
+        );
+        self.read_window_behavior();
+    }
+
+    fn read_key_repeat(&mut self) {
+        self.read_in_background(|backend| {
+            let key_repeat = match KeyRepeat::read(backend) {
+                Ok(repeat) => Some(repeat),
+                Err(e) => {
+                    tracing::warn!("can't read key repeat settings: {:#}", e);
+                    None
+                }
+            };
+            move |app: &mut Self| app.key_repeat = key_repeat
+        });
+    }
+
+    fn keyboard_input(&mut self, ui: &mut Ui) {
+        let Some(old) = self.key_repeat else {
+            ui.weak("Keyboard settings are not available.");
+            return;
+        };
+
+        let mut repeat = old;
+        let mut released = false;
+        ui.checkbox(&mut repeat.enabled, "Repeat keys");
+        ui.add_enabled_ui(repeat.enabled, |ui| {
+            for (label, value, range) in [
+                ("Delay (ms)", &mut repeat.delay, 100..=2000),
+                ("Interval (ms)", &mut repeat.interval, 10..=200),
+            ] {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    let response = ui.add(egui::DragValue::new(value).clamp_range(range));
+                    // written once the drag ends, not on every step
+                    released |=
+                        (response.changed() && !response.dragged()) || response.drag_released();
+                });
+            }
+        });
+
+        self.key_repeat = Some(repeat);
+        if repeat.enabled == old.enabled && !released {
+            return;
+        }
+        // compared with the system, the edited value changes while dragging
+        let keys = ["repeat", "delay", "repeat-interval"]
+            .map(|key| (KEYBOARD_SCHEMA.to_string(), key.to_string()));
+        self.undoable(
+            "Change key repeat",
+            &keys,
+            move |backend| {
+                let written = KeyRepeat::read(backend)?;
+                repeat.write_changes(&written, backend)
+            },
+            Self::report_write,
+        );
+        self.read_key_repeat();
+    }
+
+    fn read_gestures(&mut self) {
+        self.read_in_background(|backend| {
+            let gestures = match GestureInfo::read(backend) {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    tracing::warn!("can't read gesture extensions: {:#}", e);
+                    None
+                }
+            };
+            move |app: &mut Self| app.gestures = gestures
+        });
+    }
+
+    fn gestures_info(&mut self, ui: &mut Ui) {
+        let Some(info) = &self.gestures else {
+            ui.weak("GNOME Shell extension settings are not available.");
+            return;
+        };
+
+        if info.builtin_swipe_active() {
+            ui.label(
+                "Three-finger horizontal swipes switch workspaces, in addition to the shortcuts.",
+            );
+        } else if !info.wayland {
+            ui.label("GNOME has no built-in touchpad gestures on X11.");
+        } else {
+            ui.label("The built-in workspace swipe is turned off by an extension.");
+        }
+        if info.user_extensions_disabled {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "Extensions are disabled as a whole, none of the ones below are running.",
+            );
+        }
+
+        let mut toggled = None;
+        for (ext, state) in GESTURE_EXTENSIONS.iter().zip(&info.extensions) {
+            if state.installed {
+                let mut enabled = state.enabled;
+                if ui
+                    .checkbox(&mut enabled, ext.name)
+                    .on_hover_text(ext.description)
+                    .changed()
+                {
+                    toggled = Some((ext, enabled));
+                }
+            } else {
+                ui.weak(format!(
+                    "{} ({}) is not installed",
+                    ext.name, ext.description
+                ));
+            }
+        }
+
+        if let Some((ext, enabled)) = toggled {
+            let staged = extensions::stage_enabled(self.backend.as_ref(), ext.uuid, enabled);
+            self.propose_setting(format!("Toggle {}", ext.name), staged);
+        }
+    }
+
+    fn read_switcher_popup(&mut self) {
+        self.read_in_background(|backend| {
+            let hidden = extensions::is_installed(DISABLE_SWITCHER_POPUP_UUID).then(|| {
+                extensions::is_enabled(backend, DISABLE_SWITCHER_POPUP_UUID).unwrap_or(false)
+            });
+            let settings = switcher_popup::available(backend);
+            move |app: &mut Self| {
+                app.switcher_popup_hidden = hidden;
+                app.switcher_popup_settings = settings;
+            }
+        });
+    }
+
+    fn read_wraparound(&mut self) {
+        self.read_in_background(|backend| {
+            let wraparound = extensions::is_installed(WRAPAROUND_UUID)
+                .then(|| extensions::is_enabled(backend, WRAPAROUND_UUID).unwrap_or(false));
+            move |app: &mut Self| app.wraparound = wraparound
+        });
+    }
+
+    fn read_isolation(&mut self) {
+        self.read_in_background(|backend| {
+            let settings = isolation::available(backend);
+            move |app: &mut Self| app.isolation_settings = settings
+        });
+    }
+
+    // shown with the relative navigation rows, users setting them always
+    // ask what happens at the ends
+    fn wraparound_input(&mut self, ui: &mut Ui) {
+        let Some(enabled) = self.wraparound else {
+            ui.weak(
+                "Left on the first and right on the last workspace do nothing. \
+                 The Workspace Switch Wraparound extension makes them wrap around.",
+            );
+            return;
+        };
+        let mut wrap = enabled;
+        if ui
+            .checkbox(&mut wrap, "Wrap around at the first and last workspace")
+            .on_hover_text(WRAPAROUND_UUID)
+            .changed()
+        {
+            let staged = extensions::stage_enabled(self.backend.as_ref(), WRAPAROUND_UUID, wrap);
+            self.propose_setting("Toggle workspace wraparound".into(), staged);
+        }
+    }
+
+    fn isolation_input(&mut self, ui: &mut Ui) {
+        if self.isolation_settings.is_empty() {
+            ui.label("This GNOME Shell has no workspace isolation settings.");
+            return;
+        }
+        let mut commit = None;
+        for (i, setting) in self.isolation_settings.iter_mut().enumerate() {
+            let PopupValue::Bool(on) = &mut setting.value else {
+                continue;
+            };
+            if ui
+                .checkbox(on, setting.label)
+                .on_hover_text(format!("{} {}", setting.schema, setting.key))
+                .changed()
+            {
+                commit = Some(i);
+            }
+        }
+        if let Some(i) = commit {
+            let setting = &self.isolation_settings[i];
+            let title = format!("Change \"{}\"", setting.label);
+            let staged = setting.stage(self.backend.as_ref());
+            self.propose_setting(title, staged);
+            // shows the written value again once it's applied
+            self.read_isolation();
+        }
+    }
+
+    fn switcher_popup_input(&mut self, ui: &mut Ui) {
+        // dragged numbers are written once the drag ends
+        let mut commit = None;
+        for (i, setting) in self.switcher_popup_settings.iter_mut().enumerate() {
+            let response = match &mut setting.value {
+                PopupValue::Bool(b) => ui.checkbox(b, setting.label),
+                PopupValue::Int(n) => {
+                    ui.horizontal(|ui| {
+                        ui.label(setting.label);
+                        ui.add(egui::DragValue::new(n).clamp_range(0..=10_000).speed(10))
+                    })
+                    .inner
+                }
+            };
+            let response = response.on_hover_text(format!("{} {}", setting.schema, setting.key));
+            if (response.changed() && !response.dragged()) || response.drag_released() {
+                commit = Some(i);
+            }
+        }
+
+        match self.switcher_popup_hidden {
+            Some(hidden) => {
+                let mut show = !hidden;
+                if ui
+                    .checkbox(&mut show, "Show a popup when switching workspaces")
+                    .on_hover_text(DISABLE_SWITCHER_POPUP_UUID)
+                    .changed()
+                {
+                    let staged = extensions::stage_enabled(
+                        self.backend.as_ref(),
+                        DISABLE_SWITCHER_POPUP_UUID,
+                        !show,
+                    );
+                    self.propose_setting("Toggle the workspace switcher popup".into(), staged);
+                }
+            }
+            None if self.switcher_popup_settings.is_empty() => {
+                ui.label(
+                    "GNOME Shell always shows a popup when switching workspaces. \
+                     Install the Disable Workspace Switcher Popup extension to turn it off.",
+                );
+            }
+            None => {}
+        }
+
+        if let Some(i) = commit {
+            let setting = &self.switcher_popup_settings[i];
+            let title = format!("Change \"{}\"", setting.label);
+            let staged = setting.stage(self.backend.as_ref());
+            self.propose_setting(title, staged);
+            // shows the written value again once it's applied
+            self.read_switcher_popup();
+        }
+    }
+
+    // the count goes first so no binding points past the last workspace
+    // while the transaction is applied
+    fn all_settings_transaction(&self, num: usize) -> Result<Transaction> {
+        let backend = self.backend.as_ref();
+        let mut transaction = Transaction::new();
+        if self.model.dynamic_workspaces && self.disable_dynamic_workspaces {
+            transaction.stage(backend, MUTTER_SCHEMA, "dynamic-workspaces", "false")?;
+        }
+        transaction.stage(
+            backend,
+            WM_PREFERENCES_SCHEMA,
+            "num-workspaces",
+            &num.to_string(),
+        )?;
+        self.model.stage_edits(backend, &mut transaction)?;
+        Ok(transaction)
+    }
+
+    fn stage_all(&mut self, num: usize) {
+        let invalid = self.model.invalid_rows();
+        if !invalid.is_empty() {
+            self.status_bar.report(AppError::Validation(format!(
+                "Nothing applied: {}",
+                invalid.join("; ")
+            )));
+            return;
+        }
+        let res = self.all_settings_transaction(num).map(|transaction| {
+            // conflicts as they would be once everything is written, existing
+            // ones between keys this doesn't touch aren't its business
+            let conflicts: Vec<String> = self
+                .conflicts
+                .introduced_by(&transaction)
+                .into_iter()
+                .map(|((_, key), others)| {
+                    let names: Vec<String> = others
+                        .iter()
+                        .map(|(schema, key)| format!("{key} in {schema}"))
+                        .collect();
+                    format!("{key}: same shortcut as {}", names.join(", "))
+                })
+                .collect();
+            (transaction, conflicts)
+        });
+        match res {
+            Ok((_, conflicts)) if !conflicts.is_empty() => {
+                self.status_bar.report(AppError::Validation(format!(
+                    "Nothing applied, conflicting shortcuts: {}",
+                    conflicts.join("; ")
+                )));
+            }
+            Ok((transaction, _)) => self.propose(
+                &[Confirm::BulkApply],
+                "Apply workspace count and shortcuts".into(),
+                transaction,
+            ),
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+    }
+
+    fn section(&mut self, ui: &mut Ui, id: &str, title: &str) {
+        if id == "auto-move" && self.auto_move.is_none() {
+            return;
+        }
+        let jump = self.jump.section.as_deref() == Some(id);
+        let mut header = egui::CollapsingHeader::new(title);
+        if jump {
+            header = header.open(Some(true));
+        }
+        let response = header.show(ui, |ui| match id {
+            "profile" => self.active_profile_input(ui),
+            "profiles" => self.profiles_input(ui),
+            "presets" => self.presets_input(ui),
+            "macros" => self.macros_input(ui),
+            "custom" => self.custom_shortcuts_input(ui),
+            "windows" => self.window_behavior_input(ui),
+            "keyboard" => self.keyboard_input(ui),
+            "gestures" => self.gestures_info(ui),
+            "popup" => self.switcher_popup_input(ui),
+            "isolation" => self.isolation_input(ui),
+            "auto-move" => self.auto_move_input(ui),
+            "confirm" => self.confirm_policy_input(ui),
+            "accessibility" => self.accessibility_input(ui),
+            _ => unreachable!("section {id} has no content"),
+        });
+        if jump {
+            response
+                .header_response
+                .scroll_to_me(Some(egui::Align::TOP));
+            self.jump.section = None;
+        }
+    }
+
+    fn auto_move_input(&mut self, ui: &mut Ui) {
+        let Some(editor) = &mut self.auto_move else {
+            return;
+        };
+        if let Err(e) = editor.show(ui, self.backend.as_ref()) {
+            self.status_bar.report(AppError::Settings(e));
+        }
+    }
+
+    // Select and scroll to the row asked for with --focus, once the rows
+    // are loaded.
+    fn focus_row(&mut self, ui: &mut Ui, k: usize) {
+        let Some(key) = &self.jump.focus else {
+            return;
+        };
+        if self.model.rows[&k].gsettings_key != *key {
+            return;
+        }
+        self.selection.clear();
+        self.selection.click(k, egui::Modifiers::NONE, &[k]);
+        ui.scroll_to_cursor(Some(egui::Align::Center));
+        self.jump.focus = None;
+    }
+
+    fn selected_rows(&self) -> impl Iterator<Item = &KeybindingRow> {
+        self.selection
+            .rows
+            .iter()
+            .filter_map(|k| self.model.rows.get(k))
+    }
+
+    // Stages writes instead of doing them, so every batch operation goes
+    // through the usual preview.
+    fn stage_selection(
+        &mut self,
+        kinds: &[Confirm],
+        title: &str,
+        value: impl Fn(&KeybindingRow) -> Option<String>,
+    ) {
+        let mut transaction = Transaction::new();
+        let res = self.selected_rows().try_for_each(|row| match value(row) {
+            Some(v) => {
+                transaction.stage(self.backend.as_ref(), &row.schema, &row.gsettings_key, &v)
+            }
+            None => Ok(()),
+        });
+        match res {
+            Ok(()) if transaction.is_empty() => {
+                self.notice = Some(format!("{title}: nothing to change"));
+            }
+            Ok(()) => self.propose(kinds, title.into(), transaction),
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+    }
+
+    fn selection_toolbar(&mut self, ui: &mut Ui) {
+        if self.selection.is_empty() {
+            ui.weak("Click row labels to select them, with Ctrl or Shift for more.");
+            return;
+        }
+        let mut modifier = None;
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} selected", self.selection.rows.len()));
+            for (a, name) in [
+                (BatchAction::Apply, "Apply"),
+                (BatchAction::Clear, "Clear"),
+                (BatchAction::Reset, "Reset to original"),
+                (BatchAction::Export, "Export"),
+            ] {
+                if ui.button(name).clicked() {
+                    action = Some(a);
+                }
+            }
+            ui.menu_button("Set modifiers", |ui| {
+                modifier_checkboxes(ui, &mut self.batch_modifiers);
+                if ui.button("Set on selected rows").clicked() {
+                    modifier = Some(self.batch_modifiers);
+                    ui.close_menu();
+                }
+            });
+            if ui.button("Select none").clicked() {
+                action = Some(BatchAction::SelectNone);
+            }
+        });
+
+        if let Some(modifiers) = modifier {
+            // only edits the rows, Apply writes them
+            for k in &self.selection.rows {
+                if let Some(row) = self.model.rows.get_mut(k) {
+                    let key = row.keybinding.clone();
+                    row.assign(modifiers, &key, &self.keysyms);
+                }
+            }
+            self.model.invalidate();
+        }
+        match action {
+            Some(BatchAction::Apply) => {
+                self.stage_selection(&[Confirm::BulkApply], "Apply selected rows", |row| {
+                    (!row.is_incomplete() && row.is_modified())
+                        .then(|| row.converted_keybinding.clone())
+                })
+            }
+            Some(BatchAction::Clear) => self.stage_selection(
+                &[Confirm::BulkApply, Confirm::Clear],
+                "Clear selected rows",
+                |row| {
+                    (!GSettings::is_empty_keybinding(&row.gsettings_value))
+                        .then(|| EMPTY_KEYBINDING.to_string())
+                },
+            ),
+            Some(BatchAction::Reset) => match backup::read_backup() {
+                // the value from before this app first changed the key
+                Ok(backup) => self.stage_selection(
+                    &[Confirm::BulkApply, Confirm::Reset],
+                    "Reset selected rows",
+                    |row| {
+                        backup
+                            .iter()
+                            .find(|(s, k, _)| *s == row.schema && *k == row.gsettings_key)
+                            .map(|(_, _, v)| v.clone())
+                    },
+                ),
+                Err(e) => self.status_bar.report(AppError::Parse(e)),
+            },
+            Some(BatchAction::Export) => {
+                let mut profile = Profile::default();
+                for row in self.selected_rows() {
+                    profile.set(&row.schema, &row.gsettings_key, row.gsettings_value.trim());
+                }
+                match share::encode(&profile) {
+                    Ok(code) => self.share_code = Some(code),
+                    Err(e) => self.status_bar.report(AppError::Internal(format!("{e:#}"))),
+                }
+            }
+            Some(BatchAction::SelectNone) => self.selection.clear(),
+            None => {}
+        }
+    }
+
+    // the number row and the letters, each key marked when something in
+    // any keybinding schema already uses it with the chosen modifiers
+    fn conflict_strip(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Keys taken with");
+            modifier_checkboxes(ui, &mut self.strip_modifiers);
+        });
+        let taken_fill = ui.visuals().error_fg_color.linear_multiply(0.35);
+        for keys in ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"] {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                for key in keys.chars() {
+                    let accelerator = Accelerator::new(self.strip_modifiers, &key.to_string());
+                    let users = self.conflicts.users(&accelerator);
+                    let text =
+                        egui::RichText::new(key.to_ascii_uppercase().to_string()).monospace();
+                    let hover = if users.is_empty() {
+                        format!("{} is free", accelerator.format())
+                    } else {
+                        let names: Vec<String> =
+                            users.iter().map(|(s, k)| format!("{s} {k}")).collect();
+                        format!("{} is used by {}", accelerator.format(), names.join(", "))
+                    };
+                    // struck through too, not told by color alone
+                    let text = if users.is_empty() {
+                        text
+                    } else {
+                        text.strikethrough().background_color(taken_fill)
+                    };
+                    ui.add(egui::Label::new(text).sense(egui::Sense::hover()))
+                        .on_hover_text(hover);
+                }
+            });
+        }
+    }
+
+    fn quick_assign_bar(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let mut active = self.quick_assign.is_some();
+            if ui
+                .toggle_value(&mut active, "Quick assign")
+                .on_hover_text(
+                    "Press a key for each row in turn, the edits are applied with Apply all…",
+                )
+                .changed()
+            {
+                self.quick_assign = active.then_some(0);
+                // keep the key presses out of a text field that had focus
+                ui.memory_mut(|m| m.stop_text_input());
+            }
+            let Some(k) = self.quick_assign else {
+                return;
+            };
+            ui.label("Modifiers");
+            modifier_checkboxes(ui, &mut self.quick_assign_base);
+            if let Some(row) = self.model.rows.get(&k) {
+                ui.strong(format!("Press the key for \"{}\"", row.label));
+            }
+            ui.weak("Shift, Ctrl and Alt held down are added, Esc stops");
+        });
+    }
+
+    fn handle_quick_assign(&mut self, ctx: &egui::Context) {
+        let Some(k) = self.quick_assign else {
+            return;
+        };
+        let presses: Vec<(egui::Key, egui::Modifiers)> = ctx.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|e| match e {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+                .collect()
+        });
+        let Some((key, pressed)) = presses.into_iter().next() else {
+            return;
+        };
+        if key == egui::Key::Escape {
+            self.quick_assign = None;
+            return;
+        }
+        let Some(name) = key_name(key) else {
+            return;
+        };
+
+        let base = self.quick_assign_base;
+        let modifiers = Modifiers {
+            shift: base.shift || pressed.shift,
+            ctrl: base.ctrl || pressed.ctrl,
+            alt: base.alt || pressed.alt,
+            super_key: base.super_key,
+        };
+        let Some(row) = self.model.rows.get_mut(&k) else {
+            self.quick_assign = None;
+            return;
+        };
+        row.assign(modifiers, &name, &self.keysyms);
+        self.model.invalidate();
+        #[cfg(any(feature = "portal", feature = "x11"))]
+        self.availability_hints.reset(k);
+
+        self.quick_assign = self.model.rows.range(k + 1..).next().map(|(next, _)| *next);
+    }
+
+    fn share_window(&mut self, ctx: &egui::Context) {
+        let Some(code) = &self.share_code else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new("Share profile")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label("Paste this code into \"Import share code…\" on another machine.");
+                egui::ScrollArea::vertical()
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            TextEdit::multiline(&mut code.as_str())
+                                .code_editor()
+                                .desired_width(400.0),
+                        );
+                    });
+                if ui.button("Copy to clipboard").clicked() {
+                    ui.output_mut(|o| o.copied_text = code.clone());
+                }
+                #[cfg(feature = "qr")]
+                qr_code(ui, code);
+            });
+        if !open {
+            self.share_code = None;
+        }
+    }
+
+    fn share_import_dialog(&mut self, ctx: &egui::Context) {
+        let Some(code) = &mut self.share_import else {
+            return;
+        };
+        let mut open = true;
+        let mut stage = false;
+        egui::Window::new("Import share code")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.add(
+                    TextEdit::multiline(code)
+                        .code_editor()
+                        .hint_text("gwsm1:…")
+                        .desired_width(400.0),
+                );
+                stage = ui
+                    .add_enabled(!code.trim().is_empty(), egui::Button::new("Preview import"))
+                    .clicked();
+            });
+
+        if stage {
+            let res = share::decode(code).and_then(|p| p.stage(self.backend.as_ref()));
+            match res {
+                Ok(transaction) => {
+                    self.propose(
+                        &[Confirm::BulkApply],
+                        "Import share code".into(),
+                        transaction,
+                    );
+                    self.share_import = None;
+                }
+                Err(e) => self.status_bar.report(AppError::Parse(e)),
+            }
+        } else if !open {
+            self.share_import = None;
+        }
+    }
+
+    fn migration_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.migration else {
+            return;
+        };
+
+        let mut open = true;
+        let mut check = false;
+        let mut apply = false;
+        egui::Window::new("Migrate a scheme")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "A scheme exported on another GNOME version, keys renamed since are moved.",
+                );
+                ui.horizontal(|ui| {
+                    ui.label("File");
+                    if ui
+                        .add(TextEdit::singleline(&mut dialog.path).desired_width(400.0))
+                        .changed()
+                    {
+                        dialog.plan = None;
+                    }
+                    check = ui
+                        .add_enabled(!dialog.path.trim().is_empty(), egui::Button::new("Check"))
+                        .clicked();
+                });
+                let Some(plan) = &dialog.plan else {
+                    return;
+                };
+                ui.separator();
+                ui.label(format!(
+                    "{} change(s), {} unmanaged setting(s) left out.",
+                    plan.transaction.changes.len(),
+                    plan.skipped
+                ));
+                if !plan.renamed.is_empty() {
+                    ui.strong("Moved to their new key");
+                    for (old, new) in &plan.renamed {
+                        ui.label(format!("{old} → {new}"));
+                    }
+                }
+                if !plan.unmappable.is_empty() {
+                    ui.strong("Not on this GNOME version, left out");
+                    for key in &plan.unmappable {
+                        ui.colored_label(ui.visuals().warn_fg_color, key);
+                    }
+                }
+                apply = ui
+                    .add_enabled(
+                        !plan.transaction.is_empty(),
+                        egui::Button::new("Apply the rest…"),
+                    )
+                    .clicked();
+            });
+
+        if check {
+            let path = PathBuf::from(dialog.path.trim());
+            match migration::migrate_file(&path, self.backend.as_ref()) {
+                Ok(plan) => dialog.plan = Some(plan),
+                Err(e) => self.status_bar.report(AppError::Parse(e)),
+            }
+        } else if apply {
+            let path = dialog.path.trim().to_string();
+            if let Some(plan) = self.migration.take().and_then(|d| d.plan) {
+                self.propose(
+                    &[Confirm::BulkApply],
+                    format!("Migrate scheme from {path}"),
+                    plan.transaction,
+                );
+            }
+        } else if !open {
+            self.migration = None;
+        }
+    }
+
+    fn import_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = &mut self.import_path else {
+            return;
+        };
+
+        let mut open = true;
+        let mut stage = false;
+        egui::Window::new("Import")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let formats: Vec<&str> = IMPORTERS.iter().map(|i| i.name()).collect();
+                ui.label(format!("A {}, the format is detected.", formats.join(", ")));
+                ui.horizontal(|ui| {
+                    ui.label("File");
+                    ui.add(TextEdit::singleline(path).desired_width(400.0));
+                });
+                stage = ui
+                    .add_enabled(!path.trim().is_empty(), egui::Button::new("Preview import"))
+                    .clicked();
+            });
+
+        if stage {
+            let path = PathBuf::from(path.trim());
+            match import::import_file(&path) {
+                Ok(imported) => {
+                    if imported.skipped > 0 {
+                        tracing::info!(imported.skipped, "not importing unmanaged settings");
+                    }
+                    let staged = imported.stage(self.backend.as_ref());
+                    if !staged.failed.is_empty() {
+                        self.status_bar.report(AppError::Settings(anyhow::anyhow!(
+                            "{} imported key(s) left out: {}",
+                            staged.failed.len(),
+                            staged.failed.join("; ")
+                        )));
+                    }
+                    self.propose(
+                        &[Confirm::BulkApply],
+                        format!("Import {} from {}", imported.format, path.display()),
+                        staged.transaction,
+                    );
+                    self.import_path = None;
+                }
+                Err(e) => self.status_bar.report(AppError::Parse(e)),
+            }
+        } else if !open {
+            self.import_path = None;
+        }
+    }
+
+    fn profile_compare_window(&mut self, ctx: &egui::Context) {
+        let Some(compare) = &mut self.profile_compare else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new("Compare profiles")
+            .open(&mut open)
+            .show(ctx, |ui| match compare.show(ui) {
+                Ok(Some(notice)) => self.notice = Some(notice),
+                Ok(None) => {}
+                Err(e) => self.status_bar.report(AppError::Io(e)),
+            });
+        if !open {
+            self.profile_compare = None;
+        }
+    }
+
+    fn profiles_input(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.add(TextEdit::singleline(&mut self.new_profile_name).desired_width(200.0));
+            let name = self.new_profile_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save current settings"))
+                .on_hover_text("Replaces a profile of the same name")
+                .clicked()
+            {
+                let res = Profile::capture(self.backend.as_ref()).save_named(&name);
+                match res {
+                    Ok(path) => {
+                        self.notice = Some(format!("Saved {}", path.display()));
+                        self.new_profile_name.clear();
+                    }
+                    Err(e) => self.status_bar.report(AppError::Io(e)),
+                }
+                self.profile_names = profile::named_profiles();
+            }
+        });
+
+        if self.profile_names.is_empty() {
+            ui.weak("No profiles saved yet.");
+            return;
+        }
+        let mut load = None;
+        // the outcome of a rename or delete, None when neither happened
+        let mut res = None;
+        egui::Grid::new("profiles").show(ui, |ui| {
+            for name in &self.profile_names {
+                match (&mut self.profile_rename, &self.profile_delete) {
+                    (Some((old, new)), _) if old == name => {
+                        ui.add(TextEdit::singleline(new).desired_width(200.0));
+                        if ui.small_button("Rename").clicked() {
+                            res = Some(profile::rename_named(old, new));
+                            self.profile_rename = None;
+                        } else if ui.small_button("Cancel").clicked() {
+                            self.profile_rename = None;
+                        }
+                    }
+                    (_, Some(delete)) if delete == name => {
+                        ui.label(format!("Delete {name}?"));
+                        if ui.small_button("Delete").clicked() {
+                            res = Some(profile::delete_named(name));
+                            self.profile_delete = None;
+                        } else if ui.small_button("Keep").clicked() {
+                            self.profile_delete = None;
+                        }
+                    }
+                    _ => {
+                        ui.label(name);
+                        if ui.small_button("Load").clicked() {
+                            load = Some(name.clone());
+                        }
+                        if ui.small_button("Rename").clicked() {
+                            self.profile_rename = Some((name.clone(), name.clone()));
+                        }
+                        if ui.small_button("Delete").clicked() {
+                            self.profile_delete = Some(name.clone());
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(res) = res {
+            if let Err(e) = res {
+                self.status_bar.report(AppError::Io(e));
+            }
+            self.profile_names = profile::named_profiles();
+        }
+        if let Some(name) = load {
+            self.load_profile(&name);
+        }
+    }
+
+    fn load_profile(&mut self, name: &str) {
+        let res = Profile::load_named(name).and_then(|p| p.stage(self.backend.as_ref()));
+        match res {
+            Ok(transaction) if transaction.is_empty() => {
+                self.notice = Some(format!("The system already matches {name}"));
+            }
+            Ok(transaction) => self.propose(
+                &[Confirm::BulkApply],
+                format!("Load profile {name}"),
+                transaction,
+            ),
+            Err(e) => self.status_bar.report(AppError::Parse(e)),
+        }
+    }
+
+    fn macros_input(&mut self, ui: &mut Ui) {
+        if let Some(recording) = &self.recording {
+            let status = format!(
+                "⏺ Recording \"{}\", {} steps",
+                recording.name,
+                recording.steps.len()
+            );
+            let mut stop = None;
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, status);
+                if ui.button("Stop and save").clicked() {
+                    stop = Some(true);
+                } else if ui.button("Discard").clicked() {
+                    stop = Some(false);
+                }
+            });
+            if let Some(save) = stop {
+                self.stop_recording(save);
+            }
+        } else {
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.add(TextEdit::singleline(&mut self.macro_name).desired_width(200.0));
+                let name = self.macro_name.trim();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new("Start recording"))
+                    .on_hover_text("Every change made from now on is added to the macro, in order")
+                    .clicked()
+                {
+                    self.recording = Some(Macro::new(name));
+                }
+            });
+        }
+
+        if self.saved_macros.is_empty() {
+            ui.weak("No macros saved yet.");
+        }
+        let mut replay = None;
+        egui::Grid::new("macros").show(ui, |ui| {
+            for name in &self.saved_macros {
+                ui.label(name);
+                if ui.small_button("Replay").clicked() {
+                    replay = Some(name.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(name) = replay {
+            self.replay_macro(&name);
+        }
+    }
+
+    fn stop_recording(&mut self, save: bool) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        if !save {
+            return;
+        }
+        match recording.save() {
+            Ok(path) => {
+                self.notice = Some(format!(
+                    "Saved {} steps to {}, steps can be made conditional there with \"when\"",
+                    recording.steps.len(),
+                    path.display()
+                ));
+                self.macro_name.clear();
+            }
+            Err(e) => self.status_bar.report(AppError::Io(e)),
+        }
+        self.saved_macros = Macro::list();
+    }
+
+    fn replay_macro(&mut self, name: &str) {
+        let m = match Macro::find(name) {
+            Ok(m) => m,
+            Err(e) => {
+                let e = e.context(name.to_string());
+                return self.status_bar.report(AppError::Settings(e));
+            }
+        };
+        let keys: Vec<(String, String)> =
+            m.steps.iter().flat_map(|s| s.operation.targets()).collect();
+        let name = name.to_string();
+        self.undoable(
+            &format!("Replay {name}"),
+            &keys.clone(),
+            move |backend| m.replay(backend),
+            move |app, res| match res {
+                Ok(replay) => {
+                    for (schema, key) in &keys {
+                        app.record_write(schema, key);
+                    }
+                    app.notice = Some(format!(
+                        "Replayed {name}: {} steps ran, {} skipped",
+                        replay.ran.len(),
+                        replay.skipped.len()
+                    ));
+                }
+                Err(e) => app.status_bar.report(AppError::Settings(e.context(name))),
+            },
+        );
+        self.reread_settings();
+    }
+
+    fn active_profile_input(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Profile file");
+            ui.add(TextEdit::singleline(&mut self.active_profile_path).desired_width(400.0));
+        });
+        let path = PathBuf::from(self.active_profile_path.trim());
+        let has_path = !self.active_profile_path.trim().is_empty();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(has_path, egui::Button::new("Save current settings"))
+                .clicked()
+            {
+                let res = Profile::capture(self.backend.as_ref()).save(&path);
+                match res.and_then(|()| self.set_active_profile(&path)) {
+                    Ok(()) => self.notice = Some(format!("Saved {}", path.display())),
+                    Err(e) => self.status_bar.report(AppError::Io(e)),
+                }
+            }
+            if ui
+                .add_enabled(has_path, egui::Button::new("Save changes from defaults"))
+                .on_hover_text(
+                    "Only the keys you changed, applying it resets every other managed key",
+                )
+                .clicked()
+            {
+                let res = Profile::capture(self.backend.as_ref())
+                    .minimal(self.backend.as_ref())
+                    .save(&path);
+                match res.and_then(|()| self.set_active_profile(&path)) {
+                    Ok(()) => self.notice = Some(format!("Saved {}", path.display())),
+                    Err(e) => self.status_bar.report(AppError::Io(e)),
+                }
+            }
+            if ui
+                .add_enabled(has_path, egui::Button::new("Check drift"))
+                .on_hover_text("Same as running with --check")
+                .clicked()
+            {
+                match drift::check_file(&path, self.backend.as_ref()) {
+                    Ok(drifts) => {
+                        self.drift = Some(drifts);
+                        if let Err(e) = self.set_active_profile(&path) {
+                            self.status_bar.report(AppError::Io(e));
+                        }
+                    }
+                    Err(e) => self.status_bar.report(AppError::Parse(e)),
+                }
+            }
+        });
+    }
+
+    fn set_active_profile(&mut self, path: &Path) -> Result<()> {
+        if self.preferences.active_profile.as_deref() == Some(path) {
+            return Ok(());
+        }
+        self.preferences.active_profile = Some(path.to_path_buf());
+        self.preferences.save()
+    }
+
+    fn drift_window(&mut self, ctx: &egui::Context) {
+        let Some(drifts) = &self.drift else {
+            return;
+        };
+        let mut open = true;
+        let mut restore = false;
+        egui::Window::new("Drift")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if drifts.is_empty() {
+                    ui.label("The system matches the profile");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("drift").striped(true).show(ui, |ui| {
+                            ui.strong("Key");
+                            ui.strong("Profile");
+                            ui.strong("System");
+                            ui.end_row();
+                            for d in drifts {
+                                ui.label(&d.key).on_hover_text(&d.schema);
+                                ui.label(&d.expected);
+                                ui.label(d.actual.as_deref().unwrap_or("unreadable"));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                if ui.button("Copy report").clicked() {
+                    let path = PathBuf::from(self.active_profile_path.trim());
+                    ui.output_mut(|o| o.copied_text = drift::report(&path, drifts));
+                }
+                restore = ui.button("Restore profile values…").clicked();
+            });
+
+        if restore {
+            let mut transaction = Transaction::new();
+            let res = drifts.iter().try_for_each(|d| {
+                transaction.stage(self.backend.as_ref(), &d.schema, &d.key, &d.expected)
+            });
+            match res {
+                Ok(()) => {
+                    self.propose(&[Confirm::BulkApply], "Restore profile".into(), transaction);
+                    self.drift = None;
+                }
+                Err(e) => self.status_bar.report(AppError::Settings(e)),
+            }
+        } else if !open {
+            self.drift = None;
+        }
+    }
+
+    fn read_custom_shortcuts(&mut self) -> Result<()> {
+        let shortcuts = custom_shortcuts::read_all(self.backend.as_ref())?;
+        self.custom_edits = shortcuts.clone();
+        self.custom_shortcuts = Some(shortcuts);
+        Ok(())
+    }
+
+    fn custom_shortcuts_input(&mut self, ui: &mut Ui) {
+        if ui
+            .button("Import from GNOME Settings")
+            .on_hover_text(
+                "Custom shortcuts from Settings → Keyboard, they are saved with profiles",
+            )
+            .clicked()
+        {
+            match self.read_custom_shortcuts() {
+                Ok(()) => {
+                    self.notice = Some(format!(
+                        "Imported {} custom shortcut(s)",
+                        self.custom_edits.len()
+                    ));
+                }
+                Err(e) => self.status_bar.report(AppError::Parse(e)),
+            }
+        }
+        let Some(shortcuts) = &self.custom_shortcuts else {
+            return;
+        };
+        // an empty binding leaves the shortcut disabled
+        let valid = |c: &CustomShortcut| {
+            !c.name.trim().is_empty()
+                && !c.command.trim().is_empty()
+                && (c.binding.is_empty() || Accelerator::parse(&c.binding).is_ok())
+        };
+        let mut save = None;
+        let mut remove = None;
+        let mut add = false;
+        egui::Grid::new("custom_shortcuts")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Name");
+                ui.strong("Command");
+                ui.strong("Shortcut");
+                ui.end_row();
+                for (i, (edit, saved)) in self.custom_edits.iter_mut().zip(shortcuts).enumerate() {
+                    ui.add(TextEdit::singleline(&mut edit.name).desired_width(160.0))
+                        .on_hover_text(&edit.path);
+                    ui.add(TextEdit::singleline(&mut edit.command).code_editor());
+                    ui.add(
+                        TextEdit::singleline(&mut edit.binding)
+                            .hint_text("disabled")
+                            .desired_width(160.0),
+                    );
+                    let changed = edit != saved;
+                    if ui
+                        .add_enabled(changed && valid(edit), egui::Button::new("Save"))
+                        .clicked()
+                    {
+                        save = Some(i);
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+                let new = &mut self.new_custom;
+                ui.add(
+                    TextEdit::singleline(&mut new.name)
+                        .hint_text("New shortcut")
+                        .desired_width(160.0),
+                );
+                ui.add(
+                    TextEdit::singleline(&mut new.command)
+                        .hint_text("command")
+                        .code_editor(),
+                );
+                ui.add(
+                    TextEdit::singleline(&mut new.binding)
+                        .hint_text("<Super>Return")
+                        .desired_width(160.0),
+                );
+                add = ui
+                    .add_enabled(valid(new), egui::Button::new("Add"))
+                    .on_hover_text("Needs a name and a command, the shortcut may stay empty")
+                    .clicked();
+                ui.end_row();
+            });
+
+        let backend = self.backend.as_ref();
+        let staged = if let Some(i) = save {
+            let edit = &self.custom_edits[i];
+            let mut transaction = Transaction::new();
+            let res = edit.stage(backend, &mut transaction);
+            Some((
+                Confirm::Overwrite,
+                format!("Change custom shortcut {}", edit.name),
+                res.map(|()| transaction),
+            ))
+        } else if let Some(i) = remove {
+            let saved = &shortcuts[i];
+            Some((
+                Confirm::Clear,
+                format!("Remove custom shortcut {}", saved.name),
+                saved.stage_remove(backend),
+            ))
+        } else if add {
+            let paths: Vec<String> = shortcuts.iter().map(|c| c.path.clone()).collect();
+            let new = &self.new_custom;
+            let shortcut =
+                CustomShortcut::new_at_free_path(&paths, &new.name, &new.command, &new.binding);
+            Some((
+                Confirm::Overwrite,
+                format!("Add custom shortcut {}", shortcut.name),
+                shortcut.stage_add(backend),
+            ))
+        } else {
+            None
+        };
+        let Some((kind, title, res)) = staged else {
+            return;
+        };
+        match res {
+            Ok(transaction) => {
+                if add {
+                    self.new_custom = CustomShortcut::default();
+                }
+                self.propose(&[kind], title, transaction);
+            }
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+    }
+
+    fn presets_input(&mut self, ui: &mut Ui) {
+        egui::Grid::new("presets").show(ui, |ui| {
+            for preset in Preset::builtin() {
+                ui.strong(&preset.name);
+                ui.label(&preset.description);
+                if ui
+                    .button("Fill rows")
+                    .on_hover_text("Put the preset into the rows, to edit before Apply all…")
+                    .clicked()
+                {
+                    self.fill_rows(&preset);
+                }
+                let hover = if preset.clear_app_shortcuts {
+                    "Write the preset and clear the switch-to-application shortcuts it would clash with"
+                } else {
+                    "Write the preset"
+                };
+                if ui.button("Apply…").on_hover_text(hover).clicked() {
+                    self.stage_preset(&preset);
+                }
+                ui.end_row();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Template");
+            ui.add(
+                TextEdit::singleline(&mut self.preset_template)
+                    .hint_text("switch=<Super>{n}; move=<Shift><Super>{n}; n=1..10")
+                    .desired_width(360.0),
+            );
+            let enabled = !self.preset_template.trim().is_empty();
+            let fill = ui
+                .add_enabled(enabled, egui::Button::new("Fill rows"))
+                .clicked();
+            let apply = ui
+                .add_enabled(enabled, egui::Button::new("Apply…"))
+                .clicked();
+            if fill || apply {
+                match Preset::from_template(&self.preset_template) {
+                    Ok(preset) if fill => self.fill_rows(&preset),
+                    Ok(preset) => self.stage_preset(&preset),
+                    Err(e) => self.status_bar.report(AppError::Parse(e)),
+                }
+            }
+        });
+    }
+
+    // edits only, nothing is written until Apply all…
+    fn fill_rows(&mut self, preset: &Preset) {
+        for (key, value) in &preset.bindings {
+            let Ok(Some(accelerator)) = Accelerator::from_gsettings(value) else {
+                continue;
+            };
+            let found = self
+                .model
+                .rows
+                .iter()
+                .find(|(_, row)| row.schema == WM_KEYBINDINGS_SCHEMA && row.gsettings_key == *key)
+                .map(|(k, _)| *k);
+            let Some(k) = found else {
+                continue;
+            };
+            if let Some(row) = self.model.rows.get_mut(&k) {
+                row.assign(accelerator.modifiers, &accelerator.key, &self.keysyms);
+            }
+            self.model.invalidate();
+            #[cfg(any(feature = "portal", feature = "x11"))]
+            self.availability_hints.reset(k);
+        }
+        self.notice = Some(format!(
+            "Filled the rows with the {} preset, Apply all… writes them",
+            preset.name
+        ));
+    }
+
+    fn stage_reset(&mut self, title: &str, transaction: Result<Transaction>) {
+        match transaction {
+            Ok(transaction) if transaction.is_empty() => {
+                self.notice = Some(format!("{title}: nothing to change"))
+            }
+            Ok(transaction) => self.propose(
+                &[Confirm::BulkApply, Confirm::Reset],
+                title.into(),
+                transaction,
+            ),
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+    }
+
+    fn stage_preset(&mut self, preset: &Preset) {
+        match preset.stage(self.backend.as_ref()) {
+            Ok(transaction) => self.propose(
+                &[Confirm::BulkApply],
+                format!("Apply {} preset", preset.name),
+                transaction,
+            ),
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+    }
+
+    fn preview_transaction(&mut self, title: String, transaction: Transaction) {
+        let selected = vec![true; transaction.changes.len()];
+        self.pending_transaction = Some((title, transaction, selected));
+    }
+
+    fn transaction_preview(&mut self, ctx: &egui::Context) {
+        let Some((title, transaction, selected)) = &mut self.pending_transaction else {
+            return;
+        };
+
+        let mut apply = false;
+        let mut cancel = false;
+        egui::Window::new(title.as_str())
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("transaction_preview")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (change, selected) in
+                                    transaction.changes.iter().zip(selected.iter_mut())
+                                {
+                                    ui.checkbox(selected, "");
+                                    // rows are easier to recognize by their label
+                                    let row = self.model.rows.values().find(|r| {
+                                        r.schema == change.schema && r.gsettings_key == change.key
+                                    });
+                                    match row {
+                                        Some(row) => {
+                                            ui.label(&row.label).on_hover_text(format!(
+                                                "{} {}",
+                                                change.schema, change.key
+                                            ));
+                                        }
+                                        None => {
+                                            ui.label(format!("{} {}", change.schema, change.key));
+                                        }
+                                    }
+                                    ui.label(&change.old_value);
+                                    ui.label("→");
+                                    ui.label(&change.new_value);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if ui
+                    .checkbox(
+                        &mut self.preferences.confirm_bulk_applies,
+                        format!(
+                            "Revert unless confirmed within {} seconds",
+                            REVERT_TIMEOUT.as_secs()
+                        ),
+                    )
+                    .changed()
+                {
+                    if let Err(e) = self.preferences.save() {
+                        self.status_bar.report(AppError::Io(e));
+                    }
+                }
+                ui.horizontal(|ui| {
+                    let count = selected.iter().filter(|s| **s).count();
+                    let label = if count == selected.len() {
+                        "Apply".to_string()
+                    } else {
+                        format!("Apply {count} of {}", selected.len())
+                    };
+                    apply = ui
+                        .add_enabled(count > 0, egui::Button::new(label))
+                        .clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if apply {
+            let (title, transaction) = (title.clone(), transaction.only(selected));
+            self.apply_transaction(&title, transaction);
+        }
+        if apply || cancel {
+            self.pending_transaction = None;
+        }
+    }
+
+    // Previewed when the confirmation policy asks about any of `kinds`,
+    // applied right away otherwise.
+    fn propose(&mut self, kinds: &[Confirm], title: String, transaction: Transaction) {
+        let writable: Vec<bool> = transaction
+            .changes
+            .iter()
+            .map(|c| {
+                !self
+                    .model
+                    .locked_keys
+                    .iter()
+                    .any(|(schema, key)| *schema == c.schema && *key == c.key)
+            })
+            .collect();
+        let skipped = writable.iter().filter(|w| !**w).count();
+        let transaction = transaction.only(&writable);
+        if skipped > 0 {
+            self.notice = Some(format!(
+                "Skipping {skipped} setting(s) managed by your administrator"
+            ));
+            if transaction.is_empty() {
+                return;
+            }
+        }
+        if self.preferences.confirm.requires(kinds) {
+            self.preview_transaction(title, transaction);
+        } else {
+            self.apply_transaction(&title, transaction);
+        }
+    }
+
+    // A toggle or value outside the rows, asked about like an overwritten
+    // row and undoable like any other apply.
+    fn propose_setting(&mut self, title: String, staged: Result<Transaction>) {
+        match staged {
+            Ok(transaction) => self.propose(&[Confirm::Overwrite], title, transaction),
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+    }
+
+    fn apply_transaction(&mut self, title: &str, transaction: Transaction) {
+        let keys: Vec<(&str, &str)> = transaction
+            .changes
+            .iter()
+            .map(|c| (c.schema.as_str(), c.key.as_str()))
+            .collect();
+        for row in self.model.rows.values_mut() {
+            if keys.contains(&(row.schema.as_str(), row.gsettings_key.as_str())) {
+                row.pending = true;
+            }
+        }
+        let backend = self.backend.clone();
+        let title = title.to_string();
+        self.worker.run(move || {
+            let res = transaction.apply(backend.as_ref());
+            Done::Applied {
+                title,
+                transaction,
+                res,
+            }
+        });
+    }
+
+    fn finish_apply(&mut self, title: &str, transaction: Transaction, res: Result<()>) {
+        match res {
+            Ok(()) => {
+                self.notice = Some(format!("Wrote {} settings", transaction.changes.len()));
+                self.record_writes(&transaction);
+                self.undo.push(title, transaction.clone());
+                if self.preferences.confirm_bulk_applies {
+                    let deadline = Instant::now() + REVERT_TIMEOUT;
+                    self.unconfirmed_transaction = Some((transaction, deadline));
+                }
+            }
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+        self.reread_settings();
+    }
+
+    // A row write the policy asks about goes through the preview, together
+    // with clearing the app shortcut it conflicts with.
+    fn stage_row(&mut self, k: usize, event: RowEvent, clear_app_shortcut: Option<u32>) {
+        let row = &self.model.rows[&k];
+        let (verb, value) = match event {
+            RowEvent::Overwrite => ("Overwrite", row.converted_keybinding.clone()),
+            _ => ("Clear", EMPTY_KEYBINDING.to_string()),
+        };
+        let title = format!("{verb} \"{}\"", row.label);
+        let mut transaction = Transaction::new();
+        let res = transaction
+            .stage(
+                self.backend.as_ref(),
+                &row.schema,
+                &row.gsettings_key,
+                &value,
+            )
+            .and_then(|()| match clear_app_shortcut {
+                Some(id) => transaction.stage(
+                    self.backend.as_ref(),
+                    SHELL_KEYBINDINGS_SCHEMA,
+                    &format!("switch-to-application-{id}"),
+                    EMPTY_KEYBINDING,
+                ),
+                None => Ok(()),
+            });
+        match res {
+            Ok(()) => self.preview_transaction(title, transaction),
+            Err(e) => {
+                if let Some(row) = self.model.rows.get_mut(&k) {
+                    row.set_result(Err(e));
+                }
+            }
+        }
+    }
+
+    fn accessibility_input(&mut self, ui: &mut Ui) {
+        if ui
+            .checkbox(&mut self.preferences.high_contrast, "High contrast")
+            .on_hover_text("Stronger borders, larger buttons and no status told by color alone")
+            .changed()
+        {
+            if let Err(e) = self.preferences.save() {
+                self.status_bar.report(AppError::Io(e));
+            }
+        }
+    }
+
+    fn confirm_policy_input(&mut self, ui: &mut Ui) {
+        ui.label("Preview these before writing them:");
+        let policy = &mut self.preferences.confirm;
+        let mut changed = false;
+        for (value, label) in [
+            (&mut policy.overwrite, "Overwriting a single row"),
+            (&mut policy.bulk_apply, "Presets, imports and batch actions"),
+            (&mut policy.clear, "Clearing bindings"),
+            (&mut policy.reset, "Resetting rows to their original value"),
+        ] {
+            changed |= ui.checkbox(value, label).changed();
+        }
+        if changed {
+            if let Err(e) = self.preferences.save() {
+                self.status_bar.report(AppError::Io(e));
+            }
+        }
+    }
+
+    fn record_write(&mut self, schema: &str, key: &str) {
+        // the value as read back, gsettings may normalize what was written
+        let res = self.backend.get(schema, key).and_then(|value| {
+            self.record_step(Operation::Set {
+                schema: schema.into(),
+                key: key.into(),
+                value: value.trim().into(),
+            });
+            self.conflicts.update(schema, key, &value);
+            self.model.update_conflict(schema, key, &value);
+            self.history.record(schema, key, &value)
+        });
+        if let Err(e) = res {
+            tracing::warn!(schema, key, "can't record write: {:#}", e);
+        }
+    }
+
+    // Started on the first frame outside safe mode, the reader threads need
+    // the context to wake the UI up.
+    fn poll_external_changes(&mut self, ctx: &egui::Context) {
+        if !self.watch_started && !self.safe_mode {
+            self.watch_started = true;
+            let ctx = ctx.clone();
+            match Watcher::start(&MANAGED_SCHEMAS, move || ctx.request_repaint()) {
+                Ok(watcher) => self.watcher = Some(watcher),
+                Err(e) => tracing::warn!("no live reload: {:#}", e),
+            }
+        }
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        let changes: Vec<Change> = watcher.changes.try_iter().collect();
+        for change in changes {
+            self.apply_external_change(change);
+        }
+    }
+
+    fn apply_external_change(&mut self, change: Change) {
+        tracing::debug!(change.schema, change.key, change.value, "changed");
+        self.conflicts
+            .update(&change.schema, &change.key, &change.value);
+        self.model
+            .update_conflict(&change.schema, &change.key, &change.value);
+        match (change.schema.as_str(), change.key.as_str()) {
+            (WM_PREFERENCES_SCHEMA, "num-workspaces") | (MUTTER_SCHEMA, "dynamic-workspaces") => {
+                self.read_workspace_settings()
+            }
+            (SHELL_KEYBINDINGS_SCHEMA, _) => self.read_app_keybindings(),
+            _ => {}
+        }
+        for row in self.model.rows.values_mut() {
+            if row.schema != change.schema || row.gsettings_key != change.key {
+                continue;
+            }
+            // an edit in progress is kept, it's now compared with the new value
+            if row.is_modified() {
+                row.gsettings_value = change.value.clone();
+            } else {
+                row.load(change.value.clone(), &self.keysyms);
+            }
+        }
+    }
+
+    // Run a write that doesn't go through a transaction on the worker and
+    // make what it changed of `keys` undoable, including partial writes of a
+    // failed one. `done` gets the result on the UI thread, reads queued
+    // after this see what was written.
+    fn undoable<T: Send + 'static>(
+        &mut self,
+        title: &str,
+        keys: &[(String, String)],
+        write: impl FnOnce(&dyn SettingsBackend) -> Result<T> + Send + 'static,
+        done: impl FnOnce(&mut Self, Result<T>) + Send + 'static,
+    ) {
+        let (title, keys) = (title.to_string(), keys.to_vec());
+        self.read_in_background(move |backend| {
+            let snapshot = Snapshot::take(backend, &keys);
+            let res = write(backend);
+            let changes = snapshot.changes(backend);
+            move |app: &mut Self| {
+                app.undo.push(&title, changes);
+                done(app, res);
+            }
+        });
+    }
+
+    // `done` of the writes with nothing to take over but a failure
+    fn report_write(&mut self, res: Result<()>) {
+        if let Err(e) = res {
+            self.status_bar.report(AppError::Settings(e));
+        }
+    }
+
+    fn undo_or_redo(&mut self, redo: bool) {
+        let res = if redo {
+            self.undo.redo(self.backend.as_ref())
+        } else {
+            self.undo.undo(self.backend.as_ref())
+        };
+        match res {
+            Ok(Some(step)) => {
+                let verb = if redo { "Redid" } else { "Undid" };
+                self.notice = Some(format!("{verb} {}", step.title));
+                self.record_writes(&step.transaction);
+            }
+            Ok(None) => return,
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+        self.reread_settings();
+        self.read_window_behavior();
+        self.read_key_repeat();
+    }
+
+    // not while typing, text fields have their own undo
+    fn handle_undo_keys(&mut self, ctx: &egui::Context) {
+        let capturing = self.model.rows.values().any(|r| r.capturing);
+        if ctx.wants_keyboard_input() || self.quick_assign.is_some() || capturing {
+            return;
+        }
+        let (undo, redo) = ctx.input_mut(|i| {
+            let redo = i.consume_key(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::Z,
+            );
+            (i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z), redo)
+        });
+        if undo || redo {
+            self.undo_or_redo(redo);
+        }
+    }
+
+    fn undo_buttons(&mut self, ui: &mut Ui) {
+        let mut clicked = None;
+        for (redo, button, shortcut, step) in [
+            (false, "⟲ Undo", "Ctrl+Z", self.undo.next_undo()),
+            (true, "⟳ Redo", "Ctrl+Shift+Z", self.undo.next_redo()),
+        ] {
+            let hover = step.map_or(String::new(), |s| format!("{} ({shortcut})", s.title));
+            if ui
+                .add_enabled(step.is_some(), egui::Button::new(button))
+                .on_hover_text(hover)
+                .clicked()
+            {
+                clicked = Some(redo);
+            }
+        }
+        if let Some(redo) = clicked {
+            self.undo_or_redo(redo);
+        }
+    }
+
+    fn record_step(&mut self, operation: Operation) {
+        if let Some(recording) = &mut self.recording {
+            recording.record(operation);
+        }
+    }
+
+    fn record_writes(&mut self, transaction: &Transaction) {
+        for change in &transaction.changes {
+            self.record_write(&change.schema, &change.key);
+        }
+    }
+
+    fn reread_settings(&mut self) {
+        self.get_gsettings_values_from_config();
+        self.read_app_keybindings();
+        self.read_workspace_settings();
+        self.read_isolation();
+        self.read_switcher_popup();
+        self.read_gestures();
+        self.read_wraparound();
+        if self.custom_shortcuts.is_some() {
+            if let Err(e) = self.read_custom_shortcuts() {
+                tracing::warn!("can't reread custom shortcuts: {:#}", e);
+            }
+        }
+    }
+
+    // Only needs the mouse, so a change that takes away familiar shortcuts
+    // can still be undone, or is undone by doing nothing.
+    fn revert_countdown(&mut self, ctx: &egui::Context) {
+        let Some((transaction, deadline)) = &self.unconfirmed_transaction else {
+            return;
+        };
+        let left = deadline.saturating_duration_since(Instant::now());
+
+        let mut keep = false;
+        let mut revert = left.is_zero();
+        egui::Window::new("Keep these changes?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} settings were changed. They are reverted in {} seconds.",
+                    transaction.changes.len(),
+                    left.as_secs() + 1
+                ));
+                ui.horizontal(|ui| {
+                    keep = ui.button("Keep changes").clicked();
+                    revert |= ui.button("Revert now").clicked();
+                });
+            });
+
+        if keep {
+            self.unconfirmed_transaction = None;
+        } else if revert {
+            let transaction = transaction.clone();
+            match transaction.revert(self.backend.as_ref()) {
+                Ok(()) => {
+                    self.notice = Some("Changes reverted".into());
+                    self.undo.push("Revert changes", transaction.inverse());
+                }
+                Err(e) => self.status_bar.report(AppError::Settings(e)),
+            }
+            self.record_writes(&transaction);
+            self.unconfirmed_transaction = None;
+            self.reread_settings();
+        } else {
+            // the countdown has to tick without input
+            ctx.request_repaint_after(left.min(std::time::Duration::from_secs(1)));
+        }
+    }
+
+    fn apply_style(&mut self, ctx: &egui::Context) {
+        let high_contrast = self.preferences.high_contrast;
+        if self.styled_high_contrast == Some(high_contrast) {
+            return;
+        }
+        if high_contrast {
+            ctx.set_style(high_contrast_style());
+        } else {
+            ctx.set_style(egui::Style::default());
+        }
+        self.styled_high_contrast = Some(high_contrast);
+    }
+
+    fn export_profile(&mut self, exporter: &dyn Exporter, path: &Path, minimal: bool) {
+        let mut profile = Profile::capture(self.backend.as_ref());
+        if minimal {
+            profile = profile.minimal(self.backend.as_ref());
+        }
+        let res = exporter.export(&profile).and_then(|text| {
+            std::fs::write(path, text).with_context(|| format!("can't write {}", path.display()))
+        });
+        match res {
+            Ok(()) => {
+                self.notice = Some(format!("{} written to {}", exporter.name(), path.display()))
+            }
+            Err(e) => self.status_bar.report(AppError::Io(e)),
+        }
+    }
+
+    fn export_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.export_dialog else {
+            return;
+        };
+
+        #[cfg(feature = "portal")]
+        if let Some(receiver) = &dialog.chooser {
+            match receiver.try_recv() {
+                Ok(res) => {
+                    match res {
+                        Ok(Some(path)) => dialog.path = path.display().to_string(),
+                        Ok(None) => {}
+                        Err(e) => self.status_bar.report(AppError::Io(e)),
+                    }
+                    dialog.chooser = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => dialog.chooser = None,
+            }
+        }
+
+        let mut open = true;
+        let mut save = false;
+        egui::Window::new("Export")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let previous = dialog.exporter;
+                egui::ComboBox::from_label("Format")
+                    .selected_text(EXPORTERS[dialog.exporter].name())
+                    .show_ui(ui, |ui| {
+                        for (i, exporter) in EXPORTERS.iter().enumerate() {
+                            ui.selectable_value(&mut dialog.exporter, i, exporter.name());
+                        }
+                    });
+                let old_default = export::default_path(EXPORTERS[previous]);
+                if dialog.exporter != previous && Path::new(&dialog.path) == old_default {
+                    dialog.path = export::default_path(EXPORTERS[dialog.exporter])
+                        .display()
+                        .to_string();
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Save to");
+                    ui.add(TextEdit::singleline(&mut dialog.path).desired_width(400.0));
+                    #[cfg(feature = "portal")]
+                    if ui
+                        .add_enabled(dialog.chooser.is_none(), egui::Button::new("Choose…"))
+                        .clicked()
+                    {
+                        let name = Path::new(&dialog.path)
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        let (sender, receiver) = std::sync::mpsc::channel();
+                        std::thread::spawn(move || {
+                            let _ = sender.send(portal::save_file("Export shortcuts", &name));
+                        });
+                        dialog.chooser = Some(receiver);
+                    }
+                });
+                ui.checkbox(
+                    &mut dialog.minimal,
+                    "Only keys that differ from GNOME defaults",
+                )
+                .on_hover_text(
+                    "Applying the export elsewhere leaves the other keys as they are there",
+                );
+                save = ui
+                    .add_enabled(!dialog.path.trim().is_empty(), egui::Button::new("Save"))
+                    .clicked();
+            });
+
+        if save {
+            let exporter = EXPORTERS[dialog.exporter];
+            let path = PathBuf::from(dialog.path.trim());
+            let minimal = dialog.minimal;
+            self.export_dialog = None;
+            self.export_profile(exporter, &path, minimal);
+        } else if !open {
+            self.export_dialog = None;
+        }
+    }
+
+    fn export_diagnostics(&mut self) {
+        let path = match diagnostics_path() {
+            Ok(path) => path,
+            Err(e) => return self.status_bar.report(AppError::Io(e)),
+        };
+        match diagnostics::export(&path, self.backend.as_ref(), &self.log_panel.to_text()) {
+            Ok(()) => self.notice = Some(format!("Diagnostics written to {}", path.display())),
+            Err(e) => self.status_bar.report(AppError::Io(e)),
+        }
+    }
+
+    #[cfg(feature = "update-check")]
+    fn start_update_check(&mut self) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(update::check());
+        });
+        self.update_check = Some(receiver);
+    }
+
+    #[cfg(feature = "update-check")]
+    fn poll_update_check(&mut self, ctx: &egui::Context) {
+        let Some(receiver) = &self.update_check else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(res) => {
+                match res {
+                    Ok(release) => self.available_update = release,
+                    // not worth bothering the user with, they'll just see no banner
+                    Err(e) => tracing::warn!("update check failed: {:#}", e),
+                }
+                self.update_check = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(500));
+            }
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => self.update_check = None,
+        }
+    }
+
+    #[cfg(feature = "update-check")]
+    fn update_banner(&mut self, ui: &mut Ui) {
+        let Some(release) = &self.available_update else {
+            return;
+        };
+        let mut dismiss = false;
+        ui.horizontal(|ui| {
+            ui.label(format!("Version {} is available.", release.tag));
+            ui.hyperlink_to("Download", &release.url);
+            dismiss = ui.small_button("Dismiss").clicked();
+        });
+        if dismiss {
+            self.available_update = None;
+        }
+    }
+
+    fn run_health_checks(&mut self) {
+        self.read_in_background(|backend| {
+            let issues: Vec<Check> = doctor::run(backend)
+                .into_iter()
+                .filter(|c| c.severity != Severity::Ok)
+                .collect();
+            move |app: &mut Self| app.health_issues = issues
+        });
+    }
+
+    fn health_banner(&mut self, ui: &mut Ui) {
+        if self.health_issues.is_empty() {
+            return;
+        }
+        let mut fix = None;
+        let mut dismiss = false;
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("⚠ {} issue(s) found at startup", self.health_issues.len()),
+                );
+                dismiss = ui.small_button("Dismiss").clicked();
+            });
+            for check in &self.health_issues {
+                ui.horizontal(|ui| {
+                    let (color, severity) = match check.severity {
+                        Severity::Error => (ui.visuals().error_fg_color, "Error"),
+                        _ => (ui.visuals().warn_fg_color, "Warning"),
+                    };
+                    let text = if self.preferences.high_contrast {
+                        format!("{severity}, {}: {}", check.name, check.detail)
+                    } else {
+                        format!("{}: {}", check.name, check.detail)
+                    };
+                    ui.colored_label(color, text);
+                    if let Some(f) = &check.fix {
+                        if ui.small_button(format!("Fix: {}", f.label())).clicked() {
+                            fix = Some(f.clone());
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(fix) = fix {
+            let (schema, key) = fix.target();
+            let keys = [(schema.to_string(), key.to_string())];
+            let applied = fix.clone();
+            self.undoable(
+                &fix.label(),
+                &keys,
+                move |backend| applied.apply(backend),
+                move |app, res| match res {
+                    Ok(()) => {
+                        let (schema, key) = fix.target();
+                        app.record_write(schema, key);
+                    }
+                    Err(e) => app.status_bar.report(AppError::Settings(e)),
+                },
+            );
+            self.read_workspace_settings();
+            self.read_app_keybindings();
+            self.get_gsettings_values_from_config();
+            self.run_health_checks();
+        }
+        if dismiss {
+            self.health_issues.clear();
+        }
+    }
+
+    fn start_practice(&mut self) {
+        let count = self.model.workspace_count.unwrap_or(MAX_WORKSPACES);
+        let targets = self
+            .model
+            .rows
+            .values()
+            .filter_map(|row| {
+                let n: usize = row
+                    .gsettings_key
+                    .strip_prefix("switch-to-workspace-")?
+                    .parse()
+                    .ok()?;
+                let a = Accelerator::from_gsettings(&row.gsettings_value).ok()??;
+                (n <= count).then(|| (n, a.format()))
+            })
+            .collect();
+        self.practice = Practice::new(targets);
+        if self.practice.is_none() {
+            self.notice = Some("Bind some switch-to-workspace shortcuts to practice".into());
+        }
+    }
+
+    fn practice_window(&mut self, ctx: &egui::Context) {
+        if let Some(practice) = &mut self.practice {
+            if !practice.show(ctx) {
+                self.practice = None;
+            }
+        }
+    }
+
+    fn run_lint(&mut self) {
+        self.lint_findings = Some(lint::run(&Profile::capture(self.backend.as_ref())));
+    }
+
+    fn lint_window(&mut self, ctx: &egui::Context) {
+        let Some(findings) = &self.lint_findings else {
+            return;
+        };
+        let mut open = true;
+        let mut fix = None;
+        egui::Window::new("Lint")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if findings.is_empty() {
+                    ui.label("No problems found");
+                }
+                egui::Grid::new("lint_findings")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for check in findings {
+                            ui.label(&check.name);
+                            ui.label(&check.detail);
+                            if let Some(f) = &check.fix {
+                                if ui.small_button(f.label()).clicked() {
+                                    fix = Some(f.clone());
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(fix) = fix {
+            let (schema, key) = fix.target();
+            let keys = [(schema.to_string(), key.to_string())];
+            let applied = fix.clone();
+            self.undoable(
+                &fix.label(),
+                &keys,
+                move |backend| applied.apply(backend),
+                move |app, res| match res {
+                    Ok(()) => {
+                        let (schema, key) = fix.target();
+                        app.record_write(schema, key);
+                    }
+                    Err(e) => app.status_bar.report(AppError::Settings(e)),
+                },
+            );
+            self.reread_settings();
+            self.run_lint();
+        } else if !open {
+            self.lint_findings = None;
+        }
+    }
+
+    // reads keep working, every write fails with the reason
+    fn guard_writes(&mut self, reason: String) {
+        tracing::warn!("writes are turned off: {reason}");
+        self.write_guard = Some(reason);
+        self.rebuild_backend();
+    }
+
+    // GSettings behind the write guard and preview mode, whichever are on
+    fn rebuild_backend(&mut self) {
+        let mut backend: Box<dyn SettingsBackend> = Box::new(GSettings);
+        if let Some(reason) = &self.write_guard {
+            backend = Box::new(ReadOnly {
+                inner: backend,
+                reason: reason.clone(),
+            });
+        }
+        if let Some(writes) = &self.preview_writes {
+            backend = Box::new(Recording {
+                inner: backend,
+                writes: writes.clone(),
+            });
+        }
+        self.backend = Arc::from(backend);
+    }
+
+    fn set_preview_mode(&mut self, on: bool) {
+        self.preview_writes = on.then(PendingWrites::default);
+        self.rebuild_backend();
+        // the rows show the kept back values
+        self.reread_settings();
+    }
+
+    // the kept back writes as one transaction, so they get the usual
+    // rollback and undo
+    fn commit_preview(&mut self) {
+        let Some(writes) = self.preview_writes.take() else {
+            return;
+        };
+        self.rebuild_backend();
+        let latest = match writes.lock() {
+            Ok(writes) => backend::latest_writes(&writes),
+            Err(_) => {
+                self.status_bar.report(AppError::Internal(
+                    "The kept back writes were lost, a write panicked".into(),
+                ));
+                self.set_preview_mode(true);
+                return;
+            }
+        };
+        let mut transaction = Transaction::new();
+        let res = latest.iter().try_for_each(|w| {
+            transaction.stage(self.backend.as_ref(), &w.schema, &w.key, &w.value)
+        });
+        match res {
+            Ok(()) => self.apply_transaction("Preview mode changes", transaction),
+            Err(e) => self.status_bar.report(AppError::Settings(e)),
+        }
+        self.set_preview_mode(true);
+    }
+
+    fn preview_panel(&mut self, ui: &mut Ui) {
+        let Some(writes) = &self.preview_writes else {
+            return;
+        };
+        let commands: Vec<String> = match writes.lock() {
+            Ok(writes) => writes
+                .iter()
+                .map(|w| export::gsettings_command(&w.schema, &w.key, &w.value))
+                .collect(),
+            // reported by commit, the panel stays to discard them
+            Err(_) => vec![],
+        };
+        let (mut commit, mut discard) = (false, false);
+        ui.horizontal(|ui| {
+            ui.strong("Preview mode");
+            ui.weak("nothing is written until you commit");
+            commit = ui
+                .add_enabled(!commands.is_empty(), egui::Button::new("Commit"))
+                .clicked();
+            discard = ui
+                .add_enabled(!commands.is_empty(), egui::Button::new("Discard"))
+                .clicked();
+            if ui.button("Copy as script").clicked() {
+                ui.output_mut(|o| o.copied_text = commands.join("\n"));
+            }
+        });
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if commands.is_empty() {
+                ui.weak("No writes yet.");
+            }
+            for command in &commands {
+                ui.monospace(command);
+            }
+        });
+        if commit {
+            self.commit_preview();
+        } else if discard {
+            self.set_preview_mode(true);
+        }
+    }
+
+    fn write_guard_banner(&mut self, ui: &mut Ui) {
+        let Some(reason) = &self.write_guard else {
+            return;
+        };
+        let mut allow = false;
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("⚠ Preview only, {reason}. Changes might not reach this GNOME session."),
+            );
+            allow = ui.small_button("Allow writes anyway").clicked();
+        });
+        if allow {
+            tracing::info!("writes allowed despite the session check");
+            self.write_guard = None;
+            self.rebuild_backend();
+        }
+    }
+
+    fn safe_mode_banner(&mut self, ui: &mut Ui) {
+        if !self.safe_mode {
+            return;
+        }
+        let mut load = false;
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "Safe mode: no settings were read, the values shown are placeholders.",
+            );
+            load = ui.small_button("Load settings").clicked();
+        });
+        if load {
+            self.load_settings();
+        }
+    }
+
+    fn notice_banner(&mut self, ui: &mut Ui) {
+        let Some(notice) = &self.notice else {
+            return;
+        };
+        let mut dismiss = false;
+        ui.horizontal(|ui| {
+            ui.label(notice);
+            dismiss = ui.small_button("Dismiss").clicked();
+        });
+        if dismiss {
+            self.notice = None;
+        }
+    }
+
+    fn about_dialog(&mut self, ctx: &egui::Context) {
+        let Some(env) = &self.about else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("About")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.heading("Gnome Workspace Shortcuts Menu");
+                egui::Grid::new("about").show(ui, |ui| {
+                    ui.label("Version");
+                    ui.label(env.app_version);
+                    ui.end_row();
+                    ui.label("GNOME Shell");
+                    ui.label(env.gnome_shell_version.as_deref().unwrap_or("not found"));
+                    ui.end_row();
+                    ui.label("Session type");
+                    ui.label(&env.session_type);
+                    ui.end_row();
+                    ui.label("Desktop");
+                    ui.label(&env.desktop);
+                    ui.end_row();
+                    ui.label("Backend");
+                    ui.label(env.backend);
+                    ui.end_row();
+                    for (schema, installed) in &env.schemas {
+                        ui.label(*schema);
+                        if *installed {
+                            ui.label("installed");
+                        } else {
+                            ui.colored_label(ui.visuals().error_fg_color, "missing");
+                        }
+                        ui.end_row();
+                    }
+                });
+                if ui.button("Copy to clipboard").clicked() {
+                    ui.output_mut(|o| o.copied_text = env.to_string());
+                }
+                #[cfg(feature = "update-check")]
+                if ui
+                    .checkbox(
+                        &mut self.preferences.check_for_updates,
+                        "Check GitHub for new releases at startup",
+                    )
+                    .changed()
+                {
+                    if let Err(e) = self.preferences.save() {
+                        self.status_bar.report(AppError::Io(e));
+                    }
+                }
+            });
+        if !open {
+            self.about = None;
+        }
+    }
+
+    fn workspace_count_input(&mut self, ui: &mut Ui) {
+        let parsed = parse_number_of_workspaces(&self.num_of_workspaces);
+
+        ui.horizontal(|ui| {
+            ui.label("Number of Workspaces");
+            let te = TextEdit::singleline(&mut self.num_of_workspaces);
+            if ui.add_sized(Vec2::new(40.0, 20.0), te).changed() {
+                self.count_edited = true;
+            }
+            if ui
+                .add_enabled(parsed.is_ok(), egui::Button::new("Overwrite"))
+                .clicked()
+            {
+                if let Ok(num) = parsed {
+                    self.apply_workspace_settings(num);
+                }
+            }
+            if ui
+                .add_enabled(parsed.is_ok(), egui::Button::new("Apply all…"))
+                .on_hover_text("Write the count and every edited shortcut together, with a preview")
+                .clicked()
+            {
+                if let Ok(num) = parsed {
+                    self.stage_all(num);
+                }
+            }
+            if let Err(e) = &parsed {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        });
+        ui.horizontal(|ui| {
+            for count in COUNT_PRESETS {
+                let selected = parsed == Ok(count);
+                if ui.selectable_label(selected, count.to_string()).clicked() {
+                    self.num_of_workspaces = count.to_string();
+                    self.count_edited = true;
+                }
+            }
+            if let Ok(count) = parsed {
+                workspace_preview(ui, self.workspace_layout, count);
+            }
+        });
+
+        let mut dynamic = self.model.dynamic_workspaces;
+        if ui
+            .checkbox(&mut dynamic, "Dynamic workspaces")
+            .on_hover_text("GNOME adds and removes workspaces as they're used")
+            .changed()
+        {
+            self.set_dynamic_workspaces(dynamic);
+        }
+        if self.model.dynamic_workspaces {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "Dynamic workspaces are enabled, the number of workspaces is ignored.",
+                );
+                ui.checkbox(
+                    &mut self.disable_dynamic_workspaces,
+                    "Disable dynamic workspaces on overwrite",
+                );
+            });
+            let offer = parsed.as_ref().ok().filter(|_| self.count_edited).copied();
+            if let Some(num) = offer {
+                if ui
+                    .button(format!("Switch to {num} fixed workspaces"))
+                    .clicked()
+                {
+                    self.disable_dynamic_workspaces = true;
+                    self.apply_workspace_settings(num);
+                }
+            }
+        }
+    }
+
+    fn set_dynamic_workspaces(&mut self, enabled: bool) {
+        let keys = [(MUTTER_SCHEMA.to_string(), "dynamic-workspaces".to_string())];
+        let verb = if enabled { "Enable" } else { "Disable" };
+        let value = enabled.to_string();
+        self.undoable(
+            &format!("{verb} dynamic workspaces"),
+            &keys,
+            move |backend| {
+                backend.set_confirmed(MUTTER_SCHEMA, "dynamic-workspaces", &value)?;
+                Ok(value)
+            },
+            |app, res| match res {
+                Ok(value) => app.record_step(Operation::Set {
+                    schema: MUTTER_SCHEMA.into(),
+                    key: "dynamic-workspaces".into(),
+                    value,
+                }),
+                Err(e) => app.status_bar.report(AppError::Settings(e)),
+            },
+        );
+        self.read_workspace_settings();
+    }
+
+    // read on the worker, the rows spin until poll_worker has the values
+    fn get_gsettings_values_from_config(&mut self) {
+        let rows: Vec<(usize, String, String)> = self
+            .model
+            .rows
+            .iter_mut()
+            .map(|(k, row)| {
+                row.pending = true;
+                (*k, row.schema.clone(), row.gsettings_key.clone())
+            })
+            .collect();
+        let backend = self.backend.clone();
+        self.worker.run(move || {
+            Done::Rows(
+                rows.into_iter()
+                    .map(|(k, schema, key)| {
+                        let value = backend.get(&schema, &key);
+                        (k, schema, key, value)
+                    })
+                    .collect(),
+            )
+        });
+    }
+
+    fn poll_worker(&mut self) {
+        let finished = self.worker.finished();
+        if !finished.is_empty() {
+            // reads and writes land in the rows
+            self.model.invalidate();
+        }
+        for done in finished {
+            match done {
+                Done::Rows(values) => self.model.take_reads(values, &self.keysyms),
+                Done::Applied {
+                    title,
+                    transaction,
+                    res,
+                } => self.finish_apply(&title, transaction, res),
+                Done::RowWritten {
+                    k,
+                    title,
+                    changes,
+                    res,
+                    cleared_app_shortcut,
+                } => self.finish_row_write(k, &title, changes, res, cleared_app_shortcut),
+                Done::Read(take_over) => take_over(self),
+            }
+        }
+    }
+
+    // Snapshot, write and read back on the worker, so a row write waits for
+    // the reads and applies queued before it instead of racing them.
+    fn write_row(&mut self, k: usize, title: String, write: RowWrite, clear_app: Option<u32>) {
+        let mut keys = vec![(write.schema.clone(), write.key.clone())];
+        if let Some(id) = clear_app {
+            keys.push((
+                SHELL_KEYBINDINGS_SCHEMA.to_string(),
+                format!("switch-to-application-{id}"),
+            ));
+        }
+        let backend = self.backend.clone();
+        self.worker.run(move || {
+            let backend = backend.as_ref();
+            let snapshot = Snapshot::take(backend, &keys);
+            let res = write.run(backend);
+            let cleared_app_shortcut = clear_app
+                .filter(|_| res.is_ok())
+                .map(|id| (id, clear_app_shortcut(backend, id)));
+            Done::RowWritten {
+                k,
+                title,
+                changes: snapshot.changes(backend),
+                res,
+                cleared_app_shortcut,
+            }
+        });
+    }
+
+    fn finish_row_write(
+        &mut self,
+        k: usize,
+        title: &str,
+        changes: Transaction,
+        res: Result<String>,
+        cleared_app_shortcut: Option<(u32, Result<()>)>,
+    ) {
+        self.undo.push(title, changes);
+        let Some(row) = self.model.rows.get_mut(&k) else {
+            return;
+        };
+        row.pending = false;
+        row.finish_write(res, &self.keysyms);
+        if matches!(row.status, RowStatus::Applied) {
+            let (schema, key) = (row.schema.clone(), row.gsettings_key.clone());
+            self.record_write(&schema, &key);
+        }
+        match cleared_app_shortcut {
+            Some((id, Ok(()))) => {
+                self.record_step(Operation::Unbind {
+                    schema: SHELL_KEYBINDINGS_SCHEMA.into(),
+                    key: format!("switch-to-application-{id}"),
+                });
+                self.read_app_keybindings();
+            }
+            Some((_, res)) => {
+                if let Some(row) = self.model.rows.get_mut(&k) {
+                    row.set_result(res);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn help_panel(&mut self, ui: &mut Ui) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if let Some(row) = self.help_row.and_then(|k| self.model.rows.get(&k)) {
+                ui.heading(&row.label);
+                ui.monospace(row.gsettings_value.trim());
+                for line in help::explain(&row.gsettings_value, &self.keysyms) {
+                    ui.label(line);
+                }
+                if row.is_modified() && !row.is_incomplete() {
+                    ui.label("Edited, not written yet:");
+                    ui.monospace(&row.converted_keybinding);
+                    for line in help::explain(&row.converted_keybinding, &self.keysyms) {
+                        ui.label(line);
+                    }
+                }
+                ui.separator();
+            } else {
+                ui.weak("Click a row to have its value explained here.");
+            }
+            for block in help::syntax() {
+                match block {
+                    Block::Heading(heading) => {
+                        ui.add_space(4.0);
+                        ui.strong(heading);
+                    }
+                    Block::Text(text) => {
+                        ui.label(text);
+                    }
+                    Block::Example(example) => {
+                        ui.horizontal(|ui| {
+                            ui.monospace(&example);
+                            if ui.small_button("Copy").clicked() {
+                                ui.output_mut(|o| o.copied_text = example.clone());
+                            }
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    fn workspace_keybinding_input(&mut self, ui: &mut Ui, k: usize) {
+        self.focus_row(ui, k);
+        let Some(selection) = self.model.rows.get_mut(&k) else {
+            return;
+        };
+        let last = self
+            .history
+            .last(&selection.schema, &selection.gsettings_key);
+        selection.badge = Some(sync_badge(last, &selection.gsettings_value));
+        selection.selected = self.selection.contains(k);
+        let event = selection.show(ui, k, &self.keysyms);
+        if event.is_some() {
+            self.help_row = Some(k);
+        }
+        if event == Some(RowEvent::Clicked) {
+            let keys: Vec<usize> = self.model.rows.keys().copied().collect();
+            let modifiers = ui.input(|i| i.modifiers);
+            self.selection.click(k, modifiers, &keys);
+            return;
+        }
+        #[cfg(any(feature = "portal", feature = "x11"))]
+        {
+            if event.is_some() {
+                self.availability_hints.reset(k);
+            }
+            self.availability_hints
+                .show(ui, k, &selection.converted_keybinding, &self.keysyms);
+        }
+
+        let conflict = self.model.conflicting_app_shortcut(k);
+        if let Some(id) = conflict {
+            let clear = self.clear_app_shortcut_conflicts.entry(k).or_default();
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("<Super>{id} is taken by switch-to-application-{id}."),
+                );
+                ui.checkbox(clear, "Clear it on overwrite (the old value is backed up)");
+            });
+        }
+
+        let mut event = event;
+        if let Some((workspace, count)) = self.model.missing_workspace(k) {
+            let mut raise = false;
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("⚠ Workspace {workspace} doesn't exist, there are only {count}."),
+                );
+                raise = ui.small_button(format!("Raise to {workspace}")).clicked();
+                if ui.small_button("Clear binding").clicked() {
+                    event = Some(RowEvent::Unbind);
+                }
+            });
+            if raise {
+                self.apply_workspace_settings(workspace);
+            }
+        }
+
+        let Some(event) = event else {
+            return;
+        };
+        self.model.invalidate();
+        let clear_conflict = self
+            .clear_app_shortcut_conflicts
+            .get(&k)
+            .copied()
+            .unwrap_or_default();
+        let kind = match event {
+            RowEvent::Overwrite => Some(Confirm::Overwrite),
+            RowEvent::Unbind => Some(Confirm::Clear),
+            RowEvent::Changed | RowEvent::Clicked => None,
+        };
+        if kind.is_some_and(|kind| self.preferences.confirm.requires(&[kind])) {
+            let clear_app_shortcut =
+                conflict.filter(|_| clear_conflict && kind == Some(Confirm::Overwrite));
+            self.stage_row(k, event, clear_app_shortcut);
+            return;
+        }
+        let Some(selection) = self.model.rows.get_mut(&k) else {
+            return;
+        };
+        let Some(write) = selection.write_for(event) else {
+            return;
+        };
+        selection.pending = true;
+        let title = format!("{event:?} \"{}\"", selection.label);
+        let clear_app = conflict.filter(|_| clear_conflict && event == RowEvent::Overwrite);
+        self.write_row(k, title, write, clear_app);
+    }
+}
+
+impl eframe::App for MyApp {
+    // egui only calls this on input or when a repaint is requested, so the
+    // app stays idle between events. The settings worker and the watcher get
+    // a notifier that calls `ctx.request_repaint` from their threads when a
+    // result or a change arrives; the availability probes and timers that
+    // are still pending ask for `ctx.request_repaint_after` instead.
+    // Nothing asks for continuous repaints.
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        #[cfg(any(feature = "portal", feature = "x11"))]
+        self.availability_hints.poll(ctx);
+        #[cfg(feature = "update-check")]
+        self.poll_update_check(ctx);
+        self.apply_style(ctx);
+        self.handle_undo_keys(ctx);
+        self.handle_quick_assign(ctx);
+        self.poll_external_changes(ctx);
+        self.poll_worker();
+        self.model.annotate();
+
+        if !self.status_bar.is_empty() {
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| self.status_bar.show(ui));
+        }
+
+        if self.show_log_panel {
+            egui::TopBottomPanel::bottom("log_panel")
+                .resizable(true)
+                .show(ctx, |ui| self.log_panel.show(ui));
+        }
+
+        if self.preview_writes.is_some() {
+            egui::TopBottomPanel::bottom("preview_panel")
+                .resizable(true)
+                .show(ctx, |ui| self.preview_panel(ui));
+        }
+
+        if self.show_help_panel {
+            egui::SidePanel::right("help_panel")
+                .resizable(true)
+                .show(ctx, |ui| self.help_panel(ui));
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.safe_mode_banner(ui);
+            self.write_guard_banner(ui);
+            self.health_banner(ui);
+            self.notice_banner(ui);
+            #[cfg(feature = "update-check")]
+            self.update_banner(ui);
+            self.workspace_count_input(ui);
+
+            ui.horizontal(|ui| {
+                self.undo_buttons(ui);
+                if ui
+                    .button("Disable switch-to-application shortcuts")
+                    .clicked()
+                {
+                    let keys = Operation::ClearAppShortcuts.targets();
+                    self.undoable(
+                        "Disable switch-to-application shortcuts",
+                        &keys.clone(),
+                        move |backend| {
+                            for (schema, key) in &keys {
+                                backend.unbind(schema, key)?;
+                            }
+                            Ok(())
+                        },
+                        |app, res| match res {
+                            Ok(()) => app.record_step(Operation::ClearAppShortcuts),
+                            Err(e) => app.status_bar.report(AppError::Settings(e)),
+                        },
+                    );
+                    self.read_app_keybindings();
+                }
+                if ui
+                    .button("Restore my backup…")
+                    .on_hover_text("Put back the values from before this app first changed them")
+                    .clicked()
+                {
+                    let transaction = backup::stage_restore(self.backend.as_ref());
+                    self.stage_reset("Restore my backup", transaction);
+                }
+                if ui.button("Restore GNOME defaults…").clicked() {
+                    let transaction =
+                        backup::stage_defaults(self.backend.as_ref(), &restorable_keys());
+                    self.stage_reset("Restore GNOME defaults", transaction);
+                }
+                if ui.button("Import…").clicked() {
+                    self.import_path = Some("".into());
+                }
+                if ui
+                    .button("Migrate…")
+                    .on_hover_text("Import a scheme exported on another GNOME version")
+                    .clicked()
+                {
+                    self.migration = Some(MigrationDialog::default());
+                }
+                if ui.button("Share profile").clicked() {
+                    match share::encode(&Profile::capture(self.backend.as_ref())) {
+                        Ok(code) => self.share_code = Some(code),
+                        Err(e) => self.status_bar.report(AppError::Internal(format!("{e:#}"))),
+                    }
+                }
+                if ui.button("Import share code…").clicked() {
+                    self.share_import = Some("".into());
+                }
+                if ui.button("Compare profiles…").clicked() {
+                    self.profile_compare = Some(ProfileCompare::default());
+                }
+                if ui.button("Export…").clicked() {
+                    self.export_dialog = Some(ExportDialog::new());
+                }
+                if ui.button("Lint my scheme").clicked() {
+                    self.run_lint();
+                }
+                if ui
+                    .button("Practice")
+                    .on_hover_text("Drill the workspace switch shortcuts")
+                    .clicked()
+                {
+                    self.start_practice();
+                }
+                ui.toggle_value(&mut self.show_log_panel, "Log");
+                ui.toggle_value(&mut self.show_help_panel, "Syntax help");
+                let mut preview = self.preview_writes.is_some();
+                if ui
+                    .toggle_value(&mut preview, "Preview mode")
+                    .on_hover_text(
+                        "Collect the gsettings writes for review instead of writing them",
+                    )
+                    .changed()
+                {
+                    self.set_preview_mode(preview);
+                }
+                if ui
+                    .button("Export diagnostics")
+                    .on_hover_text(
+                        "Zip the log, current values and a doctor report for bug reports",
+                    )
+                    .clicked()
+                {
+                    self.export_diagnostics();
+                }
+                if ui.button("About").clicked() {
+                    self.about = Some(Environment::detect(self.backend.as_ref()));
+                }
+                if ui
+                    .button("Open config folder")
+                    .on_hover_text("Profiles, macros and preferences")
+                    .clicked()
+                {
+                    if let Err(e) = open_config_folder() {
+                        self.status_bar.report(AppError::Io(e));
+                    }
+                }
+                if ui.link("Open GNOME Settings → Keyboard").clicked() {
+                    if let Err(e) = open_gnome_keyboard_settings() {
+                        self.status_bar.report(AppError::Io(e));
+                    }
+                }
+            });
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    for (id, title) in SECTIONS {
+                        self.section(ui, id, title);
+                    }
+
+                    ui.heading("Shortcuts");
+                    self.conflict_strip(ui);
+                    self.quick_assign_bar(ui);
+                    self.selection_toolbar(ui);
+                    let map = &self.model.rows;
+                    let group_of = |k: &usize| {
+                        ROW_GROUPS.iter().position(|(_, _, schema, rows)| {
+                            *schema == map[k].schema
+                                && rows.iter().any(|(key, _)| *key == map[k].gsettings_key)
+                        })
+                    };
+                    let mut groups: Vec<Vec<usize>> = vec![vec![]; ROW_GROUPS.len() + 1];
+                    for k in map.keys() {
+                        // the workspace rows come first
+                        groups[group_of(k).map_or(0, |g| g + 1)].push(*k);
+                    }
+                    for (i, keys) in groups.into_iter().enumerate() {
+                        if let Some((heading, hint, _, _)) = i.checked_sub(1).map(|g| ROW_GROUPS[g])
+                        {
+                            ui.heading(heading);
+                            ui.weak(hint);
+                            if heading == "Navigation" {
+                                self.wraparound_input(ui);
+                            }
+                        }
+                        for k in keys {
+                            self.workspace_keybinding_input(ui, k);
+                            self.restore_default_button(ui, k);
+                        }
+                    }
+                });
+        });
+
+        self.transaction_preview(ctx);
+        self.revert_countdown(ctx);
+        self.about_dialog(ctx);
+        self.import_dialog(ctx);
+        self.migration_dialog(ctx);
+        self.export_dialog(ctx);
+        self.share_window(ctx);
+        self.share_import_dialog(ctx);
+        self.profile_compare_window(ctx);
+        self.lint_window(ctx);
+        self.drift_window(ctx);
+        self.practice_window(ctx);
+
+        // edited this frame, the rows drawn already show the old notes
+        if self.model.is_stale() {
+            ctx.request_repaint();
+        }
+    }
+}
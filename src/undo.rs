@@ -0,0 +1,118 @@
+//! Undo and redo of the writes made in a session. A step is the
+//! transaction that made it, undoing it applies the inverse.
+
+use anyhow::Result;
+
+use crate::{
+    backend::SettingsBackend,
+    gsettings::same_value,
+    transaction::{Change, Transaction},
+};
+
+// older steps are dropped, nobody undoes a hundred steps back
+const LIMIT: usize = 100;
+
+/// The values of a few keys before a write that doesn't go through a
+/// [`Transaction`], to make it undoable afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    values: Vec<(String, String, String)>,
+}
+
+impl Snapshot {
+    /// Keys that can't be read are left out.
+    pub fn take(backend: &dyn SettingsBackend, keys: &[(String, String)]) -> Self {
+        let values = keys
+            .iter()
+            .filter_map(|(schema, key)| {
+                let value = backend.get(schema, key).ok()?;
+                Some((schema.clone(), key.clone(), value.trim().to_string()))
+            })
+            .collect();
+        Self { values }
+    }
+
+    /// The keys that changed since, from their old to their new value.
+    pub fn changes(&self, backend: &dyn SettingsBackend) -> Transaction {
+        let mut transaction = Transaction::new();
+        for (schema, key, old) in &self.values {
+            let Ok(new) = backend.get(schema, key) else {
+                continue;
+            };
+            if !same_value(old, &new) {
+                transaction.changes.push(Change {
+                    schema: schema.clone(),
+                    key: key.clone(),
+                    old_value: old.clone(),
+                    new_value: new.trim().into(),
+                });
+            }
+        }
+        transaction
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub title: String,
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UndoStack {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+impl UndoStack {
+    /// Record a write that was made. Empty transactions are ignored, any
+    /// other write makes the undone steps unavailable for redo.
+    pub fn push(&mut self, title: &str, transaction: Transaction) {
+        if transaction.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push(Step {
+            title: title.into(),
+            transaction,
+        });
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn next_undo(&self) -> Option<&Step> {
+        self.undo.last()
+    }
+
+    pub fn next_redo(&self) -> Option<&Step> {
+        self.redo.last()
+    }
+
+    /// Restore the old values of the last step. A step that fails to undo
+    /// stays where it was, its partial writes rolled back.
+    pub fn undo(&mut self, backend: &dyn SettingsBackend) -> Result<Option<Step>> {
+        let Some(step) = self.undo.pop() else {
+            return Ok(None);
+        };
+        if let Err(e) = step.transaction.inverse().apply(backend) {
+            self.undo.push(step);
+            return Err(e);
+        }
+        self.redo.push(step.clone());
+        Ok(Some(step))
+    }
+
+    /// Write the last undone step again.
+    pub fn redo(&mut self, backend: &dyn SettingsBackend) -> Result<Option<Step>> {
+        let Some(step) = self.redo.pop() else {
+            return Ok(None);
+        };
+        if let Err(e) = step.transaction.apply(backend) {
+            self.redo.push(step);
+            return Err(e);
+        }
+        self.undo.push(step.clone());
+        Ok(Some(step))
+    }
+}
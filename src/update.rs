@@ -0,0 +1,48 @@
+//! Look up the latest GitHub release, for users of prebuilt binaries who
+//! don't get updates through a package manager.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/mucks/gnome-workspace-shortcuts-menu-rs/releases/latest";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Release {
+    #[serde(rename = "tag_name")]
+    pub tag: String,
+    #[serde(rename = "html_url")]
+    pub url: String,
+}
+
+pub fn latest_release() -> Result<Release> {
+    let release = ureq::get(LATEST_RELEASE_URL)
+        .set(
+            "User-Agent",
+            concat!("gnome-workspace-shortcuts-menu/", env!("CARGO_PKG_VERSION")),
+        )
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .context("can't reach the GitHub releases API")?
+        .into_json()?;
+    Ok(release)
+}
+
+// "v1.2.3" -> [1, 2, 3], anything after a non-numeric part is ignored
+fn version_numbers(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+pub fn is_newer(tag: &str, current: &str) -> bool {
+    version_numbers(tag) > version_numbers(current)
+}
+
+/// The latest release if it is newer than this build.
+pub fn check() -> Result<Option<Release>> {
+    let release = latest_release()?;
+    Ok(is_newer(&release.tag, env!("CARGO_PKG_VERSION")).then_some(release))
+}
@@ -0,0 +1,123 @@
+//! Notifications about keys changed by something else, like GNOME Settings,
+//! so open windows don't show stale values. Through `gsettings monitor`, or
+//! GSettings' changed signal with native settings.
+
+use std::sync::mpsc::{self, Receiver};
+#[cfg(not(feature = "native-settings"))]
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    thread,
+};
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub schema: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// `key: value` as printed by `gsettings monitor`.
+pub fn parse_monitor_line(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(": ")?;
+    // gsettings key names, anything else is a warning printed in between
+    let is_key_char = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-';
+    if key.is_empty() || !key.chars().all(is_key_char) {
+        return None;
+    }
+    Some((key.into(), value.trim().into()))
+}
+
+/// One `gsettings monitor` per schema, or with native settings one GLib
+/// subscription, stopped when this is dropped.
+pub struct Watcher {
+    #[cfg(not(feature = "native-settings"))]
+    children: Vec<Child>,
+    #[cfg(feature = "native-settings")]
+    subscriptions: Vec<crate::settings::Subscription>,
+    pub changes: Receiver<Change>,
+}
+
+impl Watcher {
+    /// `notify` is called from the reader threads after every change, to
+    /// wake up whoever drains `changes`.
+    #[cfg(feature = "native-settings")]
+    pub fn start(schemas: &[&str], notify: impl Fn() + Clone + Send + 'static) -> Result<Self> {
+        let (sender, changes) = mpsc::channel();
+        let mut watcher = Self {
+            subscriptions: vec![],
+            changes,
+        };
+        for schema in schemas {
+            let (schema_name, sender, notify) =
+                (schema.to_string(), sender.clone(), notify.clone());
+            let subscription = crate::settings::watch(schema, move |key, value| {
+                let change = Change {
+                    schema: schema_name.clone(),
+                    key,
+                    value,
+                };
+                if sender.send(change).is_ok() {
+                    notify();
+                }
+            })
+            .with_context(|| format!("can't watch {schema}"))?;
+            watcher.subscriptions.push(subscription);
+        }
+        Ok(watcher)
+    }
+
+    /// `notify` is called from the reader threads after every change, to
+    /// wake up whoever drains `changes`.
+    #[cfg(not(feature = "native-settings"))]
+    pub fn start(schemas: &[&str], notify: impl Fn() + Clone + Send + 'static) -> Result<Self> {
+        let (sender, changes) = mpsc::channel();
+        // already started monitors are stopped by drop when one fails
+        let mut watcher = Self {
+            children: vec![],
+            changes,
+        };
+        for schema in schemas {
+            let child = Command::new("gsettings")
+                .arg("monitor")
+                .arg(schema)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .with_context(|| format!("can't watch {schema}"))?;
+            watcher.children.push(child);
+            let stdout = watcher.children.last_mut().and_then(|c| c.stdout.take());
+            let stdout = stdout.context("gsettings monitor has no stdout")?;
+            let (schema, sender, notify) = (schema.to_string(), sender.clone(), notify.clone());
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    let Some((key, value)) = parse_monitor_line(&line) else {
+                        continue;
+                    };
+                    let change = Change {
+                        schema: schema.clone(),
+                        key,
+                        value,
+                    };
+                    if sender.send(change).is_err() {
+                        break;
+                    }
+                    notify();
+                }
+            });
+        }
+        Ok(watcher)
+    }
+}
+
+#[cfg(not(feature = "native-settings"))]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        for child in &mut self.children {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! egui widgets for editing GNOME keybindings. They only depend on egui, the
+//! settings are read and written through a [`SettingsBackend`].
+
+pub mod app_picker;
+
+use std::hash::Hash;
+
+use egui::{TextEdit, Ui, Vec2};
+
+pub use crate::model::{KeybindingRow, RowEvent, RowStatus, RowWrite};
+use crate::{
+    accelerator::Modifiers, backend::SettingsBackend, keysym::Keysyms, model::LOCKED_NOTE,
+};
+
+/// A checkbox per modifier, any combination can be held. Returns whether
+/// one was toggled.
+pub fn modifier_checkboxes(ui: &mut Ui, modifiers: &mut Modifiers) -> bool {
+    let mut changed = false;
+    for (held, name) in [
+        (&mut modifiers.super_key, "Super"),
+        (&mut modifiers.ctrl, "Ctrl"),
+        (&mut modifiers.alt, "Alt"),
+        (&mut modifiers.shift, "Shift"),
+    ] {
+        changed |= ui.checkbox(held, name).changed();
+    }
+    changed
+}
+
+/// What to put in a row's key field for a pressed key: the character for
+/// printable keys, the keysym name for the others.
+pub fn key_name(key: egui::Key) -> Option<String> {
+    use egui::Key::*;
+    let name = match key {
+        ArrowDown => "Down",
+        ArrowLeft => "Left",
+        ArrowRight => "Right",
+        ArrowUp => "Up",
+        Tab => "Tab",
+        Backspace => "BackSpace",
+        Enter => "Return",
+        Space => "space",
+        Insert => "Insert",
+        Delete => "Delete",
+        Home => "Home",
+        End => "End",
+        PageUp => "Page_Up",
+        PageDown => "Page_Down",
+        Minus => "-",
+        PlusEquals => "=",
+        Num0 => "0",
+        Num1 => "1",
+        Num2 => "2",
+        Num3 => "3",
+        Num4 => "4",
+        Num5 => "5",
+        Num6 => "6",
+        Num7 => "7",
+        Num8 => "8",
+        Num9 => "9",
+        F1 => "F1",
+        F2 => "F2",
+        F3 => "F3",
+        F4 => "F4",
+        F5 => "F5",
+        F6 => "F6",
+        F7 => "F7",
+        F8 => "F8",
+        F9 => "F9",
+        F10 => "F10",
+        F11 => "F11",
+        F12 => "F12",
+        F13 => "F13",
+        F14 => "F14",
+        F15 => "F15",
+        F16 => "F16",
+        F17 => "F17",
+        F18 => "F18",
+        F19 => "F19",
+        F20 => "F20",
+        // letters are lowercase keysyms, shift is a modifier
+        letter => return Some(format!("{letter:?}").to_lowercase()).filter(|s| s.len() == 1),
+    };
+    Some(name.into())
+}
+
+impl KeybindingRow {
+    // The first key pressed while capturing, with the Shift, Ctrl and Alt
+    // held down. Super can't be captured, GNOME Shell grabs it before the
+    // window sees it. Esc cancels.
+    fn capture(&mut self, ui: &Ui, keysyms: &Keysyms) -> Option<RowEvent> {
+        let (key, pressed) = ui.input(|i| {
+            i.events.iter().find_map(|e| match e {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        })?;
+        if key == egui::Key::Escape {
+            self.capturing = false;
+            return None;
+        }
+        // keep waiting for a key the row can hold
+        let name = key_name(key)?;
+        self.capturing = false;
+        let modifiers = Modifiers {
+            shift: pressed.shift,
+            ctrl: pressed.ctrl,
+            alt: pressed.alt,
+            super_key: false,
+        };
+        self.assign(modifiers, &name, keysyms);
+        Some(RowEvent::Changed)
+    }
+
+    // the value and a note instead of the edit fields
+    fn show_locked(&mut self, ui: &mut Ui) -> Option<RowEvent> {
+        ui.horizontal(|ui| {
+            let clicked = ui.selectable_label(self.selected, &self.label).clicked();
+            let te = TextEdit::singleline(&mut self.gsettings_value).interactive(false);
+            ui.add_sized(Vec2::new(300.0, 20.0), te);
+            ui.weak(format!("🔒 {LOCKED_NOTE}"))
+                .on_hover_text("Locked through dconf, writes to it are dropped");
+            clicked.then_some(RowEvent::Clicked)
+        })
+        .inner
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        id_source: impl Hash,
+        keysyms: &Keysyms,
+    ) -> Option<RowEvent> {
+        if self.locked {
+            return self.show_locked(ui);
+        }
+        let event = ui.horizontal(|ui| {
+            let mut event = None;
+
+            if let Some((icon, text)) = &self.badge {
+                ui.label(icon).on_hover_text(text);
+            }
+            let label = match &self.conflict {
+                Some(_) => egui::RichText::new(&self.label).color(ui.visuals().error_fg_color),
+                None => egui::RichText::new(&self.label),
+            };
+            let mut response = ui.selectable_label(self.selected, label);
+            if let Some(conflict) = &self.conflict {
+                response = response.on_hover_text(conflict);
+            }
+            if response.clicked() {
+                event = Some(RowEvent::Clicked);
+            }
+            if let Some(location) = &self.location {
+                ui.label("⌨").on_hover_text(location);
+            }
+
+            ui.push_id(id_source, |ui| {
+                if modifier_checkboxes(ui, &mut self.modifiers) {
+                    event = Some(RowEvent::Changed);
+                }
+            });
+
+            let te = TextEdit::singleline(&mut self.keybinding);
+            if ui.add_sized(Vec2::new(40.0, 20.0), te).changed() {
+                event = Some(RowEvent::Changed);
+                // make sure only 1 key is typed, named keys like Page_Down
+                // come from gsettings or quick assign
+                if let Some(c) = self.keybinding.chars().last() {
+                    self.keybinding = c.into();
+                }
+            }
+
+            let record = if self.capturing {
+                "Press a key…"
+            } else {
+                "Record"
+            };
+            if ui
+                .selectable_label(self.capturing, record)
+                .on_hover_text("Press the combination to use instead of typing it, Esc cancels")
+                .clicked()
+            {
+                self.capturing = !self.capturing;
+                // keep the key press out of a text field that had focus
+                ui.memory_mut(|m| m.stop_text_input());
+            } else if self.capturing {
+                if let Some(captured) = self.capture(ui, keysyms) {
+                    event = Some(captured);
+                }
+            }
+
+            // only recompute on edits, most frames just repaint
+            if event == Some(RowEvent::Changed) {
+                self.update_converted_keybinding(keysyms);
+            }
+
+            let converted_te =
+                TextEdit::singleline(&mut self.converted_keybinding).interactive(false);
+            ui.add_sized(Vec2::new(300.0, 20.0), converted_te);
+
+            let te3 = TextEdit::singleline(&mut self.gsettings_value).interactive(false);
+            ui.add_sized(Vec2::new(300.0, 20.0), te3);
+
+            let mut picked = None;
+            let mut removed = None;
+            for (i, extra) in self.extra.iter().enumerate() {
+                if ui
+                    .small_button(extra.format())
+                    .on_hover_text("Edit this binding")
+                    .clicked()
+                {
+                    picked = Some(i);
+                }
+                if ui
+                    .small_button("✖")
+                    .on_hover_text("Remove this binding")
+                    .clicked()
+                {
+                    removed = Some(i);
+                }
+            }
+            for entry in &self.foreign {
+                ui.weak(entry)
+                    .on_hover_text("Can't be edited here, it's written back as it is");
+            }
+            if let Some(i) = picked {
+                self.edit_extra(i, keysyms);
+                event = Some(RowEvent::Changed);
+            } else if let Some(i) = removed {
+                self.remove_extra(i, keysyms);
+                event = Some(RowEvent::Changed);
+            }
+            if ui
+                .add_enabled(!self.is_incomplete_edit(), egui::Button::new("+"))
+                .on_hover_text("Keep this binding and add another one")
+                .clicked()
+            {
+                self.add_binding(keysyms);
+                event = Some(RowEvent::Changed);
+            }
+
+            let incomplete = self.is_incomplete();
+            if ui
+                .add_enabled(!incomplete, egui::Button::new("Overwrite"))
+                .on_disabled_hover_text("Enter a key first")
+                .clicked()
+            {
+                event = Some(RowEvent::Overwrite);
+            }
+            if incomplete {
+                ui.colored_label(ui.visuals().warn_fg_color, "No key set");
+                if ui.button("Unbind instead").clicked() {
+                    event = Some(RowEvent::Unbind);
+                }
+            }
+            ui.menu_button("⋯", |ui| {
+                if ui.button("Unbind").clicked() {
+                    event = Some(RowEvent::Unbind);
+                    ui.close_menu();
+                }
+            });
+            match &self.status {
+                _ if self.pending => {
+                    ui.spinner();
+                }
+                RowStatus::None => {}
+                RowStatus::Applied => {
+                    ui.label("✔");
+                }
+                RowStatus::Failed(_) => {
+                    ui.colored_label(ui.visuals().error_fg_color, "✖");
+                }
+            }
+            event
+        });
+
+        if let RowStatus::Failed(e) = &self.status {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+
+        event.inner
+    }
+}
+
+/// A list of [`KeybindingRow`]s that writes changes through its backend on
+/// its own, for embedding a keybinding editor in other egui applications.
+pub struct KeybindingTable {
+    pub rows: Vec<KeybindingRow>,
+    pub keysyms: Keysyms,
+    backend: Box<dyn SettingsBackend>,
+}
+
+impl KeybindingTable {
+    pub fn new(backend: Box<dyn SettingsBackend>) -> Self {
+        Self {
+            rows: vec![],
+            keysyms: Keysyms::load(),
+            backend,
+        }
+    }
+
+    pub fn with_row(mut self, schema: &str, gsettings_key: &str, label: &str) -> Self {
+        self.rows
+            .push(KeybindingRow::new(schema, gsettings_key, label));
+        self
+    }
+
+    pub fn reload(&mut self) {
+        for row in &mut self.rows {
+            let res = row.reload(self.backend.as_ref(), &self.keysyms);
+            if res.is_err() {
+                row.set_result(res);
+            }
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let id = format!("{}/{}/{i}", row.schema, row.gsettings_key);
+            if let Some(event) = row.show(ui, id, &self.keysyms) {
+                row.apply(event, self.backend.as_ref(), &self.keysyms);
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use egui::{ColorImage, TextureHandle, TextureOptions, Ui, Vec2};
+
+use crate::desktop_entries::DesktopEntry;
+
+const ICON_SIZE: u32 = 16;
+
+/// A searchable list of installed applications with their icons, for
+/// fields that would otherwise need a command or desktop file id typed in.
+pub struct AppPicker {
+    apps: Vec<DesktopEntry>,
+    filter: String,
+    selected: Option<usize>,
+    // by icon name, None when the icon couldn't be loaded
+    icons: HashMap<String, Option<TextureHandle>>,
+}
+
+impl AppPicker {
+    pub fn new(apps: Vec<DesktopEntry>) -> Self {
+        Self {
+            apps,
+            filter: "".into(),
+            selected: None,
+            icons: HashMap::new(),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&DesktopEntry> {
+        self.apps.get(self.selected?)
+    }
+
+    pub fn app(&self, id: &str) -> Option<&DesktopEntry> {
+        self.apps.iter().find(|a| a.id == id)
+    }
+
+    fn icon(&mut self, ui: &Ui, app: &DesktopEntry) -> Option<TextureHandle> {
+        let name = app.icon.as_ref()?;
+        self.icons
+            .entry(name.clone())
+            .or_insert_with(|| {
+                let path = app.icon_path()?;
+                let image = image::open(&path).ok()?;
+                let image = image.thumbnail(ICON_SIZE, ICON_SIZE).to_rgba8();
+                let size = [image.width() as usize, image.height() as usize];
+                let image = ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+                Some(ui.ctx().load_texture(name, image, TextureOptions::LINEAR))
+            })
+            .clone()
+    }
+
+    /// Returns true when the selection changed.
+    pub fn show(&mut self, ui: &mut Ui, id_source: impl std::hash::Hash) -> bool {
+        ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text("Search applications"));
+
+        let filter = self.filter.to_lowercase();
+        let visible: Vec<usize> = (0..self.apps.len())
+            .filter(|&i| {
+                let app = &self.apps[i];
+                filter.is_empty()
+                    || app.name.to_lowercase().contains(&filter)
+                    || app.id.to_lowercase().contains(&filter)
+            })
+            .collect();
+
+        let mut changed = false;
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical()
+            .id_source(id_source)
+            .max_height(row_height * 8.0)
+            .show_rows(ui, row_height, visible.len(), |ui, range| {
+                for &i in &visible[range] {
+                    let app = self.apps[i].clone();
+                    ui.horizontal(|ui| {
+                        match self.icon(ui, &app) {
+                            Some(texture) => {
+                                ui.image(texture.id(), Vec2::splat(ICON_SIZE as f32));
+                            }
+                            None => {
+                                ui.add_space(ICON_SIZE as f32);
+                            }
+                        }
+                        if ui
+                            .selectable_label(self.selected == Some(i), &app.name)
+                            .on_hover_text(&app.id)
+                            .clicked()
+                        {
+                            self.selected = Some(i);
+                            changed = true;
+                        }
+                    });
+                }
+            });
+        changed
+    }
+}
@@ -0,0 +1,61 @@
+//! Settings calls off the UI thread. Every `gsettings` call spawns a
+//! process, so reading a screen of rows or applying a preset on the UI
+//! thread freezes the window for a noticeable time.
+
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+type Job<T> = Box<dyn FnOnce() -> T + Send>;
+
+/// One thread running jobs in the order they were queued, so a read queued
+/// after a write sees what was written. Stopped when this is dropped.
+///
+/// `notify` is called on the worker thread after every result, to wake up
+/// whoever collects them with [`Worker::finished`].
+pub struct Worker<T> {
+    jobs: Sender<Job<T>>,
+    results: Receiver<T>,
+    // queued jobs whose result wasn't taken yet
+    running: usize,
+}
+
+impl<T: Send + 'static> Worker<T> {
+    pub fn start(notify: impl Fn() + Send + 'static) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job<T>>();
+        let (sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            for job in queue {
+                if sender.send(job()).is_err() {
+                    break;
+                }
+                notify();
+            }
+        });
+        Self {
+            jobs,
+            results,
+            running: 0,
+        }
+    }
+
+    pub fn run(&mut self, job: impl FnOnce() -> T + Send + 'static) {
+        if self.jobs.send(Box::new(job)).is_ok() {
+            self.running += 1;
+        } else {
+            tracing::error!("the settings worker stopped");
+        }
+    }
+
+    /// The results of the jobs finished since the last call.
+    pub fn finished(&mut self) -> Vec<T> {
+        let results: Vec<T> = self.results.try_iter().collect();
+        self.running -= results.len();
+        results
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.running > 0
+    }
+}
@@ -0,0 +1,42 @@
+//! Ask the running session which workspace is active. X11 has a property
+//! for it; on Wayland only GNOME Shell knows, and it answers Eval calls
+//! just in unsafe mode or with development tools enabled.
+
+use anyhow::{bail, Result};
+
+/// The 1-based number of the current workspace.
+pub fn current() -> Result<usize> {
+    #[cfg(feature = "x11")]
+    if crate::x11::is_x11() {
+        return Ok(crate::x11::current_desktop()? + 1);
+    }
+    #[cfg(feature = "portal")]
+    {
+        Ok(shell_eval_workspace()? + 1)
+    }
+    #[cfg(not(feature = "portal"))]
+    bail!("can't query the current workspace without X11 or D-Bus support")
+}
+
+#[cfg(feature = "portal")]
+fn shell_eval_workspace() -> Result<usize> {
+    use anyhow::Context;
+    use zbus::blocking::Connection;
+
+    let conn = Connection::session().context("no session bus")?;
+    let reply = conn.call_method(
+        Some("org.gnome.Shell"),
+        "/org/gnome/Shell",
+        Some("org.gnome.Shell"),
+        "Eval",
+        &("global.workspace_manager.get_active_workspace_index()",),
+    )?;
+    let (ok, result): (bool, String) = reply.body().deserialize()?;
+    if !ok {
+        bail!("GNOME Shell refused to tell the current workspace, it only does in unsafe mode");
+    }
+    result
+        .trim()
+        .parse()
+        .with_context(|| format!("unexpected answer '{result}' from GNOME Shell"))
+}
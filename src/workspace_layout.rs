@@ -0,0 +1,45 @@
+//! How the workspaces are arranged, for previewing a workspace count. GNOME
+//! Shell puts them in a single row, grid extensions like Workspace Matrix
+//! into rows and columns.
+
+use crate::{backend::SettingsBackend, extensions};
+
+pub const WORKSPACE_MATRIX_UUID: &str = "wsmatrix@martin.zurowietz.de";
+pub const WORKSPACE_MATRIX_SCHEMA: &str = "org.gnome.shell.extensions.wsmatrix";
+
+/// Counts offered as one-click choices next to the count field.
+pub const COUNT_PRESETS: [usize; 4] = [4, 6, 9, 10];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+    Strip,
+    // None when the extension's column setting can't be read
+    Grid { columns: Option<usize> },
+}
+
+impl Layout {
+    pub fn detect(backend: &dyn SettingsBackend) -> Self {
+        if !extensions::is_enabled(backend, WORKSPACE_MATRIX_UUID).unwrap_or(false) {
+            return Layout::Strip;
+        }
+        let columns = backend
+            .get(WORKSPACE_MATRIX_SCHEMA, "num-columns")
+            .ok()
+            // integers may be printed with their type, like `int32 3`
+            .and_then(|v| v.split_whitespace().last()?.parse().ok())
+            .filter(|columns| *columns > 0);
+        Layout::Grid { columns }
+    }
+
+    /// (rows, columns) `count` workspaces take up, an unknown grid is made
+    /// as square as possible.
+    pub fn shape(&self, count: usize) -> (usize, usize) {
+        let count = count.max(1);
+        let columns = match self {
+            Layout::Strip => count,
+            Layout::Grid { columns: Some(c) } => (*c).min(count),
+            Layout::Grid { columns: None } => (1..=count).find(|c| c * c >= count).unwrap_or(1),
+        };
+        (count.div_ceil(columns), columns)
+    }
+}
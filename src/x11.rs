@@ -0,0 +1,138 @@
+//! Check on X11 whether another client already grabs an accelerator by
+//! grabbing it on the root window ourselves: the server refuses a passive
+//! grab that another client holds with BadAccess. A successful grab is
+//! released right away.
+
+use anyhow::{Context, Result};
+use x11rb::{
+    connection::Connection,
+    errors::ReplyError,
+    protocol::{
+        xproto::{AtomEnum, ConnectionExt, GrabMode, ModMask},
+        ErrorKind,
+    },
+};
+
+use crate::{accelerator::Modifiers, keysym::Keysyms};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrabResult {
+    Free,
+    // another client holds a grab on the same key and modifiers
+    Grabbed,
+}
+
+// named keys outside the character table that are common in keybindings
+const NAMED_KEYSYMS: &[(&str, u32)] = &[
+    ("space", 0x0020),
+    ("BackSpace", 0xff08),
+    ("Tab", 0xff09),
+    ("Return", 0xff0d),
+    ("Escape", 0xff1b),
+    ("Home", 0xff50),
+    ("Left", 0xff51),
+    ("Up", 0xff52),
+    ("Right", 0xff53),
+    ("Down", 0xff54),
+    ("Page_Up", 0xff55),
+    ("Page_Down", 0xff56),
+    ("End", 0xff57),
+    ("Insert", 0xff63),
+    ("Print", 0xff61),
+    ("Delete", 0xffff),
+];
+
+pub fn is_x11() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "x11")
+        || (std::env::var_os("DISPLAY").is_some() && std::env::var_os("WAYLAND_DISPLAY").is_none())
+}
+
+/// The numeric keysym of a key name as used in gsettings values.
+pub fn keysym_value(keysyms: &Keysyms, key: &str) -> Option<u32> {
+    if let Some(code) = keysyms.code(key) {
+        return Some(code);
+    }
+    if let Some(&(_, code)) = NAMED_KEYSYMS.iter().find(|(name, _)| *name == key) {
+        return Some(code);
+    }
+    if let Some(n) = key.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+        if (1..=35).contains(&n) {
+            return Some(0xffbe + n - 1);
+        }
+    }
+    let mut chars = key.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    // Latin-1 keysyms equal their code point, everything else is offset
+    Some(match c as u32 {
+        cp @ (0x20..=0x7e | 0xa0..=0xff) => cp,
+        cp => 0x0100_0000 + cp,
+    })
+}
+
+fn mod_mask(modifiers: Modifiers) -> ModMask {
+    let mut mask = ModMask::from(0u16);
+    for (set, m) in [
+        (modifiers.shift, ModMask::SHIFT),
+        (modifiers.ctrl, ModMask::CONTROL),
+        (modifiers.alt, ModMask::M1),
+        (modifiers.super_key, ModMask::M4),
+    ] {
+        if set {
+            mask |= m;
+        }
+    }
+    mask
+}
+
+pub fn probe(modifiers: Modifiers, keysym: u32) -> Result<GrabResult> {
+    let (conn, screen_num) = x11rb::connect(None).context("can't connect to the X server")?;
+    let setup = conn.setup();
+    let root = setup.roots[screen_num].root;
+
+    let min = setup.min_keycode;
+    let count = setup.max_keycode - min + 1;
+    let mapping = conn.get_keyboard_mapping(min, count)?.reply()?;
+    let per_keycode = usize::from(mapping.keysyms_per_keycode);
+    let keycode = mapping
+        .keysyms
+        .chunks(per_keycode)
+        .position(|syms| syms.contains(&keysym))
+        .map(|i| min + i as u8)
+        .with_context(|| format!("no key on the current layout produces keysym {keysym:#x}"))?;
+
+    let mask = mod_mask(modifiers);
+    let grab = conn
+        .grab_key(false, root, mask, keycode, GrabMode::ASYNC, GrabMode::ASYNC)?
+        .check();
+    match grab {
+        Ok(()) => {
+            conn.ungrab_key(keycode, root, mask)?.check()?;
+            Ok(GrabResult::Free)
+        }
+        Err(ReplyError::X11Error(e)) if e.error_kind == ErrorKind::Access => {
+            Ok(GrabResult::Grabbed)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The 0-based index of the current workspace, from the EWMH
+/// `_NET_CURRENT_DESKTOP` property mutter keeps on the root window.
+pub fn current_desktop() -> Result<usize> {
+    let (conn, screen_num) = x11rb::connect(None).context("can't connect to the X server")?;
+    let root = conn.setup().roots[screen_num].root;
+    let atom = conn
+        .intern_atom(true, b"_NET_CURRENT_DESKTOP")?
+        .reply()?
+        .atom;
+    let reply = conn
+        .get_property(false, root, atom, AtomEnum::CARDINAL, 0, 1)?
+        .reply()?;
+    let index = reply
+        .value32()
+        .and_then(|mut values| values.next())
+        .context("the window manager doesn't set _NET_CURRENT_DESKTOP")?;
+    Ok(index as usize)
+}
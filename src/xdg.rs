@@ -0,0 +1,81 @@
+//! Where files go per the XDG base directory spec: what users edit or sync
+//! in the config directory, backups in the data directory, the write
+//! journal and logs in the state directory.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use directories::{BaseDirs, ProjectDirs};
+
+const APP_DIR: &str = "gnome-workspace-shortcuts-menu";
+
+// None only when there is no home directory at all, neither HOME nor a
+// passwd entry
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", APP_DIR)
+}
+
+// without a home directory files go into the temporary directory, not
+// relative to wherever the app was started
+fn or_temp(dir: Option<PathBuf>) -> PathBuf {
+    dir.unwrap_or_else(|| std::env::temp_dir().join(APP_DIR))
+}
+
+/// Profiles, macros and preferences.
+pub fn config_dir() -> PathBuf {
+    or_temp(project_dirs().map(|dirs| dirs.config_dir().to_path_buf()))
+}
+
+/// Backups of the values from before this app changed them.
+pub fn data_dir() -> PathBuf {
+    or_temp(project_dirs().map(|dirs| dirs.data_dir().to_path_buf()))
+}
+
+/// The write journal and logs, kept between runs but not worth syncing.
+pub fn state_dir() -> PathBuf {
+    or_temp(project_dirs().and_then(|dirs| dirs.state_dir().map(|dir| dir.to_path_buf())))
+}
+
+/// `name` in `dir`, creating `dir` if it doesn't exist yet.
+pub fn create_file_path(dir: PathBuf, name: &str) -> Result<PathBuf> {
+    fs::create_dir_all(&dir).with_context(|| format!("can't create {}", dir.display()))?;
+    Ok(dir.join(name))
+}
+
+/// Move files older versions kept in the config directory to where they
+/// belong now. Files already at the new place are left alone.
+pub fn migrate_legacy_files(moves: &[(&str, PathBuf)]) {
+    for (name, to) in moves {
+        let from = config_dir().join(name);
+        if !from.exists() || to.exists() {
+            continue;
+        }
+        let res = to
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::rename(&from, to));
+        match res {
+            Ok(()) => tracing::info!("moved {} to {}", from.display(), to.display()),
+            Err(e) => tracing::warn!("can't move {}: {}", from.display(), e),
+        }
+    }
+}
+
+/// `XDG_DATA_HOME` followed by `XDG_DATA_DIRS`, most important first.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let system = match std::env::var("XDG_DATA_DIRS") {
+        Ok(dirs) if !dirs.is_empty() => dirs,
+        _ => "/usr/local/share:/usr/share".into(),
+    };
+    let mut dirs: Vec<PathBuf> = BaseDirs::new()
+        .map(|base| base.data_dir().to_path_buf())
+        .into_iter()
+        .collect();
+    dirs.extend(
+        system
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from),
+    );
+    dirs
+}
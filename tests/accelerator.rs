@@ -0,0 +1,133 @@
+use gnome_workspace_shortcuts_menu::accelerator::{Accelerator, Modifiers};
+use proptest::prelude::*;
+
+fn modifiers() -> impl Strategy<Value = Modifiers> {
+    (any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>()).prop_map(
+        |(shift, ctrl, alt, super_key)| Modifiers {
+            shift,
+            ctrl,
+            alt,
+            super_key,
+        },
+    )
+}
+
+fn keysym() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[a-zA-Z0-9]",
+        "[A-Za-z][A-Za-z0-9_]{1,20}",
+        Just("Page_Down".to_string()),
+        Just("KP_Add".to_string()),
+        Just("XF86AudioRaiseVolume".to_string()),
+        Just("ISO_Left_Tab".to_string()),
+        Just("F12".to_string()),
+        Just("less".to_string()),
+        Just("dead_circumflex".to_string()),
+    ]
+}
+
+fn accelerator() -> impl Strategy<Value = Accelerator> {
+    (modifiers(), keysym()).prop_map(|(modifiers, key)| Accelerator::new(modifiers, &key))
+}
+
+fn modifier_names(modifiers: &Modifiers) -> Vec<&'static str> {
+    let mut names = vec![];
+    if modifiers.shift {
+        names.push("<Shift>");
+    }
+    if modifiers.ctrl {
+        names.push("<Primary>");
+    }
+    if modifiers.alt {
+        names.push("<Alt>");
+    }
+    if modifiers.super_key {
+        names.push("<Super>");
+    }
+    names
+}
+
+proptest! {
+    #[test]
+    fn parse_inverts_format(accelerator in accelerator()) {
+        let parsed = Accelerator::parse(&accelerator.format()).unwrap();
+        prop_assert_eq!(parsed, accelerator);
+    }
+
+    #[test]
+    fn gsettings_value_round_trips(accelerator in accelerator()) {
+        let parsed = Accelerator::from_gsettings(&accelerator.to_gsettings()).unwrap();
+        prop_assert_eq!(parsed, Some(accelerator));
+    }
+
+    #[test]
+    fn gsettings_arrays_round_trip(accelerators in prop::collection::vec(accelerator(), 0..4)) {
+        let value = Accelerator::list_to_gsettings(&accelerators);
+        prop_assert_eq!(Accelerator::list_from_gsettings(&value).unwrap(), accelerators);
+    }
+
+    #[test]
+    fn display_and_from_str_round_trip(accelerator in accelerator()) {
+        let parsed: Accelerator = accelerator.to_string().parse().unwrap();
+        prop_assert_eq!(parsed, accelerator);
+    }
+
+    #[test]
+    fn modifier_order_does_not_matter(
+        accelerator in accelerator(),
+        order in Just((0..4).collect::<Vec<usize>>()).prop_shuffle(),
+    ) {
+        let names = modifier_names(&accelerator.modifiers);
+        let mut s: String = order
+            .iter()
+            .filter_map(|&i| names.get(i).copied())
+            .collect();
+        s.push_str(&accelerator.key);
+
+        let parsed = Accelerator::parse(&s).unwrap();
+        prop_assert_eq!(parsed.format(), accelerator.format());
+    }
+}
+
+#[test]
+fn shift_super_keeps_ui_order() {
+    let accelerator = Accelerator::parse("<Super><Shift>3").unwrap();
+    assert_eq!(accelerator.format(), "<Shift><Super>3");
+    assert_eq!(
+        Modifiers::parse("<Shift><Super>").unwrap(),
+        accelerator.modifiers
+    );
+}
+
+#[test]
+fn ctrl_aliases() {
+    for s in ["<Ctrl>a", "<Control>a", "<Primary>a", "<primary>a"] {
+        assert_eq!(Accelerator::parse(s).unwrap().format(), "<Ctrl>a", "{s}");
+    }
+}
+
+#[test]
+fn rejects_incomplete_accelerators() {
+    assert!(Accelerator::parse("<Super>").is_err());
+    assert!(Accelerator::parse("").is_err());
+    assert!(Accelerator::parse("<Super").is_err());
+    assert!(Accelerator::parse("<Hyper>1").is_err());
+}
+
+#[test]
+fn gsettings_arrays_are_escaped_and_annotated() {
+    let accelerators = vec![
+        Accelerator::parse("<Super>'").unwrap(),
+        Accelerator::parse("<Alt>\\").unwrap(),
+    ];
+    let value = Accelerator::list_to_gsettings(&accelerators);
+    assert_eq!(value, r"['<Super>\'', '<Alt>\\']");
+    assert_eq!(
+        Accelerator::list_from_gsettings(&value).unwrap(),
+        accelerators
+    );
+    assert_eq!(Accelerator::list_to_gsettings(&[]), "@as []");
+    assert!(Accelerator::list_from_gsettings("@as []")
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    auto_move::{self, Rule, AUTO_MOVE_WINDOWS_SCHEMA},
+    gsettings::GSettings,
+};
+
+#[test]
+fn parses_application_list() {
+    let rules =
+        auto_move::rules_from_gsettings("['firefox.desktop:1', 'org.gnome.Nautilus.desktop:3']")
+            .unwrap();
+    assert_eq!(
+        rules,
+        vec![
+            Rule::new("firefox.desktop", 1),
+            Rule::new("org.gnome.Nautilus.desktop", 3)
+        ]
+    );
+    assert!(auto_move::rules_from_gsettings("['firefox.desktop:0']").is_err());
+}
+
+#[test]
+fn write_keeps_one_rule_per_app() {
+    let fake = FakeGSettings::new().with_value(
+        AUTO_MOVE_WINDOWS_SCHEMA,
+        "application-list",
+        "['firefox.desktop:1']",
+    );
+
+    auto_move::write_rules(
+        &GSettings,
+        &[
+            Rule::new("firefox.desktop", 2),
+            Rule::new("code.desktop", 3),
+            Rule::new("firefox.desktop", 4),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        fake.value(AUTO_MOVE_WINDOWS_SCHEMA, "application-list")
+            .as_deref(),
+        Some("['firefox.desktop:4', 'code.desktop:3']")
+    );
+}
@@ -0,0 +1,191 @@
+//! A scripted stand-in for the `gsettings` binary. It is put first on PATH,
+//! records every invocation and serves values from a directory tree, so the
+//! backend can be driven end-to-end without a GNOME session.
+
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use tempfile::TempDir;
+
+// PATH and the XDG directories are process wide, tests using the fake must not
+// run concurrently
+static LOCK: Mutex<()> = Mutex::new(());
+
+// values/<schema>/<key>    holds the current value
+// defaults/<schema>/<key>  is what `get` prints with GSETTINGS_BACKEND=memory
+// descriptions/<schema>/<key> is what `describe` prints
+// reject/<schema>/<key>    makes `set` fail with the file content on stderr
+// readonly/<schema>/<key>  makes `set` succeed without storing anything
+// locked/<schema>/<key>    makes `writable` print false
+// calls                    one line per invocation, arguments tab separated
+const SCRIPT: &str = r#"#!/bin/sh
+dir="$FAKE_GSETTINGS_DIR"
+for arg in "$@"; do printf '%s\t' "$arg"; done >> "$dir/calls"
+printf '\n' >> "$dir/calls"
+
+case "$1" in
+get)
+    if [ "$GSETTINGS_BACKEND" = memory ]; then
+        if [ -f "$dir/defaults/$2/$3" ]; then
+            cat "$dir/defaults/$2/$3"
+        else
+            echo "No such key “$3”" >&2
+            exit 1
+        fi
+    elif [ -f "$dir/values/$2/$3" ]; then
+        cat "$dir/values/$2/$3"
+    else
+        echo "No such key “$3”" >&2
+        exit 1
+    fi
+    ;;
+set)
+    if [ -f "$dir/reject/$2/$3" ]; then
+        cat "$dir/reject/$2/$3" >&2
+        exit 1
+    fi
+    if [ ! -f "$dir/readonly/$2/$3" ]; then
+        mkdir -p "$dir/values/$2"
+        printf '%s\n' "$4" > "$dir/values/$2/$3"
+    fi
+    ;;
+describe)
+    if [ -f "$dir/descriptions/$2/$3" ]; then
+        cat "$dir/descriptions/$2/$3"
+    elif [ ! -f "$dir/values/$2/$3" ]; then
+        echo "No such key “$3”" >&2
+        exit 1
+    fi
+    ;;
+writable)
+    if [ -f "$dir/locked/$2/$3" ]; then
+        echo false
+    elif [ -f "$dir/values/$2/$3" ]; then
+        echo true
+    else
+        echo "No such key “$3”" >&2
+        exit 1
+    fi
+    ;;
+list-keys)
+    if [ -d "$dir/values/$2" ]; then
+        ls "$dir/values/$2"
+    else
+        echo "No such schema “$2”" >&2
+        exit 1
+    fi
+    ;;
+list-recursively)
+    if [ ! -d "$dir/values/$2" ]; then
+        echo "No such schema “$2”" >&2
+        exit 1
+    fi
+    for file in "$dir/values/$2"/*; do
+        [ -f "$file" ] && printf '%s %s %s\n' "$2" "$(basename "$file")" "$(cat "$file")"
+    done
+    ;;
+*)
+    echo "fake gsettings does not support $1" >&2
+    exit 1
+    ;;
+esac
+"#;
+
+pub struct FakeGSettings {
+    dir: TempDir,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl FakeGSettings {
+    pub fn new() -> Self {
+        let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = tempfile::tempdir().unwrap();
+
+        let bin = dir.path().join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let script = bin.join("gsettings");
+        fs::write(&script, SCRIPT).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(dir.path().join("calls"), "").unwrap();
+
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let mut paths = vec![bin];
+        paths.extend(std::env::split_paths(&path));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+        std::env::set_var("FAKE_GSETTINGS_DIR", dir.path());
+        std::env::set_var("XDG_CONFIG_HOME", dir.path().join("config"));
+        std::env::set_var("XDG_DATA_HOME", dir.path().join("data"));
+        std::env::set_var("XDG_STATE_HOME", dir.path().join("state"));
+
+        Self { dir, _lock: lock }
+    }
+
+    fn file(&self, kind: &str, schema: &str, key: &str) -> PathBuf {
+        let dir = self.dir.path().join(kind).join(schema);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(key)
+    }
+
+    pub fn with_value(self, schema: &str, key: &str, value: &str) -> Self {
+        fs::write(self.file("values", schema, key), format!("{value}\n")).unwrap();
+        self
+    }
+
+    pub fn with_default(self, schema: &str, key: &str, value: &str) -> Self {
+        fs::write(self.file("defaults", schema, key), format!("{value}\n")).unwrap();
+        self
+    }
+
+    pub fn with_description(self, schema: &str, key: &str, description: &str) -> Self {
+        fs::write(self.file("descriptions", schema, key), description).unwrap();
+        self
+    }
+
+    pub fn rejecting(self, schema: &str, key: &str, stderr: &str) -> Self {
+        fs::write(self.file("reject", schema, key), stderr).unwrap();
+        self
+    }
+
+    pub fn readonly(self, schema: &str, key: &str) -> Self {
+        fs::write(self.file("readonly", schema, key), "").unwrap();
+        self
+    }
+
+    /// Locked through dconf: reported as not writable, writes are dropped.
+    pub fn locked(self, schema: &str, key: &str) -> Self {
+        fs::write(self.file("locked", schema, key), "").unwrap();
+        self.readonly(schema, key)
+    }
+
+    pub fn value(&self, schema: &str, key: &str) -> Option<String> {
+        fs::read_to_string(self.dir.path().join("values").join(schema).join(key))
+            .ok()
+            .map(|v| v.trim_end().to_string())
+    }
+
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        fs::read_to_string(self.dir.path().join("calls"))
+            .unwrap()
+            .lines()
+            .map(|line| {
+                line.trim_end_matches('\t')
+                    .split('\t')
+                    .map(String::from)
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn set_calls(&self) -> Vec<Vec<String>> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call[0] == "set")
+            .collect()
+    }
+}
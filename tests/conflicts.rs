@@ -0,0 +1,122 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    accelerator::Accelerator,
+    conflicts::ConflictIndex,
+    gsettings::{GSettings, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA},
+    transaction::{Change, Transaction},
+};
+
+#[test]
+fn finds_keys_sharing_an_accelerator_across_schemas() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "minimize", "['<Super>h']")
+        .with_value(
+            SHELL_KEYBINDINGS_SCHEMA,
+            "switch-to-application-1",
+            "['<Super>1']",
+        );
+
+    let mut index = ConflictIndex::scan(&GSettings);
+
+    assert_eq!(
+        index.others(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']"
+        ),
+        [(
+            SHELL_KEYBINDINGS_SCHEMA.to_string(),
+            "switch-to-application-1".to_string()
+        )]
+    );
+    assert!(index
+        .others(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>2']"
+        )
+        .is_empty());
+
+    index.update(
+        SHELL_KEYBINDINGS_SCHEMA,
+        "switch-to-application-1",
+        "@as []",
+    );
+    assert!(index
+        .others(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']"
+        )
+        .is_empty());
+}
+
+#[test]
+fn lists_the_keys_bound_to_an_accelerator() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "move-to-workspace-1",
+            "['<Shift><Super>A', '<Super>1']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "minimize", "['<Super>h']");
+    let index = ConflictIndex::scan(&GSettings);
+
+    let users = |s: &str| index.users(&Accelerator::parse(s).unwrap());
+    assert_eq!(
+        users("<Shift><Super>a"),
+        [(
+            WM_KEYBINDINGS_SCHEMA.to_string(),
+            "move-to-workspace-1".to_string()
+        )]
+    );
+    assert!(users("<Super>a").is_empty());
+    assert_eq!(users("<Super>h").len(), 1);
+}
+
+#[test]
+fn only_conflicts_of_changed_keys_are_introduced() {
+    let mut index = ConflictIndex::default();
+    index.update(
+        SHELL_KEYBINDINGS_SCHEMA,
+        "switch-to-application-1",
+        "['<Super>1']",
+    );
+    // already sharing <Super>d, not touched below
+    index.update(WM_KEYBINDINGS_SCHEMA, "show-desktop", "['<Super>d']");
+    index.update(WM_KEYBINDINGS_SCHEMA, "minimize", "['<Super>d']");
+
+    let change = |key: &str, value: &str| Change {
+        schema: WM_KEYBINDINGS_SCHEMA.into(),
+        key: key.into(),
+        old_value: "@as []".into(),
+        new_value: value.into(),
+    };
+    let mut transaction = Transaction::new();
+    transaction.changes = vec![
+        change("switch-to-workspace-1", "['<Super>1']"),
+        change("switch-to-workspace-2", "['<Super>2']"),
+    ];
+
+    assert_eq!(
+        index.introduced_by(&transaction),
+        [(
+            (
+                WM_KEYBINDINGS_SCHEMA.to_string(),
+                "switch-to-workspace-1".to_string()
+            ),
+            vec![(
+                SHELL_KEYBINDINGS_SCHEMA.to_string(),
+                "switch-to-application-1".to_string()
+            )]
+        )]
+    );
+    assert!(index.introduced_by(&Transaction::new()).is_empty());
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    custom_shortcuts::{self, schema_at, CustomShortcut, MEDIA_KEYS_SCHEMA},
+    gsettings::GSettings,
+    profile::Profile,
+};
+
+const TERMINAL: &str = "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/";
+const STALE: &str = "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom1/";
+
+#[test]
+fn imports_custom_shortcuts_from_gnome_settings() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            MEDIA_KEYS_SCHEMA,
+            "custom-keybindings",
+            &format!("['{TERMINAL}', '{STALE}']"),
+        )
+        .with_value(&schema_at(TERMINAL), "name", "'Terminal'")
+        .with_value(&schema_at(TERMINAL), "command", "\"sh -c 'kgx'\"")
+        .with_value(&schema_at(TERMINAL), "binding", "'<Super>Return'");
+
+    let shortcuts = custom_shortcuts::read_all(&GSettings).unwrap();
+    assert_eq!(
+        shortcuts,
+        vec![CustomShortcut {
+            path: TERMINAL.into(),
+            name: "Terminal".into(),
+            command: "sh -c 'kgx'".into(),
+            binding: "<Super>Return".into(),
+        }]
+    );
+
+    let profile = Profile::capture(&GSettings);
+    assert_eq!(
+        profile.get(&schema_at(TERMINAL), "binding"),
+        Some("'<Super>Return'")
+    );
+}
+
+#[test]
+fn adds_and_removes_custom_shortcuts() {
+    let added = "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom1/";
+    let mut fake = FakeGSettings::new().with_value(
+        MEDIA_KEYS_SCHEMA,
+        "custom-keybindings",
+        &format!("['{TERMINAL}']"),
+    );
+    // relocatable schemas read as their defaults at any path
+    for key in ["name", "command", "binding"] {
+        fake = fake.with_value(&schema_at(added), key, "''");
+    }
+
+    let paths = custom_shortcuts::paths(&GSettings).unwrap();
+    let shortcut = CustomShortcut::new_at_free_path(&paths, "Files", "nautilus", "<Super>e");
+    assert_eq!(shortcut.path, added);
+    let transaction = shortcut.stage_add(&GSettings).unwrap();
+    // the list comes last, after the keys it points to
+    assert_eq!(
+        transaction.changes.last().unwrap().key,
+        "custom-keybindings"
+    );
+    transaction.apply(&GSettings).unwrap();
+    assert_eq!(
+        fake.value(MEDIA_KEYS_SCHEMA, "custom-keybindings")
+            .as_deref(),
+        Some(format!("['{TERMINAL}', '{added}']").as_str())
+    );
+    assert_eq!(CustomShortcut::read(&GSettings, added).unwrap(), shortcut);
+
+    shortcut
+        .stage_remove(&GSettings)
+        .unwrap()
+        .apply(&GSettings)
+        .unwrap();
+    assert_eq!(
+        custom_shortcuts::paths(&GSettings).unwrap(),
+        vec![TERMINAL.to_string()]
+    );
+    assert_eq!(
+        fake.value(&schema_at(added), "command").as_deref(),
+        Some("''")
+    );
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    desktop_entries::{self, DesktopEntry},
+    gsettings::{GSettings, SHELL_SCHEMA},
+};
+
+#[test]
+fn parses_desktop_entry_group_only() {
+    let content = "\
+[Desktop Entry]
+Type=Application
+Name=Files
+Name[de]=Dateien
+Exec=nautilus --new-window %U
+Icon=org.gnome.Nautilus
+
+[Desktop Action new-window]
+Name=New Window
+Exec=nautilus --new-window
+";
+    assert_eq!(
+        desktop_entries::parse("org.gnome.Nautilus.desktop", content),
+        Some(DesktopEntry {
+            id: "org.gnome.Nautilus.desktop".into(),
+            name: "Files".into(),
+            exec: Some("nautilus --new-window %U".into()),
+            icon: Some("org.gnome.Nautilus".into()),
+        })
+    );
+}
+
+#[test]
+fn skips_hidden_entries() {
+    let content = "[Desktop Entry]\nType=Application\nName=Helper\nNoDisplay=true\n";
+    assert_eq!(desktop_entries::parse("helper.desktop", content), None);
+}
+
+#[test]
+fn command_drops_field_codes() {
+    let entry = DesktopEntry {
+        id: "firefox.desktop".into(),
+        name: "Firefox".into(),
+        exec: Some("firefox --new-window %u".into()),
+        icon: None,
+    };
+    assert_eq!(entry.command().as_deref(), Some("firefox --new-window"));
+}
+
+#[test]
+fn dash_favorites_are_read_in_order() {
+    let _fake = FakeGSettings::new().with_value(
+        SHELL_SCHEMA,
+        "favorite-apps",
+        "['firefox.desktop', 'org.gnome.Nautilus.desktop']",
+    );
+    assert_eq!(
+        desktop_entries::dash_favorites(&GSettings).unwrap(),
+        ["firefox.desktop", "org.gnome.Nautilus.desktop"]
+    );
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    doctor::Fix,
+    doctor::{self, Severity},
+    gsettings::{
+        GSettings, MUTTER_SCHEMA, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA,
+        WM_PREFERENCES_SCHEMA,
+    },
+};
+
+#[test]
+fn reports_shadowed_workspace_binding() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_value(
+            SHELL_KEYBINDINGS_SCHEMA,
+            "switch-to-application-1",
+            "['<Super>1']",
+        );
+
+    let checks = doctor::run(&GSettings);
+
+    let conflict = checks.iter().find(|c| c.name == "conflict").unwrap();
+    assert_eq!(conflict.severity, Severity::Warning);
+    assert_eq!(
+        conflict.detail,
+        "<Super>1 is bound by switch-to-workspace-1, switch-to-application-1"
+    );
+    assert_eq!(
+        conflict.fix,
+        Some(Fix::Unbind {
+            schema: SHELL_KEYBINDINGS_SCHEMA.into(),
+            key: "switch-to-application-1".into()
+        })
+    );
+}
+
+#[test]
+fn stops_when_backend_does_not_answer() {
+    let _fake = FakeGSettings::new();
+
+    let checks = doctor::run(&GSettings);
+
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].name, "backend");
+}
+
+#[test]
+fn reports_missing_schema_and_dynamic_workspaces() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(MUTTER_SCHEMA, "dynamic-workspaces", "true");
+
+    let checks = doctor::run(&GSettings);
+
+    assert!(checks
+        .iter()
+        .any(|c| c.severity == Severity::Error && c.detail.contains(WM_KEYBINDINGS_SCHEMA)));
+    assert!(checks.iter().any(|c| c.name == "dynamic workspaces"));
+}
+
+#[test]
+fn reports_workspace_binding_taken_by_panel_switcher() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Control><Alt>Tab']",
+        )
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-panels",
+            "['<Control><Alt>Tab']",
+        );
+
+    let checks = doctor::run(&GSettings);
+
+    let conflict = checks.iter().find(|c| c.name == "conflict").unwrap();
+    assert_eq!(
+        conflict.detail,
+        "<Ctrl><Alt>Tab is bound by switch-to-workspace-1, switch-panels"
+    );
+}
+
+#[test]
+fn reports_overlay_key_held_for_super_chords() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(MUTTER_SCHEMA, "overlay-key", "'Super_L'")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "move-to-workspace-1",
+            "['<Shift><Ctrl>1']",
+        );
+
+    let checks = doctor::run(&GSettings);
+
+    let prefix = checks.iter().find(|c| c.name == "prefix").unwrap();
+    assert!(
+        prefix.detail.starts_with(
+            "Super_L alone runs overlay-key and is also held for switch-to-workspace-1;"
+        ),
+        "{}",
+        prefix.detail
+    );
+    assert_eq!(
+        prefix.fix,
+        Some(Fix::Set {
+            schema: MUTTER_SCHEMA.into(),
+            key: "overlay-key".into(),
+            value: "'Super_R'".into()
+        })
+    );
+}
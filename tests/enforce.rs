@@ -0,0 +1,59 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    enforce::Enforcer,
+    gsettings::{GSettings, WM_KEYBINDINGS_SCHEMA},
+    profile::Profile,
+};
+
+#[test]
+fn corrections_are_notified_once_per_cooldown() {
+    let fake = FakeGSettings::new().with_value(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>Home']",
+    );
+    let mut profile = Profile::default();
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1']",
+    );
+    let mut enforcer = Enforcer::new(profile, Duration::from_secs(60));
+    let start = Instant::now();
+
+    let corrections = enforcer.correct(&GSettings, start);
+    assert_eq!(corrections.len(), 1);
+    assert!(corrections[0].notify);
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>1']")
+    );
+    assert!(enforcer
+        .correct(&GSettings, start + Duration::from_secs(1))
+        .is_empty());
+
+    // another tool fighting back within the cooldown is corrected quietly
+    GSettings::set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>Home']",
+    )
+    .unwrap();
+    let corrections = enforcer.correct(&GSettings, start + Duration::from_secs(10));
+    assert_eq!(corrections.len(), 1);
+    assert!(!corrections[0].notify);
+
+    GSettings::set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>Home']",
+    )
+    .unwrap();
+    let corrections = enforcer.correct(&GSettings, start + Duration::from_secs(61));
+    assert!(corrections[0].notify);
+}
@@ -0,0 +1,78 @@
+use gnome_workspace_shortcuts_menu::{
+    export::{self, EXPORTERS},
+    gsettings::{WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA},
+    keyboard::KEYBOARD_SCHEMA,
+    profile::Profile,
+};
+use insta::assert_snapshot;
+
+fn profile() -> Profile {
+    let mut profile = Profile::default();
+    profile.set(WM_PREFERENCES_SCHEMA, "num-workspaces", "4");
+    profile.set(KEYBOARD_SCHEMA, "delay", "uint32 300");
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1', '<Primary><Alt>Home']",
+    );
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "move-to-workspace-1",
+        "['<Shift><Super>1']",
+    );
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-last",
+        "['<Super>Tab']",
+    );
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-left",
+        "['<Super>Left']",
+    );
+    profile.set(WM_KEYBINDINGS_SCHEMA, "minimize", "@as []");
+    profile
+}
+
+fn export(id: &str) -> String {
+    export::find(id).unwrap().export(&profile()).unwrap()
+}
+
+#[test]
+fn script() {
+    assert_snapshot!(export("script"));
+}
+
+#[test]
+fn dconf_keyfile() {
+    assert_snapshot!(export("dconf"));
+}
+
+#[test]
+fn nix() {
+    assert_snapshot!(export("nix"));
+}
+
+#[test]
+fn ansible() {
+    assert_snapshot!(export("ansible"));
+}
+
+#[test]
+fn sway() {
+    assert_snapshot!(export("sway"));
+}
+
+#[test]
+fn hyprland() {
+    assert_snapshot!(export("hyprland"));
+}
+
+#[test]
+fn every_format_has_its_own_id() {
+    let mut ids: Vec<&str> = EXPORTERS.iter().map(|e| e.id()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), EXPORTERS.len());
+    assert!(export::find("toml").is_err());
+}
@@ -0,0 +1,628 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    accelerator::Accelerator,
+    backend::{self, SettingsBackend},
+    backup,
+    gsettings::{
+        self, GSettings, MUTTER_SCHEMA, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA,
+        WM_PREFERENCES_SCHEMA,
+    },
+    keyboard::{KeyRepeat, KEYBOARD_SCHEMA},
+    preset::Preset,
+    transaction::Transaction,
+};
+
+#[test]
+fn get_reads_wm_keybinding() {
+    let _fake = FakeGSettings::new().with_value(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1']",
+    );
+
+    let value = GSettings::get_wm_keybinding("switch-to-workspace-1").unwrap();
+    assert_eq!(value.trim(), "['<Super>1']");
+}
+
+#[test]
+fn wm_keybinding_is_parsed() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1', '<Primary><Alt>1']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-1", "@as []");
+
+    let formatted: Vec<String> = GSettings::wm_keybinding("switch-to-workspace-1")
+        .unwrap()
+        .iter()
+        .map(Accelerator::format)
+        .collect();
+    assert_eq!(formatted, ["<Super>1", "<Ctrl><Alt>1"]);
+    assert!(GSettings::wm_keybinding("move-to-workspace-1")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn get_fails_for_unknown_key() {
+    let _fake = FakeGSettings::new();
+
+    let err = GSettings::get_wm_keybinding("switch-to-workspace-99").unwrap_err();
+    assert!(err.to_string().contains("No such key"), "{err}");
+}
+
+#[test]
+fn set_passes_schema_key_and_value() {
+    let fake = FakeGSettings::new();
+
+    GSettings::set_wm_keybinding("move-to-workspace-3", "['<Shift><Super>3']").unwrap();
+
+    assert_eq!(
+        fake.set_calls(),
+        vec![vec![
+            "set",
+            WM_KEYBINDINGS_SCHEMA,
+            "move-to-workspace-3",
+            "['<Shift><Super>3']"
+        ]]
+    );
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-3")
+            .as_deref(),
+        Some("['<Shift><Super>3']")
+    );
+}
+
+#[test]
+fn set_surfaces_stderr() {
+    let _fake = FakeGSettings::new().rejecting(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "0-1:unknown keyword",
+    );
+
+    let err = GSettings::set_wm_keybinding("switch-to-workspace-1", "[<Super>1]").unwrap_err();
+    assert_eq!(err.to_string(), "0-1:unknown keyword");
+}
+
+#[test]
+fn workspace_settings_are_parsed() {
+    let fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "6")
+        .with_value(MUTTER_SCHEMA, "dynamic-workspaces", "true");
+
+    assert_eq!(GSettings::get_number_of_workspaces().unwrap(), 6);
+    assert!(GSettings::get_dynamic_workspaces().unwrap());
+
+    GSettings::set_dynamic_workspaces(false).unwrap();
+    GSettings::set_number_of_workspaces(9).unwrap();
+    assert_eq!(
+        fake.value(MUTTER_SCHEMA, "dynamic-workspaces").as_deref(),
+        Some("false")
+    );
+    assert_eq!(GSettings::get_number_of_workspaces().unwrap(), 9);
+}
+
+#[test]
+fn unbind_writes_empty_array() {
+    let fake = FakeGSettings::new().with_value(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-2",
+        "['<Super>2']",
+    );
+
+    GSettings::unbind(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2").unwrap();
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2")
+            .as_deref(),
+        Some("@as []")
+    );
+}
+
+#[test]
+fn unbind_fails_when_value_is_not_stored() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-2",
+            "['<Super>2']",
+        )
+        .readonly(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2");
+
+    let err = GSettings::unbind(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2").unwrap_err();
+    assert!(err.to_string().contains("is still ['<Super>2']"), "{err}");
+}
+
+#[test]
+fn transaction_rolls_back_on_failure() {
+    let fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>Home']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2", "@as []")
+        .rejecting(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-2",
+            "key is not writable",
+        );
+
+    let mut transaction = Transaction::new();
+    transaction
+        .stage(
+            &GSettings,
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .unwrap();
+    transaction
+        .stage(
+            &GSettings,
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-2",
+            "['<Super>2']",
+        )
+        .unwrap();
+
+    let err = transaction.apply(&GSettings).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("key is not writable"),
+        "{err:#}"
+    );
+    assert!(
+        format!("{err:#}").ends_with("the earlier writes were rolled back"),
+        "{err:#}"
+    );
+
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>Home']")
+    );
+    let written: Vec<String> = fake
+        .set_calls()
+        .into_iter()
+        .map(|call| call[3].clone())
+        .collect();
+    assert_eq!(
+        written,
+        vec!["['<Super>1']", "['<Super>2']", "['<Super>Home']"]
+    );
+
+    let backup = backup::read_backup().unwrap();
+    assert!(backup.contains(&(
+        WM_KEYBINDINGS_SCHEMA.into(),
+        "switch-to-workspace-1".into(),
+        "['<Super>Home']".into()
+    )));
+}
+
+#[test]
+fn transaction_revert_restores_old_values() {
+    let fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>Home']",
+        )
+        .with_value(MUTTER_SCHEMA, "dynamic-workspaces", "true");
+
+    let mut transaction = Transaction::new();
+    transaction
+        .stage(
+            &GSettings,
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .unwrap();
+    transaction
+        .stage(&GSettings, MUTTER_SCHEMA, "dynamic-workspaces", "false")
+        .unwrap();
+    transaction.apply(&GSettings).unwrap();
+    transaction.revert(&GSettings).unwrap();
+
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>Home']")
+    );
+    assert_eq!(
+        fake.value(MUTTER_SCHEMA, "dynamic-workspaces").as_deref(),
+        Some("true")
+    );
+}
+
+#[test]
+fn i3_preset_clears_app_shortcuts_first() {
+    let mut fake = FakeGSettings::new();
+    for id in 1..10 {
+        fake = fake.with_value(
+            SHELL_KEYBINDINGS_SCHEMA,
+            &format!("switch-to-application-{id}"),
+            &format!("['<Super>{id}']"),
+        );
+    }
+    for i in 1..=10 {
+        fake = fake
+            .with_value(
+                WM_KEYBINDINGS_SCHEMA,
+                &format!("switch-to-workspace-{i}"),
+                "@as []",
+            )
+            .with_value(
+                WM_KEYBINDINGS_SCHEMA,
+                &format!("move-to-workspace-{i}"),
+                "@as []",
+            );
+    }
+
+    let transaction = Preset::i3_style().stage(&GSettings).unwrap();
+    assert_eq!(transaction.changes.len(), 29);
+    transaction.apply(&GSettings).unwrap();
+
+    let schemas: Vec<String> = fake
+        .set_calls()
+        .into_iter()
+        .map(|call| call[1].clone())
+        .collect();
+    assert!(schemas[..9].iter().all(|s| s == SHELL_KEYBINDINGS_SCHEMA));
+    assert!(schemas[9..].iter().all(|s| s == WM_KEYBINDINGS_SCHEMA));
+
+    assert_eq!(
+        fake.value(SHELL_KEYBINDINGS_SCHEMA, "switch-to-application-1")
+            .as_deref(),
+        Some("@as []")
+    );
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-10")
+            .as_deref(),
+        Some("['<Super>0']")
+    );
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-4")
+            .as_deref(),
+        Some("['<Shift><Super>4']")
+    );
+}
+
+#[test]
+fn key_repeat_writes_only_changed_keys() {
+    let fake = FakeGSettings::new()
+        .with_value(KEYBOARD_SCHEMA, "repeat", "true")
+        .with_value(KEYBOARD_SCHEMA, "delay", "uint32 500")
+        .with_value(KEYBOARD_SCHEMA, "repeat-interval", "uint32 30");
+
+    let old = KeyRepeat::read(&GSettings).unwrap();
+    assert_eq!(
+        old,
+        KeyRepeat {
+            enabled: true,
+            delay: 500,
+            interval: 30
+        }
+    );
+    let new = KeyRepeat { delay: 250, ..old };
+    new.write_changes(&old, &GSettings).unwrap();
+
+    assert_eq!(fake.set_calls().len(), 1);
+    assert_eq!(
+        fake.value(KEYBOARD_SCHEMA, "delay").as_deref(),
+        Some("uint32 250")
+    );
+}
+
+#[test]
+fn set_confirmed_reports_writes_that_do_not_stick() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(KEYBOARD_SCHEMA, "delay", "uint32 500")
+        .readonly(WM_PREFERENCES_SCHEMA, "num-workspaces");
+
+    let err = GSettings
+        .set_confirmed(WM_PREFERENCES_SCHEMA, "num-workspaces", "6")
+        .unwrap_err();
+    assert!(err.to_string().contains("is still 4"), "{err}");
+    GSettings
+        .set_confirmed(KEYBOARD_SCHEMA, "delay", "uint32 250")
+        .unwrap();
+
+    // gsettings prints the type of unsigned numbers, whatever was written
+    assert!(gsettings::same_value("250", "uint32 250"));
+    assert!(!gsettings::same_value("'250'", "uint32 250"));
+}
+
+#[test]
+fn every_builtin_preset_binds_all_workspace_rows() {
+    for preset in Preset::builtin() {
+        assert_eq!(preset.bindings.len(), 20, "{}", preset.name);
+    }
+    let ctrl_alt = Preset::ctrl_alt();
+    assert!(ctrl_alt.bindings.contains(&(
+        "move-to-workspace-10".to_string(),
+        "['<Shift><Ctrl><Alt>0']".to_string()
+    )));
+    assert!(!ctrl_alt.clear_app_shortcuts);
+}
+
+#[test]
+fn template_expands_into_workspace_bindings() {
+    let template = Preset::from_template("switch=<Super>{n}; move=<Shift><Super>{n}").unwrap();
+    let i3 = Preset::i3_style();
+    assert_eq!(template.bindings, i3.bindings);
+    assert!(template.clear_app_shortcuts);
+
+    let some = Preset::from_template(" move = <Alt><Ctrl>{n} ; n=9..10").unwrap();
+    assert_eq!(
+        some.bindings,
+        [
+            ("move-to-workspace-9".into(), "['<Ctrl><Alt>9']".into()),
+            ("move-to-workspace-10".into(), "['<Ctrl><Alt>0']".into()),
+        ]
+    );
+    assert!(!some.clear_app_shortcuts);
+
+    for bad in [
+        "",
+        "n=1..4",
+        "switch=<Super>{n}; n=0..3",
+        "jump=<Super>{n}",
+        "switch=<Super",
+    ] {
+        assert!(Preset::from_template(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn snapshot_backs_up_untouched_keys_for_restoring() {
+    let keys = [
+        (
+            WM_PREFERENCES_SCHEMA.to_string(),
+            "num-workspaces".to_string(),
+        ),
+        (
+            WM_KEYBINDINGS_SCHEMA.to_string(),
+            "switch-to-workspace-1".to_string(),
+        ),
+    ];
+    let fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "6")
+        .with_default(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_default(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>Home']",
+        );
+
+    assert_eq!(backup::snapshot(&GSettings, &keys).unwrap(), 2);
+    // a second start doesn't replace what was there first
+    GSettings::set(WM_PREFERENCES_SCHEMA, "num-workspaces", "9").unwrap();
+    assert_eq!(backup::snapshot(&GSettings, &keys).unwrap(), 0);
+
+    let restore = backup::stage_restore(&GSettings).unwrap();
+    let values: Vec<&str> = restore
+        .changes
+        .iter()
+        .map(|c| c.new_value.as_str())
+        .collect();
+    assert_eq!(values, ["6"]);
+
+    let defaults = backup::stage_defaults(&GSettings, &keys).unwrap();
+    defaults.apply(&GSettings).unwrap();
+    assert_eq!(
+        fake.value(WM_PREFERENCES_SCHEMA, "num-workspaces")
+            .as_deref(),
+        Some("4")
+    );
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>Home']")
+    );
+}
+
+#[test]
+fn writes_are_refused_outside_gnome() {
+    use gnome_workspace_shortcuts_menu::{backend::ReadOnly, environment::not_gnome_reason};
+
+    assert_eq!(not_gnome_reason(Some("ubuntu:GNOME"), Some(false)), None);
+    assert_eq!(not_gnome_reason(None, Some(true)), None);
+    assert_eq!(
+        not_gnome_reason(Some("KDE"), None).as_deref(),
+        Some("the desktop is KDE, not GNOME")
+    );
+    assert_eq!(
+        not_gnome_reason(Some(""), Some(false)).as_deref(),
+        Some("no GNOME session was found")
+    );
+
+    let fake = FakeGSettings::new().with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4");
+    let backend = ReadOnly {
+        inner: Box::new(GSettings),
+        reason: "the desktop is sway, not GNOME".into(),
+    };
+    assert_eq!(
+        backend
+            .get(WM_PREFERENCES_SCHEMA, "num-workspaces")
+            .unwrap()
+            .trim(),
+        "4"
+    );
+    let err = backend
+        .set(WM_PREFERENCES_SCHEMA, "num-workspaces", "6")
+        .unwrap_err();
+    assert!(err.to_string().contains("sway"), "{err}");
+    assert!(fake.set_calls().is_empty());
+}
+
+#[test]
+fn preview_mode_keeps_writes_back() {
+    use gnome_workspace_shortcuts_menu::{
+        backend::{PendingWrites, Recording},
+        export::gsettings_command,
+    };
+
+    let fake = FakeGSettings::new().with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4");
+    let writes = PendingWrites::default();
+    let backend = Recording {
+        inner: Box::new(GSettings),
+        writes: writes.clone(),
+    };
+    backend
+        .set_confirmed(WM_PREFERENCES_SCHEMA, "num-workspaces", "6")
+        .unwrap();
+    backend
+        .set(WM_PREFERENCES_SCHEMA, "num-workspaces", "8")
+        .unwrap();
+    // reads see the kept back value, nothing reached gsettings
+    assert_eq!(
+        backend
+            .get(WM_PREFERENCES_SCHEMA, "num-workspaces")
+            .unwrap(),
+        "8"
+    );
+    assert!(fake.set_calls().is_empty());
+
+    let writes = writes.lock().unwrap();
+    assert_eq!(writes.len(), 2);
+    let latest = backend::latest_writes(&writes);
+    assert_eq!(latest.len(), 1);
+    assert_eq!(
+        gsettings_command(&latest[0].schema, &latest[0].key, &latest[0].value),
+        "gsettings set 'org.gnome.desktop.wm.preferences' 'num-workspaces' '8'"
+    );
+}
+
+#[test]
+fn remote_sessions_are_told_apart_from_local_ones() {
+    use gnome_workspace_shortcuts_menu::environment::remote_reason;
+
+    let vars = |set: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            set.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        }
+    };
+    assert_eq!(
+        remote_reason(vars(&[("WAYLAND_DISPLAY", "wayland-0")]), true),
+        None
+    );
+    assert_eq!(
+        remote_reason(
+            vars(&[
+                ("DISPLAY", ":0"),
+                ("SSH_CONNECTION", "10.0.0.2 5022 10.0.0.1 22")
+            ]),
+            true
+        )
+        .as_deref(),
+        Some("this runs over SSH")
+    );
+    assert_eq!(
+        remote_reason(vars(&[]), true).as_deref(),
+        Some("there is no graphical session")
+    );
+    assert_eq!(
+        remote_reason(vars(&[("DISPLAY", ":0")]), false).as_deref(),
+        Some("there is no session bus")
+    );
+}
+
+#[test]
+fn locked_keys_are_detected() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-2",
+            "['<Super>2']",
+        )
+        .locked(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2");
+    let keys: Vec<(String, String)> = ["switch-to-workspace-1", "switch-to-workspace-2"]
+        .iter()
+        .map(|key| (WM_KEYBINDINGS_SCHEMA.to_string(), key.to_string()))
+        .collect();
+
+    assert_eq!(backend::locked_keys(&GSettings, &keys), keys[1..]);
+    assert!(GSettings::is_writable(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1").unwrap());
+    assert!(GSettings::is_writable(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-9").is_err());
+}
+
+#[test]
+fn isolation_settings_are_only_offered_when_installed() {
+    use gnome_workspace_shortcuts_menu::{isolation, switcher_popup::PopupValue};
+
+    let _fake = FakeGSettings::new()
+        .with_value(
+            "org.gnome.shell.app-switcher",
+            "current-workspace-only",
+            "false",
+        )
+        .with_value(
+            "org.gnome.shell.extensions.dash-to-dock",
+            "isolate-workspaces",
+            "true",
+        );
+    let settings = isolation::available(&GSettings);
+    let found: Vec<(&str, PopupValue)> = settings.iter().map(|s| (s.key, s.value)).collect();
+    assert_eq!(
+        found,
+        vec![
+            ("current-workspace-only", PopupValue::Bool(false)),
+            ("isolate-workspaces", PopupValue::Bool(true)),
+        ]
+    );
+}
+
+#[test]
+fn isolation_toggles_are_staged() {
+    use gnome_workspace_shortcuts_menu::{isolation, switcher_popup::PopupValue};
+
+    let fake = FakeGSettings::new().with_value(
+        "org.gnome.shell.window-switcher",
+        "current-workspace-only",
+        "false",
+    );
+    let mut setting = isolation::available(&GSettings).remove(0);
+    setting.value = PopupValue::Bool(true);
+    let transaction = setting.stage(&GSettings).unwrap();
+    assert!(fake.set_calls().is_empty());
+    let change = &transaction.changes[0];
+    assert_eq!(
+        (change.old_value.as_str(), change.new_value.as_str()),
+        ("false", "true")
+    );
+}
+
+#[test]
+fn enabling_an_extension_is_staged() {
+    use gnome_workspace_shortcuts_menu::extensions;
+
+    let fake = FakeGSettings::new().with_value(
+        "org.gnome.shell",
+        "enabled-extensions",
+        "['a@example.com']",
+    );
+    let transaction = extensions::stage_enabled(&GSettings, "b@example.com", true).unwrap();
+    assert!(fake.set_calls().is_empty());
+    assert_eq!(
+        transaction.changes[0].new_value,
+        "['a@example.com', 'b@example.com']"
+    );
+}
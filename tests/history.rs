@@ -0,0 +1,30 @@
+use gnome_workspace_shortcuts_menu::history::{self, Entry, SyncState};
+
+#[test]
+fn timestamps_are_utc_dates() {
+    assert_eq!(history::format_timestamp(0), "1970-01-01 00:00");
+    assert_eq!(history::format_timestamp(951_827_696), "2000-02-29 12:34");
+}
+
+#[test]
+fn sync_state_ignores_formatting() {
+    let entry = Entry {
+        schema: "org.gnome.desktop.wm.keybindings".into(),
+        key: "switch-to-workspace-1".into(),
+        value: "['<Super>1']".into(),
+        at: 0,
+    };
+
+    assert_eq!(
+        history::sync_state(None, "['<Super>1']"),
+        SyncState::NeverApplied
+    );
+    assert_eq!(
+        history::sync_state(Some(&entry), "[\"<Super>1\"]\n"),
+        SyncState::Synced
+    );
+    assert_eq!(
+        history::sync_state(Some(&entry), "@as []"),
+        SyncState::Drifted
+    );
+}
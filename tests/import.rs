@@ -0,0 +1,201 @@
+mod common;
+
+use std::path::Path;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    export,
+    gsettings::{
+        GSettings, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    import,
+    keyboard::KEYBOARD_SCHEMA,
+    migration,
+    profile::Profile,
+};
+
+fn profile() -> Profile {
+    let mut profile = Profile::default();
+    profile.set(WM_PREFERENCES_SCHEMA, "num-workspaces", "4");
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1', '<Primary><Alt>Home']",
+    );
+    profile
+}
+
+fn import(data: &str) -> import::Imported {
+    import::import(Path::new("settings"), data.as_bytes()).unwrap()
+}
+
+#[test]
+fn exported_files_import_as_the_same_profile() {
+    for (id, format) in [("script", "gsettings script"), ("dconf", "dconf dump")] {
+        let exported = export::find(id).unwrap().export(&profile()).unwrap();
+        let imported = import(&exported);
+        assert_eq!(imported.format, format);
+        assert_eq!(
+            imported
+                .profile
+                .get(WM_PREFERENCES_SCHEMA, "num-workspaces"),
+            Some("4")
+        );
+        assert_eq!(
+            imported
+                .profile
+                .get(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1"),
+            Some("['<Super>1', '<Primary><Alt>Home']")
+        );
+    }
+    assert_eq!(import(&profile().to_json().unwrap()).profile, profile());
+}
+
+#[test]
+fn toml_values_are_converted_to_gvariant_text() {
+    let imported = import(
+        r#"
+        [org.gnome.desktop.wm.preferences]
+        num-workspaces = 6
+
+        ["org.gnome.desktop.wm.keybindings"]
+        switch-to-workspace-1 = ["<Super>1"]
+        switch-to-workspace-2 = []
+        move-to-workspace-1 = "['<Shift><Super>1']"
+        "#,
+    );
+
+    assert_eq!(imported.format, "TOML");
+    let get = |schema, key| imported.profile.get(schema, key);
+    assert_eq!(get(WM_PREFERENCES_SCHEMA, "num-workspaces"), Some("6"));
+    assert_eq!(
+        get(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1"),
+        Some("['<Super>1']")
+    );
+    assert_eq!(
+        get(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2"),
+        Some("@as []")
+    );
+    assert_eq!(
+        get(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-1"),
+        Some("['<Shift><Super>1']")
+    );
+}
+
+#[test]
+fn unmanaged_and_unknown_keys_are_left_out() {
+    let fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1", "@as []");
+    let imported = import(
+        "[org/gnome/desktop/wm/preferences]\nnum-workspaces=4\n\n\
+         [org/gnome/desktop/wm/keybindings]\nswitch-to-workspace-1=['<Super>1']\nshow-desktop=['<Super>d']\n\n\
+         [org/gnome/gedit/preferences/editor]\ntabs-size=uint32 4\n",
+    );
+
+    assert_eq!(imported.skipped, 1);
+    let staged = imported.stage(&GSettings);
+
+    // show-desktop isn't in the fake, the unchanged workspace count isn't staged
+    let keys: Vec<&str> = staged
+        .transaction
+        .changes
+        .iter()
+        .map(|c| c.key.as_str())
+        .collect();
+    assert_eq!(keys, ["switch-to-workspace-1"]);
+    assert_eq!(staged.failed.len(), 1);
+    assert!(
+        staged.failed[0].starts_with("show-desktop in org.gnome.desktop.wm.keybindings"),
+        "{:?}",
+        staged.failed
+    );
+    assert!(fake.set_calls().is_empty());
+}
+
+#[test]
+fn unknown_formats_name_the_supported_ones() {
+    let err = import::import(Path::new("notes.txt"), b"just some notes = [")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("gsettings script"), "{err}");
+}
+
+#[test]
+fn single_directory_dumps_are_matched_by_key() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1", "@as []")
+        .with_value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2", "@as []");
+    // dconf dump /org/gnome/desktop/wm/keybindings/
+    let imported = import(
+        "[/]\nswitch-to-workspace-1=['<Super>1']\nswitch-to-workspace-2=['<Super>2']\nunknown-key=true\n",
+    );
+
+    assert_eq!(imported.format, "dconf dump");
+    assert_eq!(imported.skipped, 1);
+    let transaction = imported.stage(&GSettings).transaction;
+    assert_eq!(transaction.changes.len(), 2);
+
+    let partial = transaction.only(&[false, true]);
+    let keys: Vec<&str> = partial.changes.iter().map(|c| c.key.as_str()).collect();
+    assert_eq!(keys, ["switch-to-workspace-2"]);
+}
+
+#[test]
+fn migration_moves_renamed_keys_and_reports_missing_ones() {
+    let fake = FakeGSettings::new()
+        .with_value(KEYBOARD_SCHEMA, "delay", "uint32 500")
+        .with_value(SHELL_KEYBINDINGS_SCHEMA, "show-screenshot-ui", "['Print']")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>Home']",
+        );
+    let mut old = Profile::default();
+    old.set(
+        "org.gnome.settings-daemon.peripherals.keyboard",
+        "delay",
+        "uint32 250",
+    );
+    old.set(
+        "org.gnome.settings-daemon.plugins.media-keys",
+        "area-screenshot",
+        "'<Shift>Print'",
+    );
+    old.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1']",
+    );
+    old.set(WM_KEYBINDINGS_SCHEMA, "show-desktop", "['<Super>d']");
+    old.set(
+        "org.gnome.gedit.preferences.editor",
+        "tabs-size",
+        "uint32 4",
+    );
+
+    let plan = migration::plan(&old, &GSettings);
+    assert_eq!(plan.skipped, 1);
+    assert_eq!(
+        plan.unmappable,
+        [format!("{WM_KEYBINDINGS_SCHEMA} show-desktop")]
+    );
+    assert_eq!(plan.renamed.len(), 2);
+    assert_eq!(plan.renamed[0].1, format!("{KEYBOARD_SCHEMA} delay"));
+
+    plan.transaction.apply(&GSettings).unwrap();
+    assert_eq!(
+        fake.value(KEYBOARD_SCHEMA, "delay").as_deref(),
+        Some("uint32 250")
+    );
+    assert_eq!(
+        fake.value(SHELL_KEYBINDINGS_SCHEMA, "show-screenshot-ui")
+            .as_deref(),
+        Some("['<Shift>Print']")
+    );
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>1']")
+    );
+}
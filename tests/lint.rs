@@ -0,0 +1,64 @@
+use gnome_workspace_shortcuts_menu::{
+    doctor::Fix, gsettings::WM_KEYBINDINGS_SCHEMA, lint, profile::Profile,
+};
+
+fn profile(bindings: &[(&str, &str)]) -> Profile {
+    let mut profile = Profile::default();
+    for (key, value) in bindings {
+        profile.set(WM_KEYBINDINGS_SCHEMA, key, value);
+    }
+    profile
+}
+
+#[test]
+fn finds_odd_modifier_and_missing_move() {
+    let findings = lint::run(&profile(&[
+        ("switch-to-workspace-1", "['<Super>1']"),
+        ("switch-to-workspace-2", "['<Super>2']"),
+        ("switch-to-workspace-3", "['<Primary><Alt>3']"),
+        ("move-to-workspace-1", "['<Shift><Super>1']"),
+        ("move-to-workspace-2", "['<Shift><Super>2']"),
+        ("move-to-workspace-3", "['<Shift><Super>3']"),
+    ]));
+
+    let details: Vec<&str> = findings.iter().map(|c| c.detail.as_str()).collect();
+    assert_eq!(
+        details,
+        vec!["switch-to-workspace-3 uses <Ctrl><Alt>3, most others use <Super>",]
+    );
+    assert_eq!(
+        findings[0].fix,
+        Some(Fix::Set {
+            schema: WM_KEYBINDINGS_SCHEMA.into(),
+            key: "switch-to-workspace-3".into(),
+            value: "['<Super>3']".into(),
+        })
+    );
+
+    let findings = lint::run(&profile(&[
+        ("switch-to-workspace-1", "['<Super>1']"),
+        ("move-to-workspace-1", "@as []"),
+    ]));
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].fix,
+        Some(Fix::Set {
+            schema: WM_KEYBINDINGS_SCHEMA.into(),
+            key: "move-to-workspace-1".into(),
+            value: "['<Shift><Super>1']".into(),
+        })
+    );
+}
+
+#[test]
+fn reports_gnome_default_taken_by_workspace_binding() {
+    let findings = lint::run(&profile(&[("switch-to-workspace-1", "['<Super>a']")]));
+
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].name, "shadows default");
+    assert!(
+        findings[0].detail.contains("toggle-application-view"),
+        "{}",
+        findings[0].detail
+    );
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    gsettings::{
+        GSettings, SHELL_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    macros::{Condition, Macro, Operation, Step},
+};
+
+#[test]
+fn replays_in_order_and_skips_unmet_conditions() {
+    let fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1", "@as []")
+        .with_value(
+            SHELL_KEYBINDINGS_SCHEMA,
+            "switch-to-application-1",
+            "['<Super>1']",
+        );
+
+    let mut m = Macro::new("setup");
+    m.record(Operation::Set {
+        schema: WM_KEYBINDINGS_SCHEMA.into(),
+        key: "switch-to-workspace-1".into(),
+        value: "['<Super>1']".into(),
+    });
+    m.record(Operation::SetWorkspaceCount { count: 6 });
+    // sees the count written by the step before
+    m.steps.push(Step {
+        operation: Operation::Unbind {
+            schema: SHELL_KEYBINDINGS_SCHEMA.into(),
+            key: "switch-to-application-1".into(),
+        },
+        when: Some(Condition::Equals {
+            schema: WM_PREFERENCES_SCHEMA.into(),
+            key: "num-workspaces".into(),
+            value: "4".into(),
+        }),
+    });
+
+    let replay = m.replay(&GSettings).unwrap();
+
+    assert_eq!(
+        replay.ran,
+        [
+            "set switch-to-workspace-1 to ['<Super>1']",
+            "set 6 workspaces"
+        ]
+    );
+    assert_eq!(replay.skipped, ["unbind switch-to-application-1"]);
+    let written: Vec<String> = fake.set_calls().iter().map(|c| c[2].clone()).collect();
+    assert_eq!(written, ["switch-to-workspace-1", "num-workspaces"]);
+    assert_eq!(
+        fake.value(SHELL_KEYBINDINGS_SCHEMA, "switch-to-application-1")
+            .as_deref(),
+        Some("['<Super>1']")
+    );
+}
+
+#[test]
+fn saved_macros_are_found_by_name() {
+    let _fake = FakeGSettings::new();
+    let mut m = Macro::new("clear apps");
+    m.record(Operation::ClearAppShortcuts);
+
+    m.save().unwrap();
+
+    assert_eq!(Macro::list(), ["clear apps"]);
+    assert_eq!(Macro::find("clear apps").unwrap(), m);
+    assert!(Macro::find("missing").is_err());
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use gnome_workspace_shortcuts_menu::{
+    accelerator::Modifiers,
+    conflicts::ConflictIndex,
+    gsettings::WM_KEYBINDINGS_SCHEMA,
+    keysym::Keysyms,
+    model::{parse_number_of_workspaces, Model, RowEvent, MAX_WORKSPACES},
+};
+
+#[test]
+fn workspace_count_is_checked() {
+    assert_eq!(parse_number_of_workspaces(" 4 "), Ok(4));
+    assert_eq!(
+        parse_number_of_workspaces("four").unwrap_err(),
+        "'four' is not a number"
+    );
+    assert!(parse_number_of_workspaces("0").is_err());
+    assert!(parse_number_of_workspaces(&(MAX_WORKSPACES + 1).to_string()).is_err());
+}
+
+// row 0 is switch-to-workspace-1, row 10 move-to-workspace-1
+#[test]
+fn workspace_rows_come_first() {
+    let model = Model::new();
+    assert_eq!(model.rows[&0].gsettings_key, "switch-to-workspace-1");
+    assert_eq!(model.rows[&10].gsettings_key, "move-to-workspace-1");
+    assert!(model
+        .rows
+        .values()
+        .all(|row| row.gsettings_value.is_empty()));
+}
+
+#[test]
+fn rows_past_the_workspace_count_are_flagged() {
+    let keysyms = Keysyms::default();
+    let mut model = Model::new();
+    model
+        .rows
+        .get_mut(&5)
+        .unwrap()
+        .load("['<Super>6']".into(), &keysyms);
+    model.workspace_count = Some(4);
+    assert_eq!(model.missing_workspace(5), Some((6, 4)));
+    assert_eq!(model.missing_workspace(6), None);
+
+    model.dynamic_workspaces = true;
+    assert_eq!(model.missing_workspace(5), None);
+}
+
+#[test]
+fn super_digits_of_app_shortcuts_shadow_workspace_rows() {
+    let keysyms = Keysyms::default();
+    let mut model = Model::new();
+    model
+        .rows
+        .get_mut(&1)
+        .unwrap()
+        .load("['<Super>2']".into(), &keysyms);
+    model
+        .rows
+        .get_mut(&2)
+        .unwrap()
+        .load("['<Ctrl>3']".into(), &keysyms);
+    model.app_keybindings.insert(2, "['<Super>2']".into());
+    model.app_keybindings.insert(3, "['<Super>3']".into());
+    assert_eq!(model.conflicting_app_shortcut(1), Some(2));
+    assert_eq!(model.conflicting_app_shortcut(2), None);
+}
+
+#[test]
+fn edits_without_a_key_are_invalid() {
+    let keysyms = Keysyms::default();
+    let mut model = Model::new();
+    let row = model.rows.get_mut(&0).unwrap();
+    row.load("['<Super>1']".into(), &keysyms);
+    assert!(model.invalid_rows().is_empty());
+
+    let row = model.rows.get_mut(&0).unwrap();
+    row.assign(Modifiers::default(), "", &keysyms);
+    assert_eq!(
+        model.invalid_rows(),
+        ["Switch to workspace 1 has no key, use Clear to unbind it"]
+    );
+}
+
+#[test]
+fn overwrite_keeps_entries_the_parser_rejects() {
+    let keysyms = Keysyms::default();
+    let mut model = Model::new();
+    let row = model.rows.get_mut(&0).unwrap();
+    row.load("['<Super>1', '<Hyper>1']".into(), &keysyms);
+    assert_eq!(row.keybinding, "1");
+    assert_eq!(row.foreign, ["<Hyper>1"]);
+    assert!(!row.is_modified());
+
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    row.assign(shift, "1", &keysyms);
+    let write = row.write_for(RowEvent::Overwrite).unwrap();
+    assert_eq!(write.value.as_deref(), Some("['<Shift>1', '<Hyper>1']"));
+
+    // clearing the edited binding leaves the foreign one to write
+    row.assign(Modifiers::default(), "", &keysyms);
+    assert!(!row.is_incomplete());
+    assert_eq!(row.converted_keybinding, "['<Hyper>1']");
+    assert!(model.invalid_rows().is_empty());
+}
+
+#[test]
+fn conflicts_are_recomputed_once_invalidated() {
+    let keysyms = Keysyms::default();
+    let mut model = Model::new();
+    let mut index = ConflictIndex::default();
+    index.update(WM_KEYBINDINGS_SCHEMA, "panel-run-dialog", "['<Super>1']");
+    model.set_conflicts(index);
+    model
+        .rows
+        .get_mut(&0)
+        .unwrap()
+        .load("['<Super>1']".into(), &keysyms);
+    model.annotate();
+    assert_eq!(
+        model.rows[&0].conflict.as_deref(),
+        Some("Same shortcut as panel-run-dialog in org.gnome.desktop.wm.keybindings")
+    );
+    assert!(!model.is_stale());
+
+    let super_key = Modifiers {
+        super_key: true,
+        ..Default::default()
+    };
+    model
+        .rows
+        .get_mut(&0)
+        .unwrap()
+        .assign(super_key, "2", &keysyms);
+    model.annotate();
+    assert!(model.rows[&0].conflict.is_some());
+
+    model.invalidate();
+    model.annotate();
+    assert!(model.rows[&0].conflict.is_none());
+
+    // another key taking the edited binding
+    model.update_conflict(
+        WM_KEYBINDINGS_SCHEMA,
+        "activate-window-menu",
+        "['<Super>2']",
+    );
+    model.annotate();
+    assert!(model.rows[&0].conflict.is_some());
+}
+
+#[test]
+fn rows_of_unknown_keys_are_dropped() {
+    let mut model = Model::new();
+    let schemas = model.row_schemas();
+    assert!(schemas.contains(WM_KEYBINDINGS_SCHEMA));
+    let total = model.rows.len();
+
+    // other schemas couldn't be listed, their rows stay
+    let known = BTreeMap::from([(
+        WM_KEYBINDINGS_SCHEMA.to_string(),
+        vec!["switch-to-workspace-1".to_string()],
+    )]);
+    model.drop_absent_rows(&known);
+
+    let wm_rows: Vec<&str> = model
+        .rows
+        .values()
+        .filter(|row| row.schema == WM_KEYBINDINGS_SCHEMA)
+        .map(|row| row.gsettings_key.as_str())
+        .collect();
+    assert_eq!(wm_rows, ["switch-to-workspace-1"]);
+    assert!(model.rows.len() > 1 && model.rows.len() < total);
+}
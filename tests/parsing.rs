@@ -0,0 +1,206 @@
+//! Real-world `gsettings get`, `gsettings monitor`, `dconf dump` and `localectl`
+//! output fed through the parsers, and the text formats generated from parsed
+//! values.
+
+use gnome_workspace_shortcuts_menu::{
+    accelerator::Accelerator,
+    dconf,
+    gsettings::WM_KEYBINDINGS_SCHEMA,
+    help::{self, Block},
+    key_location::{self, LayoutVariant},
+    keyboard_map,
+    keysym::Keysyms,
+    profile::Profile,
+    switcher_popup, watch,
+};
+use insta::assert_debug_snapshot;
+
+#[test]
+fn single_binding() {
+    assert_debug_snapshot!(Accelerator::list_from_gsettings("['<Super>1']\n"));
+}
+
+#[test]
+fn empty_array() {
+    assert_debug_snapshot!(Accelerator::list_from_gsettings("@as []\n"));
+}
+
+#[test]
+fn legacy_empty_string() {
+    assert_debug_snapshot!(Accelerator::list_from_gsettings("['']\n"));
+}
+
+#[test]
+fn multiple_bindings() {
+    assert_debug_snapshot!(Accelerator::list_from_gsettings(
+        "['<Primary><Alt>Right', '<Super>Page_Down']\n"
+    ));
+}
+
+#[test]
+fn default_move_to_workspace_up() {
+    assert_debug_snapshot!(Accelerator::list_from_gsettings(
+        "['<Super><Shift>Page_Up', '<Super><Shift><Alt>Left', '<Control><Shift><Alt>Up']\n"
+    ));
+}
+
+#[test]
+fn double_quoted() {
+    assert_debug_snapshot!(Accelerator::list_from_gsettings("[\"<Super>d\"]"));
+}
+
+#[test]
+fn first_binding_of_array() {
+    assert_debug_snapshot!(Accelerator::from_gsettings(
+        "['<Primary><Alt>Right', '<Super>Page_Down']\n"
+    ));
+}
+
+#[test]
+fn not_an_array() {
+    assert_debug_snapshot!(
+        Accelerator::list_from_gsettings("'<Super>1'").map_err(|e| e.to_string())
+    );
+}
+
+#[test]
+fn unknown_modifier() {
+    assert_debug_snapshot!(
+        Accelerator::list_from_gsettings("['<Hyper>1']").map_err(|e| e.to_string())
+    );
+}
+
+#[test]
+fn dconf_dump() {
+    assert_debug_snapshot!(dconf::parse_dump(
+        "[/]\nswitch-to-workspace-1=['<Super>1']\nmove-to-workspace-1=['<Shift><Super>1']\n\n[custom]\nfoo=true\n"
+    )
+    .map_err(|e| e.to_string()));
+}
+
+#[test]
+fn typed_popup_values() {
+    assert_debug_snapshot!(["true", "uint32 600", "-5", "'abc'"]
+        .map(|v| switcher_popup::PopupValue::parse(v).map_err(|e| e.to_string())));
+}
+
+#[test]
+fn keyboard_map_lists_bindings_per_key() {
+    let mut profile = Profile::default();
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1']",
+    );
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "move-to-workspace-1",
+        "['<Shift><Super>1']",
+    );
+    profile.set(WM_KEYBINDINGS_SCHEMA, "close", "['<Alt>F4']");
+
+    let html = keyboard_map::html("<Team> scheme", &profile);
+    assert!(
+        html.contains("<title>&lt;Team&gt; scheme</title>"),
+        "{html}"
+    );
+    assert!(html.contains("&lt;Super&gt; → switch to workspace 1<br>&lt;Shift&gt;&lt;Super&gt; → move to workspace 1"), "{html}");
+    assert!(
+        html.contains("<div class=\"key bound\" data-key=\"f4\">F4"),
+        "{html}"
+    );
+    assert!(
+        html.contains("<div class=\"key\" data-key=\"q\">q</div>"),
+        "{html}"
+    );
+}
+
+#[test]
+fn monitor_lines() {
+    assert_eq!(
+        watch::parse_monitor_line("switch-to-workspace-1: ['<Super>1', '<Alt>F1']"),
+        Some((
+            "switch-to-workspace-1".to_string(),
+            "['<Super>1', '<Alt>F1']".to_string()
+        ))
+    );
+    assert_eq!(
+        watch::parse_monitor_line("num-workspaces: 4\n").unwrap().1,
+        "4"
+    );
+    assert_eq!(watch::parse_monitor_line("(gsettings:1234): warning"), None);
+}
+
+#[test]
+fn keyboard_layouts() {
+    let localectl = "   System Locale: LANG=de_DE.UTF-8\n       VC Keymap: de\n      X11 Layout: de\n       X11 Model: pc105\n";
+    let model = key_location::parse_localectl_model(localectl).unwrap();
+    assert_eq!(LayoutVariant::from_model(&model), Some(LayoutVariant::Iso));
+    assert_eq!(
+        key_location::parse_localectl_model("X11 Layout: us\n"),
+        None
+    );
+
+    let sources = "[('xkb', 'us+intl'), ('ibus', 'anthy')]\n";
+    let layout = key_location::first_xkb_layout(sources).unwrap();
+    assert_eq!(LayoutVariant::from_layout(&layout), LayoutVariant::Ansi);
+    assert_eq!(key_location::first_xkb_layout("@a(ss) []"), None);
+
+    assert_eq!(
+        key_location::notes("['<Super>less', '<Super>1']", LayoutVariant::Iso).len(),
+        1
+    );
+    assert!(key_location::notes("['<Super>less']", LayoutVariant::Ansi)[0].contains("Shift"));
+}
+
+#[cfg(feature = "portal")]
+#[test]
+fn file_chooser_uris() {
+    use gnome_workspace_shortcuts_menu::portal::path_of_uri;
+    use std::path::PathBuf;
+
+    assert_eq!(
+        path_of_uri("file:///home/me/My%20Shortcuts.sh"),
+        Some(PathBuf::from("/home/me/My Shortcuts.sh"))
+    );
+    assert_eq!(
+        path_of_uri("file://localhost/tmp/a.sh"),
+        Some(PathBuf::from("/tmp/a.sh"))
+    );
+    assert_eq!(path_of_uri("https://example.com/a.sh"), None);
+    assert_eq!(path_of_uri("file:///tmp/%zz"), None);
+}
+
+#[test]
+fn help_explains_values_and_every_example_parses() {
+    let keysyms = Keysyms::load();
+    assert_eq!(
+        help::explain("['<Super>1', '<Primary><Shift>bracketleft']", &keysyms),
+        [
+            "2 bindings, any of them triggers this action.",
+            "<Super>1: hold Super, press 1",
+            "<Shift><Ctrl>bracketleft: hold Shift+Ctrl, press bracketleft (the [ key)",
+        ]
+    );
+    assert_eq!(
+        help::explain("@as []", &keysyms),
+        ["Unbound, no key triggers this action."]
+    );
+
+    let examples: Vec<String> = help::syntax()
+        .into_iter()
+        .filter_map(|b| match b {
+            Block::Example(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    assert!(examples.len() > 5);
+    for example in examples {
+        let parsed = if example.starts_with(['[', '@']) {
+            Accelerator::list_from_gsettings(&example).map(drop)
+        } else {
+            Accelerator::parse(&example).map(drop)
+        };
+        assert!(parsed.is_ok(), "{example}");
+    }
+}
@@ -0,0 +1,19 @@
+use gnome_workspace_shortcuts_menu::preferences::{Confirm, ConfirmPolicy, Preferences};
+
+#[test]
+fn confirmation_policy_defaults_to_previewing_bulk_writes() {
+    // preferences saved before the policy existed
+    let preferences: Preferences = serde_json::from_str(r#"{"check_for_updates":true}"#).unwrap();
+    let policy = preferences.confirm;
+
+    assert_eq!(policy, ConfirmPolicy::default());
+    assert!(!policy.requires(&[Confirm::Overwrite]));
+    assert!(!policy.requires(&[Confirm::Clear]));
+    assert!(policy.requires(&[Confirm::BulkApply, Confirm::Clear]));
+
+    let none = ConfirmPolicy {
+        bulk_apply: false,
+        ..policy
+    };
+    assert!(!none.requires(&[Confirm::BulkApply, Confirm::Clear, Confirm::Reset]));
+}
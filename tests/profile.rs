@@ -0,0 +1,282 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    doctor, drift,
+    gsettings::{
+        GSettings, MUTTER_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA,
+    },
+    hooks::Hooks,
+    profile::{self, Profile},
+    share,
+    transaction::Transaction,
+};
+
+#[test]
+fn diff_covers_keys_of_both_sides() {
+    let mut left = Profile::default();
+    left.set("s", "a", "1");
+    left.set("s", "b", "2");
+    let mut right = Profile::default();
+    right.set("s", "b", "3");
+    right.set("s", "c", "4");
+
+    let diffs: Vec<(String, Option<String>, Option<String>)> = profile::diff(&left, &right)
+        .into_iter()
+        .map(|d| (d.key, d.left, d.right))
+        .collect();
+    assert_eq!(
+        diffs,
+        vec![
+            ("a".into(), Some("1".into()), None),
+            ("b".into(), Some("2".into()), Some("3".into())),
+            ("c".into(), None, Some("4".into())),
+        ]
+    );
+}
+
+#[test]
+fn drift_lists_changed_and_unreadable_keys() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-2", "@as []");
+    let mut profile = Profile::default();
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-1",
+        "['<Super>1']",
+    );
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-2",
+        "['<Super>2']",
+    );
+    profile.set(
+        WM_KEYBINDINGS_SCHEMA,
+        "switch-to-workspace-3",
+        "['<Super>3']",
+    );
+
+    let drifts: Vec<(String, Option<String>)> = drift::check(&profile, &GSettings)
+        .into_iter()
+        .map(|d| (d.key, d.actual))
+        .collect();
+    assert_eq!(
+        drifts,
+        vec![
+            ("switch-to-workspace-2".into(), Some("@as []".into())),
+            ("switch-to-workspace-3".into(), None),
+        ]
+    );
+}
+
+#[test]
+fn share_code_round_trips_and_tolerates_wrapping() {
+    let mut profile = Profile::default();
+    for i in 1..=10 {
+        profile.set(
+            WM_KEYBINDINGS_SCHEMA,
+            &format!("switch-to-workspace-{i}"),
+            &format!("['<Super>{}']", i % 10),
+        );
+    }
+
+    let code = share::encode(&profile).unwrap();
+    assert!(code.starts_with("gwsm1:"), "{code}");
+    let (head, tail) = code.split_at(20);
+    assert_eq!(
+        share::decode(&format!("{head}\n  {tail}")).unwrap(),
+        profile
+    );
+    assert!(share::decode("gwsm1:AAAA").is_err());
+}
+
+#[test]
+fn named_profiles_can_be_renamed_and_deleted() {
+    let _fake = FakeGSettings::new();
+    let mut laptop = Profile::default();
+    laptop.set(WM_PREFERENCES_SCHEMA, "num-workspaces", "4");
+
+    laptop.save_named("laptop").unwrap();
+    Profile::default().save_named("desktop").unwrap();
+    assert_eq!(profile::named_profiles(), ["desktop", "laptop"]);
+
+    assert!(profile::rename_named("laptop", "desktop").is_err());
+    profile::rename_named("laptop", "travel").unwrap();
+    assert_eq!(Profile::load_named("travel").unwrap(), laptop);
+
+    profile::delete_named("desktop").unwrap();
+    assert_eq!(profile::named_profiles(), ["travel"]);
+    assert!(profile::named_path("../escape").is_err());
+}
+
+#[test]
+fn staged_changes_show_conflicts_before_writing() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-2",
+            "['<Super>2']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-3", "@as []");
+    let mut transaction = Transaction::new();
+    transaction
+        .stage(
+            &GSettings,
+            WM_KEYBINDINGS_SCHEMA,
+            "move-to-workspace-3",
+            "['<Super>2']",
+        )
+        .unwrap();
+
+    let after = Profile::capture(&GSettings).with_changes(&transaction);
+
+    let conflicts: Vec<String> = doctor::check_profile(&after)
+        .into_iter()
+        .filter(|c| c.name == "conflict")
+        .map(|c| c.detail)
+        .collect();
+    assert_eq!(
+        conflicts,
+        ["<Super>2 is bound by switch-to-workspace-2, move-to-workspace-3"]
+    );
+}
+
+#[test]
+fn hooks_run_around_applying_a_profile() {
+    let fake = FakeGSettings::new().with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4");
+    let out = tempfile::tempdir().unwrap();
+    let log = out.path().join("hooks");
+    let mut profile = Profile::default();
+    profile.set(WM_PREFERENCES_SCHEMA, "num-workspaces", "6");
+    profile.hooks = Hooks {
+        pre_apply: Some("exit 1".into()),
+        post_apply: Some(format!("echo post >> {}", log.display())),
+    };
+
+    // a failing pre_apply hook writes nothing
+    assert!(profile
+        .stage(&GSettings)
+        .unwrap()
+        .apply(&GSettings)
+        .is_err());
+    assert_eq!(
+        fake.value(WM_PREFERENCES_SCHEMA, "num-workspaces")
+            .as_deref(),
+        Some("4")
+    );
+
+    profile.hooks.pre_apply = Some(format!("echo pre >> {}", log.display()));
+    profile
+        .stage(&GSettings)
+        .unwrap()
+        .apply(&GSettings)
+        .unwrap();
+    assert_eq!(
+        fake.value(WM_PREFERENCES_SCHEMA, "num-workspaces")
+            .as_deref(),
+        Some("6")
+    );
+    assert_eq!(std::fs::read_to_string(&log).unwrap(), "pre\npost\n");
+
+    // share codes don't carry hooks
+    let code = share::encode(&profile).unwrap();
+    assert!(share::decode(&code).unwrap().hooks.is_empty());
+}
+
+#[test]
+fn capture_includes_relative_navigation() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-left",
+            "['<Super>Page_Up']",
+        )
+        .with_value(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-down", "@as []");
+    let profile = Profile::capture(&GSettings);
+    assert_eq!(
+        profile.get(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-left"),
+        Some("['<Super>Page_Up']")
+    );
+    assert_eq!(
+        profile.get(WM_KEYBINDINGS_SCHEMA, "move-to-workspace-down"),
+        Some("@as []")
+    );
+}
+
+#[test]
+fn minimal_profiles_reset_the_keys_they_leave_out() {
+    let fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "6")
+        .with_default(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        )
+        .with_default(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>Home']",
+        );
+    let minimal = Profile::capture(&GSettings).minimal(&GSettings);
+    assert!(minimal.minimal);
+    assert_eq!(
+        minimal.get(WM_PREFERENCES_SCHEMA, "num-workspaces"),
+        Some("6")
+    );
+    assert_eq!(
+        minimal.get(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1"),
+        Some("['<Super>1']")
+    );
+
+    // a minimal profile without the switch key puts it back to the default
+    let mut profile = Profile::from_json(&minimal.to_json().unwrap()).unwrap();
+    profile.values.retain(|v| v.key == "num-workspaces");
+    let transaction = profile.stage(&GSettings).unwrap();
+    assert_eq!(transaction.changes.len(), 1);
+    transaction.apply(&GSettings).unwrap();
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>Home']")
+    );
+
+    // full profiles don't touch what they don't mention
+    profile.minimal = false;
+    profile.values.clear();
+    assert!(profile.stage(&GSettings).unwrap().changes.is_empty());
+}
+
+#[test]
+fn capture_includes_window_placement() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_KEYBINDINGS_SCHEMA, "toggle-maximized", "['<Alt>F10']")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "move-to-monitor-left",
+            "['<Shift><Super>Left']",
+        )
+        .with_value(
+            MUTTER_KEYBINDINGS_SCHEMA,
+            "toggle-tiled-left",
+            "['<Super>Left']",
+        );
+    let profile = Profile::capture(&GSettings);
+    assert_eq!(
+        profile.get(WM_KEYBINDINGS_SCHEMA, "toggle-maximized"),
+        Some("['<Alt>F10']")
+    );
+    assert_eq!(
+        profile.get(MUTTER_KEYBINDINGS_SCHEMA, "toggle-tiled-left"),
+        Some("['<Super>Left']")
+    );
+    assert_eq!(
+        profile.get(WM_KEYBINDINGS_SCHEMA, "move-to-monitor-left"),
+        Some("['<Shift><Super>Left']")
+    );
+}
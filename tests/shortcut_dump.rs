@@ -0,0 +1,51 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    custom_shortcuts::{schema_at, MEDIA_KEYS_SCHEMA},
+    gsettings::{GSettings, MUTTER_KEYBINDINGS_SCHEMA, WM_KEYBINDINGS_SCHEMA},
+    shortcut_dump,
+};
+
+const TERMINAL: &str = "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/";
+
+#[test]
+fn dumps_every_keybinding_key_with_its_description() {
+    let _fake = FakeGSettings::new()
+        .with_value(WM_KEYBINDINGS_SCHEMA, "minimize", "['<Super>h']")
+        .with_description(WM_KEYBINDINGS_SCHEMA, "minimize", "Hide window\n")
+        .with_value(WM_KEYBINDINGS_SCHEMA, "begin-move", "@as []")
+        .with_value(
+            MUTTER_KEYBINDINGS_SCHEMA,
+            "toggle-tiled-left",
+            "['<Super>Left']",
+        )
+        .with_value(MEDIA_KEYS_SCHEMA, "volume-step", "6")
+        .with_value(
+            MEDIA_KEYS_SCHEMA,
+            "custom-keybindings",
+            &format!("['{TERMINAL}']"),
+        )
+        .with_value(&schema_at(TERMINAL), "name", "'Terminal'")
+        .with_value(&schema_at(TERMINAL), "command", "'kgx'")
+        .with_value(&schema_at(TERMINAL), "binding", "'<Super>Return'");
+
+    let shortcuts = shortcut_dump::collect(&GSettings);
+
+    let keys: Vec<&str> = shortcuts.iter().map(|s| s.key.as_str()).collect();
+    assert_eq!(
+        keys,
+        ["begin-move", "minimize", "toggle-tiled-left", "binding"]
+    );
+    assert!(shortcuts[0].accelerators.is_empty());
+    assert_eq!(shortcuts[1].accelerators, ["<Super>h"]);
+    assert_eq!(shortcuts[1].description.as_deref(), Some("Hide window"));
+    assert_eq!(shortcuts[3].description.as_deref(), Some("Terminal"));
+    assert_eq!(shortcuts[3].command.as_deref(), Some("kgx"));
+
+    let json = shortcut_dump::to_json(&shortcuts).unwrap();
+    assert!(
+        json.contains("\"schema\": \"org.gnome.mutter.keybindings\""),
+        "{json}"
+    );
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    gsettings::{GSettings, WM_KEYBINDINGS_SCHEMA, WM_PREFERENCES_SCHEMA},
+    transaction::Transaction,
+    undo::{Snapshot, UndoStack},
+};
+
+#[test]
+fn undo_and_redo_walk_through_several_applies() {
+    let fake = FakeGSettings::new()
+        .with_value(WM_PREFERENCES_SCHEMA, "num-workspaces", "4")
+        .with_value(
+            WM_KEYBINDINGS_SCHEMA,
+            "switch-to-workspace-1",
+            "['<Super>1']",
+        );
+    let mut stack = UndoStack::default();
+    for (title, key, value) in [
+        ("first", "num-workspaces", "6"),
+        ("second", "num-workspaces", "8"),
+    ] {
+        let mut transaction = Transaction::new();
+        transaction
+            .stage(&GSettings, WM_PREFERENCES_SCHEMA, key, value)
+            .unwrap();
+        transaction.apply(&GSettings).unwrap();
+        stack.push(title, transaction);
+    }
+    let count = || fake.value(WM_PREFERENCES_SCHEMA, "num-workspaces");
+
+    assert_eq!(stack.undo(&GSettings).unwrap().unwrap().title, "second");
+    assert_eq!(count().as_deref(), Some("6"));
+    stack.undo(&GSettings).unwrap();
+    assert_eq!(count().as_deref(), Some("4"));
+    assert!(stack.undo(&GSettings).unwrap().is_none());
+
+    assert_eq!(stack.redo(&GSettings).unwrap().unwrap().title, "first");
+    assert_eq!(count().as_deref(), Some("6"));
+
+    // a new write can't be followed by redoing the old second step
+    let keys = [(
+        WM_KEYBINDINGS_SCHEMA.to_string(),
+        "switch-to-workspace-1".to_string(),
+    )];
+    let snapshot = Snapshot::take(&GSettings, &keys);
+    GSettings::unbind(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1").unwrap();
+    stack.push("unbind", snapshot.changes(&GSettings));
+    assert!(stack.next_redo().is_none());
+
+    stack.undo(&GSettings).unwrap();
+    assert_eq!(
+        fake.value(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1")
+            .as_deref(),
+        Some("['<Super>1']")
+    );
+}
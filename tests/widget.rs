@@ -0,0 +1,48 @@
+#![cfg(feature = "widget")]
+
+use gnome_workspace_shortcuts_menu::{
+    accelerator::Modifiers,
+    gsettings::WM_KEYBINDINGS_SCHEMA,
+    keysym::Keysyms,
+    widget::{KeybindingRow, RowEvent, RowStatus},
+};
+
+#[test]
+fn extra_bindings_are_kept_and_edited() {
+    let keysyms = Keysyms::default();
+    let mut row = KeybindingRow::new(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1", "1");
+    row.load("['<Super>1', '<Primary><Alt>1']".into(), &keysyms);
+    assert_eq!(row.keybinding, "1");
+    assert!(!row.is_modified());
+
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    row.assign(shift, "1", &keysyms);
+    assert_eq!(row.converted_keybinding, "['<Shift>1', '<Ctrl><Alt>1']");
+
+    row.add_binding(&keysyms);
+    assert!(row.keybinding.is_empty());
+    assert!(!row.is_incomplete());
+    assert_eq!(row.converted_keybinding, "['<Shift>1', '<Ctrl><Alt>1']");
+
+    row.edit_extra(1, &keysyms);
+    assert_eq!(row.converted_keybinding, "['<Ctrl><Alt>1', '<Shift>1']");
+    row.remove_extra(0, &keysyms);
+    assert_eq!(row.converted_keybinding, "['<Ctrl><Alt>1']");
+}
+
+#[test]
+fn writes_are_taken_out_of_the_row() {
+    let mut row = KeybindingRow::new(WM_KEYBINDINGS_SCHEMA, "switch-to-workspace-1", "1");
+    row.load("['<Super>1']".into(), &Keysyms::default());
+    assert!(row.write_for(RowEvent::Changed).is_none());
+    let write = row.write_for(RowEvent::Overwrite).unwrap();
+    assert_eq!(write.value.as_deref(), Some("['<Super>1']"));
+    assert_eq!(row.write_for(RowEvent::Unbind).unwrap().value, None);
+
+    row.locked = true;
+    assert!(row.write_for(RowEvent::Overwrite).is_none());
+    assert!(matches!(row.status, RowStatus::Failed(_)));
+}
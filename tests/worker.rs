@@ -0,0 +1,40 @@
+use std::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use gnome_workspace_shortcuts_menu::worker::Worker;
+
+#[test]
+fn jobs_finish_in_the_order_they_were_queued() {
+    let mut worker = Worker::start(|| {});
+    assert!(!worker.is_busy());
+    for i in 0..3 {
+        worker.run(move || {
+            std::thread::sleep(Duration::from_millis(10 * (3 - i)));
+            i
+        });
+    }
+    assert!(worker.is_busy());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut results = vec![];
+    while worker.is_busy() && Instant::now() < deadline {
+        results.extend(worker.finished());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(results, vec![0, 1, 2]);
+}
+
+#[test]
+fn every_result_is_notified() {
+    let (sender, notified) = mpsc::channel();
+    let mut worker = Worker::start(move || sender.send(()).unwrap());
+    worker.run(|| 1);
+    worker.run(|| 2);
+    for _ in 0..2 {
+        notified.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+    assert_eq!(worker.finished(), vec![1, 2]);
+    assert!(!worker.is_busy());
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    gsettings::{GSettings, SHELL_SCHEMA},
+    workspace_layout::{Layout, WORKSPACE_MATRIX_SCHEMA},
+};
+
+#[test]
+fn workspaces_are_a_strip_without_a_grid_extension() {
+    let _fake = FakeGSettings::new().with_value(SHELL_SCHEMA, "enabled-extensions", "@as []");
+    assert_eq!(Layout::detect(&GSettings), Layout::Strip);
+    assert_eq!(Layout::Strip.shape(6), (1, 6));
+}
+
+#[test]
+fn grid_follows_the_workspace_matrix_columns() {
+    let _fake = FakeGSettings::new()
+        .with_value(
+            SHELL_SCHEMA,
+            "enabled-extensions",
+            "['wsmatrix@martin.zurowietz.de']",
+        )
+        .with_value(WORKSPACE_MATRIX_SCHEMA, "num-columns", "3");
+    let layout = Layout::detect(&GSettings);
+    assert_eq!(layout, Layout::Grid { columns: Some(3) });
+    assert_eq!(layout.shape(10), (4, 3));
+    assert_eq!(Layout::Grid { columns: None }.shape(9), (3, 3));
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use std::fs;
+
+use common::FakeGSettings;
+use gnome_workspace_shortcuts_menu::{
+    backup::{self, BACKUP_FILE},
+    history::{History, HISTORY_FILE},
+    xdg,
+};
+
+#[test]
+fn backups_and_history_live_outside_the_config_directory() {
+    let _fake = FakeGSettings::new();
+
+    backup::backup_value("org.gnome.mutter", "dynamic-workspaces", "true").unwrap();
+    History::default()
+        .record("org.gnome.mutter", "dynamic-workspaces", "false")
+        .unwrap();
+
+    assert!(xdg::data_dir().join(BACKUP_FILE).exists());
+    assert!(xdg::state_dir().join(HISTORY_FILE).exists());
+    assert!(!xdg::config_dir().exists());
+}
+
+#[test]
+fn legacy_files_move_unless_the_new_one_exists() {
+    let _fake = FakeGSettings::new();
+    fs::create_dir_all(xdg::config_dir()).unwrap();
+    fs::write(xdg::config_dir().join(BACKUP_FILE), "old backup\n").unwrap();
+    fs::write(xdg::config_dir().join(HISTORY_FILE), "old history").unwrap();
+    let history = xdg::create_file_path(xdg::state_dir(), HISTORY_FILE).unwrap();
+    fs::write(&history, "new history").unwrap();
+
+    xdg::migrate_legacy_files(&[
+        (BACKUP_FILE, backup::backup_file()),
+        (HISTORY_FILE, history.clone()),
+    ]);
+
+    assert_eq!(
+        fs::read_to_string(backup::backup_file()).unwrap(),
+        "old backup\n"
+    );
+    assert_eq!(fs::read_to_string(&history).unwrap(), "new history");
+    assert!(xdg::config_dir().join(HISTORY_FILE).exists());
+}